@@ -1,5 +1,5 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use hexout::HexOutSettings;
+use hexout::{ConstHexOutSettings, HexOutSettings};
 
 fn criterion_benchmark(c: &mut Criterion) {
     let size = 1024 * 1024;
@@ -11,6 +11,8 @@ fn criterion_benchmark(c: &mut Criterion) {
     }
     let settings: HexOutSettings = HexOutSettings::default();
     c.bench_function("hex_out 1M bytes", |b| b.iter(|| hexout::hex_out(&data, &settings, 0, 0, 0)));
+    let settings: ConstHexOutSettings<1, 16> = ConstHexOutSettings::new();
+    c.bench_function("const hex_out 1M bytes", |b| b.iter(|| settings.hex_out(&data)));
 }
 
 criterion_group!(benches, criterion_benchmark);
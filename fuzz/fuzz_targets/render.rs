@@ -0,0 +1,12 @@
+#![no_main]
+
+use hexout::{hex_out, HexOutError, HexOutSettings};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (HexOutSettings, Vec<u8>, u8, u8, u8)| {
+    let (settings, data, offset, start_line, line_count) = input;
+    match hex_out(&data, &settings, offset.into(), start_line.into(), line_count.into()) {
+        Err(HexOutError::UnalignedOffset { .. }) => assert!(settings.strict),
+        result => assert!(result.is_ok()),
+    }
+});
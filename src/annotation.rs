@@ -0,0 +1,123 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{hex_out, HexOutError, HexOutSettings, LineAnnotator};
+
+/// A labelled range of bytes overlaid on a dump, e.g. a decoded field of a binary structure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    /// Range of data indices covered by the annotation.
+    pub range: Range<usize>,
+    /// Name of the annotated range (e.g. `header.len`).
+    pub label: String,
+    /// Optional decoded value shown next to the label.
+    pub value: Option<String>,
+}
+
+impl Annotation {
+    /// Create an annotation without a value.
+    pub fn new(range: Range<usize>, label: impl Into<String>) -> Self {
+        Self {
+            range,
+            label: label.into(),
+            value: None,
+        }
+    }
+
+    /// Attach a decoded value to the annotation.
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+}
+
+/// Annotations sorted for looking up those covering a byte, e.g. to show "inside `header.len`" when hovering over a
+/// byte in a viewer, without scanning all of them on each move of the pointer.
+///
+/// Lookups take logarithmic time plus the number of annotations overlapping the byte's start.
+/// ```rust
+/// use hexout::{Annotation, AnnotationIndex, Layout};
+/// let mut annotations = Layout::new().u8("header.kind").u16_be("header.len").apply(&[1, 0, 3]);
+/// annotations.push(Annotation::new(0..3, "header"));
+/// let index = AnnotationIndex::new(annotations);
+/// let labels = index.annotations_at(2).map(|annotation| annotation.label.as_str()).collect::<Vec<_>>();
+/// assert_eq!(labels, ["header", "header.len"]);
+/// assert_eq!(index.annotations_at(3).count(), 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnnotationIndex {
+    /// The annotations by start, longest first.
+    annotations: Vec<Annotation>,
+    /// The largest end of the annotations up to each one.
+    max_ends: Vec<usize>,
+}
+
+impl AnnotationIndex {
+    /// Index `annotations`; empty ranges are kept but never found.
+    pub fn new(annotations: impl Into<Vec<Annotation>>) -> Self {
+        let mut annotations = annotations.into();
+        annotations.sort_by(|a, b| a.range.start.cmp(&b.range.start).then(b.range.end.cmp(&a.range.end)));
+        let max_ends = annotations
+            .iter()
+            .scan(0, |max_end, annotation| {
+                *max_end = annotation.range.end.max(*max_end);
+                Some(*max_end)
+            })
+            .collect();
+        Self { annotations, max_ends }
+    }
+
+    /// The annotations covering the byte at data index `offset`, from the outermost to the innermost: by start, the
+    /// longest first among those starting at the same byte.
+    pub fn annotations_at(&self, offset: usize) -> impl Iterator<Item = &Annotation> {
+        let end = self.annotations.partition_point(|annotation| annotation.range.start <= offset);
+        // Annotations before the first one reaching past the offset all end before it
+        let start = self.max_ends[..end].partition_point(|max_end| *max_end <= offset);
+        self.annotations[start..end].iter().filter(move |annotation| annotation.range.contains(&offset))
+    }
+
+    /// All annotations, in the order of [`annotations_at`](Self::annotations_at).
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+}
+
+/// Generate a hex dump with annotations overlaid.
+///
+/// Every other annotation is highlighted with `highlight_style` so neighbouring ranges can be told apart, and each
+/// line lists the annotations starting on it (as `label=value`) after the ASCII column, following the note of the
+/// `line_annotator` if one is set.
+///
+/// All other parameters are the same as for [`hex_out`].
+pub fn hex_out_annotated(
+    data: &[u8],
+    annotations: &[Annotation],
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<String, HexOutError> {
+    let mut settings = settings.clone();
+    settings.highlights.extend(annotations.iter().step_by(2).map(|annotation| annotation.range.clone()));
+    let annotations: Arc<[Annotation]> = annotations.into();
+    let line_annotator = settings.line_annotator.take();
+    settings.line_annotator = Some(LineAnnotator::new(move |line_offset, line_bytes| {
+        let line = line_offset..line_offset + line_bytes.len();
+        let mut note = line_annotator.as_ref().and_then(|annotator| annotator.annotate(line_offset, line_bytes));
+        for annotation in annotations.iter().filter(|annotation| line.contains(&annotation.range.start)) {
+            let note = match &mut note {
+                Some(note) => {
+                    note.push_str(if note.is_empty() { "" } else { ", " });
+                    note
+                }
+                None => note.insert(String::new()),
+            };
+            note.push_str(&annotation.label);
+            if let Some(value) = &annotation.value {
+                note.push('=');
+                note.push_str(value);
+            }
+        }
+        note
+    }));
+    hex_out(data, &settings, offset, start_line, line_count)
+}
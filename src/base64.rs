@@ -0,0 +1,21 @@
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Number of characters taken by `length` bytes in base64, including the padding.
+pub(crate) fn base64_width(length: usize) -> usize {
+    length.div_ceil(3) * 4
+}
+
+/// Append `bytes` in base64 (RFC 4648, padded with `=`) to `buffer`.
+pub(crate) fn push_base64(buffer: &mut String, bytes: &[u8]) {
+    for chunk in bytes.chunks(3) {
+        let value =
+            chunk.iter().enumerate().fold(0u32, |value, (index, byte)| value | (*byte as u32) << (16 - 8 * index));
+        for index in 0..4 {
+            if index <= chunk.len() {
+                buffer.push(ALPHABET[(value >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                buffer.push('=');
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{guessed_endianness, hex_out, validate, Endianness, HexOutError, HexOutSettings, LineAnnotator};
+
+/// A named range of bits within a register, e.g. an enable flag or a mode selector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitField {
+    /// Name of the field (e.g. `EN`).
+    pub name: String,
+    /// Bits covered by the field, bit 0 being the least significant bit of the group value.
+    pub bits: Range<u32>,
+}
+
+/// A group of a dump described as a register made of bit fields, see [`hex_out_registers`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Register {
+    /// Data index of the first byte of the group holding the register.
+    pub offset: usize,
+    /// Name of the register (e.g. `CTRL`).
+    pub name: String,
+    /// Bit fields of the register, listed in this order.
+    pub fields: Vec<BitField>,
+}
+
+impl Register {
+    /// Create a register without fields.
+    pub fn new(offset: usize, name: impl Into<String>) -> Self {
+        Self {
+            offset,
+            name: name.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Add a field covering `bits`.
+    pub fn field(mut self, name: impl Into<String>, bits: Range<u32>) -> Self {
+        self.fields.push(BitField { name: name.into(), bits });
+        self
+    }
+
+    /// Add a single-bit flag.
+    pub fn flag(self, name: impl Into<String>, bit: u32) -> Self {
+        self.field(name, bit..bit + 1)
+    }
+
+    /// Decode `value` into a legend such as `CTRL: EN MODE=2`: flags are listed by name when set, wider fields as
+    /// `name=value`.
+    fn legend(&self, value: u128) -> String {
+        let mut legend = format!("{}:", self.name);
+        let mut empty = true;
+        for field in &self.fields {
+            let width = field.bits.end - field.bits.start;
+            let mask = if width >= u128::BITS { u128::MAX } else { (1 << width) - 1 };
+            let bits = (value >> field.bits.start) & mask;
+            if width == 1 && bits == 0 {
+                continue;
+            }
+            legend.push(' ');
+            legend.push_str(&field.name);
+            if width != 1 {
+                legend.push('=');
+                legend.push_str(&bits.to_string());
+            }
+            empty = false;
+        }
+        if empty {
+            legend.push_str(" -");
+        }
+        legend
+    }
+}
+
+/// Generate a hex dump of peripheral registers with their bit fields decoded.
+///
+/// Each register is read from the group at its offset, in the byte order of that group (see `endianness` and
+/// `group_endianness`), and each line lists the legends of the registers starting on it after the ASCII column,
+/// following the note of the `line_annotator` if one is set.  Registers extending past the end of the data are not
+/// decoded.  Fields that are empty or extend past the bits of a group are rejected with
+/// [`HexOutError::InvalidBitField`].
+///
+/// All other parameters are the same as for [`hex_out`].
+/// ```rust
+/// use hexout::{hex_out_registers, HexOutSettings, Register};
+/// let registers = [Register::new(0, "CTRL").flag("EN", 0).flag("IRQ", 1).field("MODE", 4..6)];
+/// let settings = HexOutSettings { group_size: 4, groups_per_line: 2, show_ascii: false, show_centerline: false, ..Default::default() };
+/// let dump = hex_out_registers(&[0x21, 0, 0, 0, 0, 0, 0, 0], &registers, &settings, 0, 0, 0).unwrap();
+/// assert_eq!(dump, "00000000: 00000021 00000000 CTRL: EN MODE=2");
+/// ```
+pub fn hex_out_registers(
+    data: &[u8],
+    registers: &[Register],
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<String, HexOutError> {
+    validate(settings, offset)?;
+    let group_size = settings.group_size;
+    let group_bits = group_size as u32 * 8;
+    for register in registers {
+        if let Some(field) = register.fields.iter().find(|field| field.bits.is_empty() || field.bits.end > group_bits) {
+            return Err(HexOutError::InvalidBitField { register: register.name.clone(), field: field.name.clone() });
+        }
+    }
+    let mut settings = settings.clone();
+    let total_bytes_per_line = group_size * settings.groups_per_line;
+    let grid_start = if settings.align_address { 0 } else { offset };
+    let guessed = guessed_endianness(data.into(), &settings, offset);
+    let legends: Arc<[(usize, String)]> = registers
+        .iter()
+        .filter_map(|register| {
+            let bytes = data.get(register.offset..register.offset.checked_add(group_size)?)?;
+            let group_index = register.offset.saturating_sub(grid_start) % total_bytes_per_line / group_size;
+            let endianness = match settings.group_endianness.get(group_index).copied().unwrap_or(settings.endianness) {
+                Endianness::Auto => guessed,
+                endianness => endianness,
+            };
+            Some((register.offset, register.legend(endianness.read(bytes))))
+        })
+        .collect();
+    let line_annotator = settings.line_annotator.take();
+    settings.line_annotator = Some(LineAnnotator::new(move |line_offset, line_bytes| {
+        let line = line_offset..line_offset + line_bytes.len();
+        let mut note = line_annotator.as_ref().and_then(|annotator| annotator.annotate(line_offset, line_bytes));
+        for (_, legend) in legends.iter().filter(|(offset, _)| line.contains(offset)) {
+            let note = match &mut note {
+                Some(note) => {
+                    note.push_str(if note.is_empty() { "" } else { ", " });
+                    note
+                }
+                None => note.insert(String::new()),
+            };
+            note.push_str(legend);
+        }
+        note
+    }));
+    hex_out(data, &settings, offset, start_line, line_count)
+}
@@ -0,0 +1,74 @@
+use std::fmt::Write;
+
+use crate::{validate, HexOutError, HexOutSettings};
+
+/// Generate a dump of the bits of `data` in groups of `bit_group_size` bits, starting at bit `bit_offset`, for data
+/// that isn't aligned to bytes such as compressed streams or radio captures.
+///
+/// Bits are counted from the most significant bit of the first byte, and each line starts with the address of its
+/// first bit as `byte.bit`, the byte in hex (including `address_origin`) and the bit in decimal.  Bits past the end
+/// of the data in the last group are shown as `invalid_data_placeholder`.  Of the settings, only `address_origin`,
+/// `address_width`, `show_offset`, `groups_per_line` and `show_centerline` apply.  `start_line` and `line_count`
+/// select a window of lines as for [`hex_out`](crate::hex_out).
+///
+/// Bit group sizes outside 1-128 are rejected with [`HexOutError::InvalidBitGroupSize`].
+/// ```rust
+/// use hexout::{bit_dump, HexOutSettings};
+/// let settings = HexOutSettings { groups_per_line: 4, show_centerline: false, ..Default::default() };
+/// let dump = bit_dump(&[0b1011_0011, 0b1000_1111], &settings, 3, 5, 0, 0).unwrap();
+/// assert_eq!(dump, "00000000.3: 10011 10001 111??");
+/// ```
+pub fn bit_dump(
+    data: &[u8],
+    settings: &HexOutSettings,
+    bit_offset: usize,
+    bit_group_size: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<String, HexOutError> {
+    validate(settings, 0)?;
+    if bit_group_size == 0 || bit_group_size > 128 {
+        return Err(HexOutError::InvalidBitGroupSize);
+    }
+    let total_bits = data.len().checked_mul(8).ok_or(HexOutError::RangeOverflow)?;
+    let groups_per_line = settings.groups_per_line;
+    let bits_per_line = bit_group_size.checked_mul(groups_per_line).ok_or(HexOutError::RangeOverflow)?;
+    let mut line_start = start_line
+        .checked_mul(bits_per_line)
+        .and_then(|skipped| skipped.checked_add(bit_offset))
+        .ok_or(HexOutError::RangeOverflow)?;
+    let mut result = String::new();
+    let mut lines = 0;
+    while line_start < total_bits && (line_count == 0 || lines < line_count) {
+        if lines > 0 {
+            result.push('\n');
+        }
+        if settings.show_offset {
+            let address =
+                ((line_start / 8) as u128).checked_add(settings.address_origin).ok_or(HexOutError::RangeOverflow)?;
+            let _ = write!(result, "{address:0width$x}.{}: ", line_start % 8, width = settings.address_width);
+        }
+        for group in 0..groups_per_line {
+            let group_start = line_start + group * bit_group_size;
+            if group_start >= total_bits {
+                break;
+            }
+            if group > 0 {
+                result.push(' ');
+            }
+            if group > 0 && group == groups_per_line / 2 && settings.show_centerline {
+                result.push(' ');
+            }
+            for bit in group_start..group_start + bit_group_size {
+                match data.get(bit / 8) {
+                    Some(byte) if (byte >> (7 - bit % 8)) & 1 == 1 => result.push('1'),
+                    Some(_) => result.push('0'),
+                    None => result.push(settings.invalid_data_placeholder),
+                }
+            }
+        }
+        line_start = line_start.saturating_add(bits_per_line);
+        lines += 1;
+    }
+    Ok(result)
+}
@@ -0,0 +1,103 @@
+use std::fmt::Write;
+
+use crate::{format_version, Annotation};
+
+/// Name of the format of [`bookmarks_json_tagged`].
+const BOOKMARKS_FORMAT: &str = "hexout-bookmarks";
+
+/// The annotations as a JSON bookmark list for hex editors, an array of objects with the offset and length of each
+/// annotated range and its label, e.g. `[{"offset": 4, "length": 2, "label": "version"}]`.
+///
+/// Offsets are data indices, so they are file offsets when the whole file was dumped.
+/// ```rust
+/// use hexout::{bookmarks_json, Annotation};
+/// let annotations = [Annotation::new(0..4, "magic"), Annotation::new(4..6, "version").with_value("2")];
+/// assert_eq!(
+///     bookmarks_json(&annotations),
+///     "[\n  {\"offset\": 0, \"length\": 4, \"label\": \"magic\"},\n  \
+///      {\"offset\": 4, \"length\": 2, \"label\": \"version\"}\n]"
+/// );
+/// ```
+pub fn bookmarks_json(annotations: &[Annotation]) -> String {
+    let mut json = String::new();
+    push_bookmarks_json(&mut json, annotations, "");
+    json
+}
+
+/// The annotations as a JSON bookmark list as written by [`bookmarks_json`], tagged with the name and
+/// [version](crate::format_version) of the format, so parsers can detect changes to it, e.g.
+/// `{"format": "hexout-bookmarks", "version": "1.0.0", "bookmarks": [...]}`.
+/// ```rust
+/// use hexout::{bookmarks_json_tagged, Annotation};
+/// assert_eq!(
+///     bookmarks_json_tagged(&[Annotation::new(0..4, "magic")]),
+///     "{\n  \"format\": \"hexout-bookmarks\",\n  \"version\": \"1.0.0\",\n  \"bookmarks\": [\n    \
+///      {\"offset\": 0, \"length\": 4, \"label\": \"magic\"}\n  ]\n}"
+/// );
+/// ```
+pub fn bookmarks_json_tagged(annotations: &[Annotation]) -> String {
+    let mut json = format!(
+        "{{\n  \"format\": \"{BOOKMARKS_FORMAT}\",\n  \"version\": \"{}\",\n  \"bookmarks\": ",
+        format_version()
+    );
+    push_bookmarks_json(&mut json, annotations, "  ");
+    json.push_str("\n}");
+    json
+}
+
+/// Append the JSON array of bookmarks to `json`, with its lines after the first indented by `indent`.
+fn push_bookmarks_json(json: &mut String, annotations: &[Annotation], indent: &str) {
+    if annotations.is_empty() {
+        json.push_str("[]");
+        return;
+    }
+    json.push_str("[\n");
+    for (index, annotation) in annotations.iter().enumerate() {
+        let (offset, length) = (annotation.range.start, annotation.range.len());
+        let _ = write!(json, "{indent}  {{\"offset\": {offset}, \"length\": {length}, \"label\": \"");
+        push_json_escaped(json, &annotation.label);
+        json.push_str(if index + 1 < annotations.len() { "\"},\n" } else { "\"}\n" });
+    }
+    json.push_str(indent);
+    json.push(']');
+}
+
+/// The annotations as a CSV bookmark list for hex editors, with an `offset,length,label` header and one row per
+/// annotated range.  Labels holding commas, quotes or line breaks are quoted.
+///
+/// Offsets are data indices, as for [`bookmarks_json`].
+/// ```rust
+/// use hexout::{bookmarks_csv, Annotation};
+/// let annotations = [Annotation::new(0..4, "magic"), Annotation::new(4..6, "version, minor")];
+/// assert_eq!(bookmarks_csv(&annotations), "offset,length,label\n0,4,magic\n4,2,\"version, minor\"");
+/// ```
+pub fn bookmarks_csv(annotations: &[Annotation]) -> String {
+    let mut csv = String::from("offset,length,label");
+    for annotation in annotations {
+        let _ = write!(csv, "\n{},{},", annotation.range.start, annotation.range.len());
+        let label = &annotation.label;
+        if label.contains([',', '"', '\n', '\r']) {
+            let _ = write!(csv, "\"{}\"", label.replace('"', "\"\""));
+        } else {
+            csv.push_str(label);
+        }
+    }
+    csv
+}
+
+/// Append `text` to `buffer`, escaped for a JSON string.
+fn push_json_escaped(buffer: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(buffer, "\\u{:04x}", c as u32);
+            }
+            c => buffer.push(c),
+        }
+    }
+}
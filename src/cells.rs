@@ -0,0 +1,204 @@
+use crate::{geometry::column_spans, Column, DumpLines, HexOutError, HexOutSettings, RenderContext, Style};
+
+/// Semantic class of a [`HexCell`], after the style the character would be rendered with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexCellClass {
+    /// Unstyled characters: valid bytes, separators, borders, captions, headers and notes.
+    Plain,
+    /// A line address (see `address_style`).
+    Address,
+    /// The `invalid_data_placeholder` of a byte before the offset or past the end of the data in a group with valid
+    /// bytes (see `error_style`).
+    Missing,
+    /// A byte on a striped line or group (see `stripe_style`).
+    Striped,
+    /// A highlighted byte (see `highlight_style`).
+    Highlighted,
+    /// A selected byte (see `selection_style`).
+    Selected,
+    /// The byte under the cursor (see `cursor_style`).
+    Cursor,
+}
+
+/// Styled classes, each starting its runs of cells with the private use character at [`FIRST_MARKER`] plus its index.
+const CLASSES: [HexCellClass; 6] = [
+    HexCellClass::Address,
+    HexCellClass::Missing,
+    HexCellClass::Striped,
+    HexCellClass::Highlighted,
+    HexCellClass::Selected,
+    HexCellClass::Cursor,
+];
+
+const FIRST_MARKER: u32 = 0xf8f0;
+
+/// Private use character ending a styled run of cells.
+const END_MARKER: char = '\u{f8ff}';
+
+/// A character of a dump with its semantic class, see [`hex_out_cells`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexCell {
+    /// The character as it appears in the plain dump.
+    pub ch: char,
+    /// The class of the character, independent of the styles configured.
+    pub class: HexCellClass,
+    /// The fixed width column the character is in on a line of data (the ASCII column without `ascii_open` and
+    /// `ascii_close`), `None` for separators, borders, variable width columns and lines that aren't data.
+    pub column: Option<Column>,
+}
+
+/// Generate a hex dump as a matrix of cells, one row per line of [`hex_out`](crate::hex_out) and one cell per
+/// character, each with its semantic class in place of its style.
+///
+/// The rows are the same whatever the styles configured, so GUI and golden tests can compare them structurally: the
+/// characters of a row make up the line of the unstyled dump, and the classes tell which bytes are highlighted,
+/// selected and so on.  Escape sequences in `ascii_open` and `ascii_close` are left out.
+///
+/// All parameters are the same as for [`hex_out`](crate::hex_out).
+/// ```rust
+/// use hexout::{hex_out_cells, HexCellClass, HexOutSettings};
+/// let settings = HexOutSettings { cursor: Some(1), show_ascii: false, address_width: 4, ..Default::default() };
+/// let rows = hex_out_cells(b"ab", &settings, 0, 0, 0).unwrap();
+/// let cursor = rows[0].iter().filter(|cell| cell.class == HexCellClass::Cursor);
+/// assert_eq!(cursor.map(|cell| cell.ch).collect::<String>(), "62");
+/// ```
+pub fn hex_out_cells(
+    data: &[u8],
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<Vec<Vec<HexCell>>, HexOutError> {
+    let mut rows = Vec::new();
+    hex_out_segments(data, settings, offset, start_line, line_count, |segments| {
+        let cells = segments.iter().flat_map(|segment| {
+            segment.text.chars().map(|ch| HexCell { ch, class: segment.class, column: segment.column })
+        });
+        rows.push(cells.collect());
+    })?;
+    Ok(rows)
+}
+
+/// Indices of the rows that differ between two renders of the same lines, e.g. the rows of [`hex_out_cells`] before
+/// and after the data changed, so a TUI repaints only those.
+///
+/// Rows are compared by index; rows present in only one of the renders count as changed, so indices at or past the
+/// length of `new` are rows to clear.  Any rows comparable for equality will do, e.g. the lines of
+/// [`hex_out_vec`](crate::hex_out_vec).
+/// ```rust
+/// use hexout::{changed_rows, hex_out_cells, HexOutSettings};
+/// let settings = HexOutSettings { groups_per_line: 4, ..Default::default() };
+/// let mut data = vec![0u8; 12];
+/// let old = hex_out_cells(&data, &settings, 0, 0, 0).unwrap();
+/// data[5] = 0xff;
+/// let new = hex_out_cells(&data, &settings, 0, 0, 0).unwrap();
+/// assert_eq!(changed_rows(&old, &new), [1]);
+/// ```
+pub fn changed_rows<T: PartialEq>(old: &[T], new: &[T]) -> Vec<usize> {
+    (0..old.len().max(new.len())).filter(|index| old.get(*index) != new.get(*index)).collect()
+}
+
+/// A run of characters of a line of a dump sharing their semantic class and column, see [`hex_out_segments`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexSegment<'a> {
+    /// The characters as they appear in the plain dump.
+    pub text: &'a str,
+    /// The class of the characters, as for [`HexCell::class`].
+    pub class: HexCellClass,
+    /// The column of the characters, as for [`HexCell::column`].
+    pub column: Option<Column>,
+}
+
+/// Generate a hex dump as runs of characters sharing their semantic class and column, passing the segments of each
+/// line to `emit` (an empty slice for an empty line).
+///
+/// The segments are those of [`hex_out_cells`] merged into runs, and borrow their text from the line as rendered, so
+/// UIs can draw each run with a style of their own, and writers write them out, without a styled string to assemble
+/// and parse again.
+///
+/// All other parameters are the same as for [`hex_out`](crate::hex_out).
+/// ```rust
+/// use hexout::{hex_out_segments, Column, HexCellClass, HexOutSettings};
+/// let settings = HexOutSettings { selection: Some(1..2), show_ascii: false, address_width: 4, ..Default::default() };
+/// let mut lines = Vec::new();
+/// hex_out_segments(b"abc", &settings, 0, 0, 0, |segments| {
+///     lines.push(segments.iter().map(|segment| (segment.text.to_string(), segment.class)).collect::<Vec<_>>());
+/// })
+/// .unwrap();
+/// assert_eq!(
+///     lines[0],
+///     [
+///         ("0000".to_string(), HexCellClass::Address),
+///         (": ".to_string(), HexCellClass::Plain),
+///         ("61 ".to_string(), HexCellClass::Plain),
+///         ("62".to_string(), HexCellClass::Selected),
+///         (" 63".to_string(), HexCellClass::Plain),
+///     ]
+/// );
+/// ```
+pub fn hex_out_segments(
+    data: &[u8],
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+    mut emit: impl FnMut(&[HexSegment]),
+) -> Result<(), HexOutError> {
+    let style = |class| Some(Style::new(marker(class), END_MARKER));
+    let settings = HexOutSettings {
+        address_style: style(HexCellClass::Address),
+        error_style: style(HexCellClass::Missing),
+        stripe_style: settings.stripe_style.as_ref().and(style(HexCellClass::Striped)),
+        highlight_style: style(HexCellClass::Highlighted),
+        selection_style: style(HexCellClass::Selected),
+        cursor_style: style(HexCellClass::Cursor),
+        ..settings.without_styles()
+    };
+    let spans = column_spans(&settings).0;
+    let mut lines = DumpLines::new(data.into(), RenderContext::default(), &settings, offset, start_line, line_count)?;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if !lines.next_line(&mut line) {
+            break;
+        }
+        let data_line = lines.current_line.is_some();
+        let column_at =
+            |position| spans.iter().find(|(_, span)| data_line && span.contains(&position)).map(|(column, _)| *column);
+        let mut segments = Vec::new();
+        let mut class = HexCellClass::Plain;
+        // Byte index of the start of the run and character position of the next character in the plain line
+        let mut start = 0;
+        let mut position = 0;
+        for (index, ch) in line.char_indices() {
+            let marked = (ch as u32).checked_sub(FIRST_MARKER).and_then(|index| CLASSES.get(index as usize));
+            if ch == END_MARKER || marked.is_some() {
+                push_segment(&mut segments, &line[start..index], class, column_at(position.max(1) - 1));
+                class = marked.copied().unwrap_or(HexCellClass::Plain);
+                start = index + ch.len_utf8();
+                continue;
+            }
+            if index > start && column_at(position) != column_at(position - 1) {
+                push_segment(&mut segments, &line[start..index], class, column_at(position - 1));
+                start = index;
+            }
+            position += 1;
+        }
+        push_segment(&mut segments, &line[start..], class, column_at(position.max(1) - 1));
+        emit(&segments);
+    }
+    Ok(())
+}
+
+/// Append a segment of `text` to `segments` unless it is empty.
+fn push_segment<'a>(segments: &mut Vec<HexSegment<'a>>, text: &'a str, class: HexCellClass, column: Option<Column>) {
+    if !text.is_empty() {
+        segments.push(HexSegment { text, class, column });
+    }
+}
+
+/// Private use character starting a run of cells of `class`.
+fn marker(class: HexCellClass) -> char {
+    let index = CLASSES.iter().position(|marked| *marked == class).unwrap_or_default();
+    char::from_u32(FIRST_MARKER + index as u32).unwrap_or(END_MARKER)
+}
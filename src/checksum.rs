@@ -0,0 +1,143 @@
+use std::ops::Range;
+
+use crate::{Annotation, Endianness};
+
+/// Algorithm computing a checksum field, see [`ChecksumField`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumAlgorithm {
+    /// 8-bit sum of the bytes, as in [`Column::Checksum`](crate::Column::Checksum).
+    Sum8,
+    /// 8-bit XOR of the bytes, as in NMEA sentences.
+    Xor8,
+    /// CRC-16/CCITT-FALSE (polynomial 0x1021, initial value 0xffff), as in many serial protocols.
+    Crc16,
+    /// CRC-32 (ISO-HDLC), as in zip, PNG and Ethernet.
+    Crc32,
+    /// Adler-32, as in zlib.
+    Adler32,
+}
+
+impl ChecksumAlgorithm {
+    /// Number of bytes of the checksum.
+    pub fn size(self) -> usize {
+        match self {
+            ChecksumAlgorithm::Sum8 | ChecksumAlgorithm::Xor8 => 1,
+            ChecksumAlgorithm::Crc16 => 2,
+            ChecksumAlgorithm::Crc32 | ChecksumAlgorithm::Adler32 => 4,
+        }
+    }
+
+    /// The checksum of `bytes`.
+    /// ```rust
+    /// use hexout::ChecksumAlgorithm;
+    /// assert_eq!(ChecksumAlgorithm::Crc16.compute(b"123456789"), 0x29b1);
+    /// assert_eq!(ChecksumAlgorithm::Crc32.compute(b"123456789"), 0xcbf43926);
+    /// ```
+    pub fn compute(self, bytes: &[u8]) -> u32 {
+        match self {
+            ChecksumAlgorithm::Sum8 => bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)).into(),
+            ChecksumAlgorithm::Xor8 => bytes.iter().fold(0u8, |sum, byte| sum ^ byte).into(),
+            ChecksumAlgorithm::Crc16 => bytes
+                .iter()
+                .fold(0xffffu16, |crc, byte| {
+                    (0..8).fold(crc ^ (u16::from(*byte) << 8), |crc, _| match crc & 0x8000 {
+                        0 => crc << 1,
+                        _ => (crc << 1) ^ 0x1021,
+                    })
+                })
+                .into(),
+            ChecksumAlgorithm::Crc32 => !bytes.iter().fold(!0u32, |crc, byte| {
+                (0..8).fold(crc ^ u32::from(*byte), |crc, _| match crc & 1 {
+                    0 => crc >> 1,
+                    _ => (crc >> 1) ^ 0xedb8_8320,
+                })
+            }),
+            ChecksumAlgorithm::Adler32 => {
+                let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), byte| {
+                    let a = (a + u32::from(*byte)) % 65521;
+                    (a, (b + a) % 65521)
+                });
+                b << 16 | a
+            }
+        }
+    }
+}
+
+/// A checksum stored in the data, to be verified against the bytes it covers, see [`verify_checksums`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumField {
+    /// Name of the field (e.g. `crc`).
+    pub label: String,
+    /// Data index of the first byte of the stored checksum.
+    pub offset: usize,
+    /// Algorithm computing the checksum.
+    pub algorithm: ChecksumAlgorithm,
+    /// Range of data indices the checksum is computed over.
+    pub covered: Range<usize>,
+    /// Byte order of the stored checksum (default is big-endian, as in most protocols and file formats).
+    pub endianness: Endianness,
+}
+
+impl ChecksumField {
+    /// Create a big-endian checksum field.
+    pub fn new(label: impl Into<String>, offset: usize, algorithm: ChecksumAlgorithm, covered: Range<usize>) -> Self {
+        Self {
+            label: label.into(),
+            offset,
+            algorithm,
+            covered,
+            endianness: Endianness::Big,
+        }
+    }
+
+    /// Set the byte order of the stored checksum.
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    /// Range of data indices of the stored checksum.
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset.saturating_add(self.algorithm.size())
+    }
+
+    /// The stored and the computed checksum, or `None` if the field or the bytes it covers reach past the end of
+    /// `data`.
+    pub fn verify(&self, data: &[u8]) -> Option<(u32, u32)> {
+        let stored = self.endianness.read(data.get(self.range())?) as u32;
+        Some((stored, self.algorithm.compute(data.get(self.covered.clone())?)))
+    }
+}
+
+/// Verify the checksum fields of `data`, returning an annotation for each, to render with
+/// [`hex_out_annotated`](crate::hex_out_annotated) as an integrity report.
+///
+/// Each annotation covers the stored checksum and reads `OK` if it matches the checksum computed over the covered
+/// bytes, or `BAD` with the computed checksum otherwise.  Fields reaching past the end of the data are left out.  The
+/// annotations can be combined with those of a [`Layout`](crate::Layout), keeping them sorted by their range.
+/// ```rust
+/// use hexout::{hex_out_annotated, verify_checksums, ChecksumAlgorithm, ChecksumField, HexOutSettings};
+/// let fields = [
+///     ChecksumField::new("sum", 4, ChecksumAlgorithm::Sum8, 0..4),
+///     ChecksumField::new("xor", 5, ChecksumAlgorithm::Xor8, 0..4),
+/// ];
+/// let annotations = verify_checksums(&[1, 2, 3, 4, 0x0a, 0x05], &fields);
+/// assert_eq!(annotations[0].value.as_deref(), Some("OK"));
+/// assert_eq!(annotations[1].value.as_deref(), Some("BAD (computed 0x04)"));
+/// let settings = HexOutSettings { groups_per_line: 6, show_ascii: false, ..Default::default() };
+/// let dump = hex_out_annotated(&[1, 2, 3, 4, 0x0a, 0x05], &annotations, &settings, 0, 0, 0).unwrap();
+/// assert_eq!(dump, "00000000: 01 02 03  04 0a 05 sum=OK, xor=BAD (computed 0x04)");
+/// ```
+pub fn verify_checksums(data: &[u8], fields: &[ChecksumField]) -> Vec<Annotation> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let (stored, computed) = field.verify(data)?;
+            let value = match stored == computed {
+                true => "OK".to_string(),
+                false => format!("BAD (computed {computed:#0width$x})", width = field.algorithm.size() * 2 + 2),
+            };
+            Some(Annotation::new(field.range(), field.label.clone()).with_value(value))
+        })
+        .collect()
+}
@@ -0,0 +1,136 @@
+use std::ops::{Range, RangeInclusive};
+
+use crate::{guess_endianness, Endianness, HexOutSettings, LineAnnotator};
+
+/// What the value of a group most likely is, as guessed by a [`GroupClassifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GroupKind {
+    /// An address within one of the pointer ranges.
+    Pointer,
+    /// A run of printable ASCII characters.
+    Text,
+    /// An integer up to the small integer limit, including 0.
+    SmallInteger,
+    /// A 4- or 8-byte IEEE 754 float of a plausible magnitude.
+    Float,
+    /// None of the above.
+    Unknown,
+}
+
+impl GroupKind {
+    /// The glyph shown for the kind by [`GroupClassifier::annotator`]: `*` for pointers, `a` for text, `i` for small
+    /// integers, `f` for floats and `.` otherwise.
+    pub fn glyph(self) -> char {
+        match self {
+            GroupKind::Pointer => '*',
+            GroupKind::Text => 'a',
+            GroupKind::SmallInteger => 'i',
+            GroupKind::Float => 'f',
+            GroupKind::Unknown => '.',
+        }
+    }
+}
+
+/// Guesses what the groups of a dump might be, as the "telescope" views of debuggers do: pointers into the address
+/// ranges of a process, text, small integers or floats.
+///
+/// The kinds are tried in that order, and the first to fit the group's value wins.
+/// ```rust
+/// use hexout::{Endianness, GroupClassifier, GroupKind};
+/// let classifier = GroupClassifier { pointer_ranges: vec![0x40_0000..0x50_0000], ..Default::default() };
+/// let kinds = [0x40_1234u32, u32::from_le_bytes(*b"text"), 42, 1.5f32.to_bits(), 0xdead_beef]
+///     .map(|value| classifier.classify(&value.to_le_bytes(), Endianness::Little));
+/// use GroupKind::*;
+/// assert_eq!(kinds, [Pointer, Text, SmallInteger, Float, Unknown]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupClassifier {
+    /// Address ranges whose values are taken as pointers, e.g. the mappings of a process (default is none).
+    pub pointer_ranges: Vec<Range<u128>>,
+    /// Largest value taken as a small integer (default is 0xffff).
+    pub small_integer_max: u128,
+    /// Magnitudes of plausible floats (default is 1e-4 to 1e7); 0 is a small integer.
+    pub float_magnitudes: RangeInclusive<f64>,
+}
+
+impl Default for GroupClassifier {
+    fn default() -> Self {
+        Self {
+            pointer_ranges: Vec::new(),
+            small_integer_max: 0xffff,
+            float_magnitudes: 1e-4..=1e7,
+        }
+    }
+}
+
+impl GroupClassifier {
+    /// The kind of the group `bytes` (at most 16 of them) in the byte order `endianness`.
+    pub fn classify(&self, bytes: &[u8], endianness: Endianness) -> GroupKind {
+        let value = endianness.read(&bytes[..bytes.len().min(16)]);
+        let float = match bytes.len() {
+            4 => Some(f32::from_bits(value as u32) as f64),
+            8 => Some(f64::from_bits(value as u64)),
+            _ => None,
+        };
+        if self.pointer_ranges.iter().any(|range| range.contains(&value)) {
+            GroupKind::Pointer
+        } else if !bytes.is_empty() && bytes.iter().all(|byte| (0x20..0x7f).contains(byte)) {
+            GroupKind::Text
+        } else if value <= self.small_integer_max {
+            GroupKind::SmallInteger
+        } else if float.is_some_and(|float| self.float_magnitudes.contains(&float.abs())) {
+            GroupKind::Float
+        } else {
+            GroupKind::Unknown
+        }
+    }
+
+    /// A line annotator showing the [glyph](GroupKind::glyph) of the kind of each group of the line in the annotation
+    /// column, e.g. `*ii.` for a pointer, two small integers and an unknown value, to set as the `line_annotator` of
+    /// `settings`.
+    ///
+    /// Groups are read in their byte order (see `endianness` and `group_endianness`), guessed per line for
+    /// `Endianness::Auto`.  Groups cut short by the offset or the end of the data show a blank.
+    /// ```rust
+    /// use hexout::{hex_out, GroupClassifier, HexOutSettings};
+    /// let mut settings = HexOutSettings { group_size: 4, groups_per_line: 4, show_ascii: false, ..Default::default() };
+    /// settings.line_annotator = Some(GroupClassifier::default().annotator(&settings));
+    /// let data = [7u32.to_le_bytes(), *b"name", 2.5f32.to_le_bytes(), 0x8badf00du32.to_le_bytes()].concat();
+    /// let dump = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    /// assert_eq!(dump, "00000000: 00000007 656d616e  40200000 8badf00d iaf.");
+    /// ```
+    pub fn annotator(&self, settings: &HexOutSettings) -> LineAnnotator {
+        let classifier = self.clone();
+        let group_size = settings.group_size.max(1);
+        let total_bytes_per_line = group_size.saturating_mul(settings.groups_per_line).max(1);
+        let (endianness, group_endianness) = (settings.endianness, settings.group_endianness.clone());
+        let align_address = settings.align_address;
+        LineAnnotator::new(move |line_offset, line_bytes| {
+            let guessed = guess_endianness(line_bytes, group_size);
+            // Lines of dumps aligned to the address start with a group cut short by the offset, if any
+            let lead = match align_address {
+                true => (group_size - line_offset % group_size) % group_size,
+                false => 0,
+            };
+            let first_group = match align_address {
+                true => (line_offset + lead) % total_bytes_per_line / group_size,
+                false => 0,
+            };
+            let mut glyphs = String::new();
+            if lead > 0 {
+                glyphs.push(' ');
+            }
+            for (index, group) in line_bytes[lead.min(line_bytes.len())..].chunks(group_size).enumerate() {
+                let endianness = match group_endianness.get(first_group + index).copied().unwrap_or(endianness) {
+                    Endianness::Auto => guessed,
+                    endianness => endianness,
+                };
+                match group.len() == group_size {
+                    true => glyphs.push(classifier.classify(group, endianness).glyph()),
+                    false => glyphs.push(' '),
+                }
+            }
+            Some(glyphs)
+        })
+    }
+}
@@ -0,0 +1,130 @@
+use std::{env, ffi::OsString, io::IsTerminal};
+
+use crate::{strip_ansi, HexOutSettings};
+
+/// Decide whether styled (colored) output should be written to `stream`.
+///
+/// Follows the common conventions:
+/// - `CLICOLOR_FORCE` set to anything but `0` forces colors on, even when piped;
+/// - a non-empty `NO_COLOR` turns colors off;
+/// - `CLICOLOR=0` turns colors off;
+/// - otherwise colors are used only if `stream` is a terminal.
+///
+/// ```rust,no_run
+/// use hexout::{color_enabled, hex_out, HexOutSettings, Style};
+/// let settings = HexOutSettings { address_style: Some(Style::bold()), ..Default::default() };
+/// let stdout = std::io::stdout();
+/// let settings = if color_enabled(&stdout) { settings } else { settings.without_styles() };
+/// println!("{}", hex_out(b"data", &settings, 0, 0, 0).unwrap());
+/// ```
+pub fn color_enabled(stream: &impl IsTerminal) -> bool {
+    decide(
+        stream.is_terminal(),
+        env::var_os("NO_COLOR"),
+        env::var_os("CLICOLOR_FORCE"),
+        env::var_os("CLICOLOR"),
+    )
+}
+
+/// The decision of [`color_enabled`], given the environment variables' values.
+pub(crate) fn decide(
+    is_terminal: bool,
+    no_color: Option<OsString>,
+    clicolor_force: Option<OsString>,
+    clicolor: Option<OsString>,
+) -> bool {
+    if clicolor_force.is_some_and(|value| value != "0") {
+        true
+    } else if no_color.is_some_and(|value| !value.is_empty()) || clicolor.is_some_and(|value| value == "0") {
+        false
+    } else {
+        is_terminal
+    }
+}
+
+/// Make the console understand ANSI escape sequences, returning `false` if it can't.
+///
+/// On Windows this enables virtual terminal processing for stdout and stderr (legacy consoles otherwise print escape
+/// codes literally).  On other platforms terminals already handle escape sequences and this always succeeds.
+#[cfg(feature = "windows-console")]
+pub fn enable_virtual_terminal() -> bool {
+    #[cfg(windows)]
+    {
+        windows::enable_virtual_terminal()
+    }
+    #[cfg(not(windows))]
+    {
+        true
+    }
+}
+
+#[cfg(all(windows, feature = "windows-console"))]
+mod windows {
+    type Handle = *mut core::ffi::c_void;
+
+    const STD_OUTPUT_HANDLE: u32 = -11i32 as u32;
+    const STD_ERROR_HANDLE: u32 = -12i32 as u32;
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetStdHandle(std_handle: u32) -> Handle;
+        fn GetConsoleMode(console: Handle, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console: Handle, mode: u32) -> i32;
+    }
+
+    pub fn enable_virtual_terminal() -> bool {
+        [STD_OUTPUT_HANDLE, STD_ERROR_HANDLE].into_iter().all(|std_handle| {
+            // SAFETY: the handle comes straight from GetStdHandle and the mode is a valid out-pointer
+            unsafe {
+                let handle = GetStdHandle(std_handle);
+                let mut mode = 0;
+                if handle.is_null() || GetConsoleMode(handle, &mut mode) == 0 {
+                    // Not a console (e.g. redirected), nothing to enable
+                    return true;
+                }
+                mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+                    || SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+            }
+        })
+    }
+}
+
+impl HexOutSettings {
+    /// A copy of these settings with all styling removed (styles, error prefix/postfix, address hyperlinks and escape
+    /// sequences in `ascii_open`/`ascii_close`), leaving only plain text output.
+    pub fn without_styles(&self) -> HexOutSettings {
+        HexOutSettings {
+            hex_out_error_prefix: None,
+            hex_out_error_postfix: None,
+            cursor_style: None,
+            selection_style: None,
+            highlight_style: None,
+            flag_style: None,
+            address_style: None,
+            address_link: None,
+            error_style: None,
+            stripe_style: None,
+            guide_style: None,
+            ascii_open: strip_ansi(&self.ascii_open),
+            ascii_close: strip_ansi(&self.ascii_close),
+            ..self.clone()
+        }
+    }
+
+    /// A copy of these settings suitable for `stream`: unchanged if [`color_enabled`] says so, otherwise without styles.
+    ///
+    /// With the `windows-console` feature, virtual terminal processing is enabled as well, and styles are dropped if
+    /// the console doesn't support it.
+    pub fn for_stream(&self, stream: &impl IsTerminal) -> HexOutSettings {
+        #[cfg(feature = "windows-console")]
+        let supported = color_enabled(stream) && enable_virtual_terminal();
+        #[cfg(not(feature = "windows-console"))]
+        let supported = color_enabled(stream);
+        if supported {
+            self.clone()
+        } else {
+            self.without_styles()
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{ascii_area_width, base64::base64_width, hex_area_width, visible_width, HexOutSettings};
+
+/// A column of a dump line, see [`HexOutSettings::columns`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    /// The line number (decimal, right-aligned to `address_width`).
+    LineNumber,
+    /// The address of the first byte of the line.
+    Address,
+    /// The bytes in hex.
+    Hex,
+    /// The bytes as ASCII, non-printable ones as `.` (see also `text_mode`).
+    Ascii,
+    /// The valid bytes in base64, see `show_base64`.
+    Base64,
+    /// The 8-bit sum of the valid bytes of the line, in hex.
+    Checksum,
+    /// The [`similarity_digest`](crate::similarity_digest) of the valid bytes of the line, in hex, matching lines of
+    /// two dumps even if their data shifted by a few bytes.
+    Digest,
+    /// The number of valid bytes on the line, e.g. `(10 bytes)`.
+    ByteCount,
+    /// The text of the `line_decoder` for the record on the line, e.g. a disassembled instruction.
+    Decoded,
+    /// The note of the `line_annotator`, e.g. values decoded by a [`Layout`](crate::Layout).
+    Annotation,
+}
+
+impl Column {
+    /// Whether the column has a fixed width, so it can be lined up in a table.  Variable width columns follow the
+    /// borders, if any.
+    pub(crate) fn is_fixed_width(self) -> bool {
+        !matches!(self, Column::ByteCount | Column::Decoded | Column::Annotation)
+    }
+
+    /// Width of a fixed width column without borders (the ASCII column including `ascii_open` and `ascii_close`).
+    pub(crate) fn width(self, settings: &HexOutSettings) -> usize {
+        match self {
+            Column::LineNumber | Column::Address => settings.address_width,
+            Column::Hex => hex_area_width(settings),
+            Column::Ascii => {
+                visible_width(&settings.ascii_open) + ascii_area_width(settings) + visible_width(&settings.ascii_close)
+            }
+            Column::Base64 => base64_width(settings.group_size * settings.groups_per_line),
+            Column::Checksum => 2,
+            Column::Digest => 8,
+            Column::ByteCount | Column::Decoded | Column::Annotation => 0,
+        }
+    }
+
+    /// Separator between this column and the `next` one when not drawing borders.
+    pub(crate) fn separator(self, next: Column, settings: &HexOutSettings) -> &str {
+        match (self, next) {
+            (Column::LineNumber, Column::Address) => " ",
+            (Column::LineNumber | Column::Address, _) => &settings.address_separator,
+            _ => " ",
+        }
+    }
+}
+
+impl HexOutSettings {
+    /// The columns of each line in order: `columns` if set, otherwise the columns enabled by `show_line_number`,
+    /// `show_offset`, `show_ascii`, `show_base64`, `line_decoder`, `show_byte_count` and `line_annotator`.
+    pub fn effective_columns(&self) -> Vec<Column> {
+        if !self.columns.is_empty() {
+            return self.columns.clone();
+        }
+        let mut columns = Vec::with_capacity(8);
+        if self.show_line_number {
+            columns.push(Column::LineNumber);
+        }
+        if self.show_offset {
+            columns.push(Column::Address);
+        }
+        columns.push(Column::Hex);
+        if self.show_ascii {
+            columns.push(Column::Ascii);
+        }
+        if self.show_base64 {
+            columns.push(Column::Base64);
+        }
+        if self.line_decoder.is_some() {
+            columns.push(Column::Decoded);
+        }
+        if self.show_byte_count {
+            columns.push(Column::ByteCount);
+        }
+        if self.line_annotator.is_some() {
+            columns.push(Column::Annotation);
+        }
+        columns
+    }
+}
+
+/// The columns in the order they are rendered: with borders, the variable width columns go after the fixed width ones.
+pub(crate) fn ordered_columns(settings: &HexOutSettings) -> Vec<Column> {
+    let mut columns = settings.effective_columns();
+    if settings.borders.vertical().is_some() {
+        columns.sort_by_key(|column| !column.is_fixed_width());
+    }
+    columns
+}
+
+/// Position (in characters) of the address within a line without borders, if it is preceded only by columns that are
+/// never escaped or styled.
+pub(crate) fn address_position(settings: &HexOutSettings) -> Option<usize> {
+    if settings.borders.vertical().is_some() {
+        return None;
+    }
+    let mut position = 0;
+    let mut previous: Option<Column> = None;
+    for column in settings.effective_columns() {
+        if let Some(previous) = previous {
+            position += visible_width(previous.separator(column, settings));
+        }
+        match column {
+            Column::Address => return Some(position),
+            Column::LineNumber | Column::Checksum | Column::Digest => position += column.width(settings),
+            _ => return None,
+        }
+        previous = Some(column);
+    }
+    None
+}
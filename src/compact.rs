@@ -0,0 +1,382 @@
+use std::sync::Arc;
+
+use crate::{
+    hex_out, Borders, ByteHistogram, Endianness, GroupRadix, HexOutError, HexOutSettings, Style, StyledAreas, TextMode,
+};
+
+/// The layout options of [`HexOutSettings`] in a `Copy` struct, without any `String`, `Vec` or callback fields.
+///
+/// Copying these settings costs nothing, so code logging dumps from hot paths can keep them in a `static`, tweak a
+/// copy per call (e.g. its `address_origin`) and render it with [`CompactSettings::hex_out`].  Styles are kept apart
+/// in a [`StyleTable`], and both combine into full settings with [`CompactSettings::with_styles`], or into a preset
+/// shared between threads with [`CompactSettings::share`].  Common layouts are provided as presets
+/// ([`XXD`](Self::XXD), [`HEXDUMP_C`](Self::HEXDUMP_C), [`COMPACT`](Self::COMPACT) and [`WIDE32`](Self::WIDE32)).
+/// ```rust
+/// use hexout::CompactSettings;
+/// const SETTINGS: CompactSettings = CompactSettings { group_size: 2, groups_per_line: 4, ..CompactSettings::new() };
+/// let settings = CompactSettings { address_origin: 0x1000, ..SETTINGS };
+/// assert_eq!(settings.hex_out(b"compact", 0, 0, 0).unwrap(), "00001000: 6f63 706d  6361 ??74 |comp act |");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactSettings {
+    /// See [`HexOutSettings::address_origin`].
+    pub address_origin: u128,
+    /// See [`HexOutSettings::address_width`].
+    pub address_width: usize,
+    /// See [`HexOutSettings::align_address`].
+    pub align_address: bool,
+    /// See [`HexOutSettings::endianness`].
+    pub endianness: Endianness,
+    /// See [`HexOutSettings::group_size`].
+    pub group_size: usize,
+    /// See [`HexOutSettings::groups_per_line`].
+    pub groups_per_line: usize,
+    /// See [`HexOutSettings::invalid_data_placeholder`].
+    pub invalid_data_placeholder: char,
+    /// See [`HexOutSettings::show_ascii`].
+    pub show_ascii: bool,
+    /// See [`HexOutSettings::show_centerline`].
+    pub show_centerline: bool,
+    /// See [`HexOutSettings::show_header`].
+    pub show_header: bool,
+    /// See [`HexOutSettings::show_offset`].
+    pub show_offset: bool,
+    /// See [`HexOutSettings::strict`].
+    pub strict: bool,
+    /// See [`HexOutSettings::uppercase`].
+    pub uppercase: bool,
+    /// See [`HexOutSettings::borders`].
+    pub borders: Borders,
+    /// See [`HexOutSettings::rule_interval`].
+    pub rule_interval: usize,
+    /// See [`HexOutSettings::pad_lines`].
+    pub pad_lines: bool,
+    /// See [`HexOutSettings::pad_last_line`].
+    pub pad_last_line: bool,
+    /// See [`HexOutSettings::show_byte_count`].
+    pub show_byte_count: bool,
+    /// See [`HexOutSettings::show_line_number`].
+    pub show_line_number: bool,
+    /// See [`HexOutSettings::show_truncation`].
+    pub show_truncation: bool,
+    /// See [`HexOutSettings::header_interval`].
+    pub header_interval: usize,
+    /// See [`HexOutSettings::inline_chars`].
+    pub inline_chars: bool,
+    /// See [`HexOutSettings::dual_endianness`].
+    pub dual_endianness: bool,
+    /// See [`HexOutSettings::stripe_groups`].
+    pub stripe_groups: bool,
+    /// See [`HexOutSettings::ascii_placeholder`].
+    pub ascii_placeholder: char,
+    /// See [`HexOutSettings::text_mode`].
+    pub text_mode: TextMode,
+    /// See [`HexOutSettings::show_base64`].
+    pub show_base64: bool,
+    /// See [`HexOutSettings::binary`].
+    pub binary: bool,
+    /// See [`HexOutSettings::group_radix`].
+    pub group_radix: GroupRadix,
+    /// See [`HexOutSettings::skip_fill`].
+    pub skip_fill: Option<u8>,
+    /// See [`HexOutSettings::guide_interval`].
+    pub guide_interval: usize,
+    /// See [`HexOutSettings::guide_char`].
+    pub guide_char: char,
+    /// See [`HexOutSettings::styled_areas`].
+    pub styled_areas: StyledAreas,
+    /// See [`HexOutSettings::pad_last_ascii`].
+    pub pad_last_ascii: bool,
+    /// See [`HexOutSettings::ascii_centerline`].
+    pub ascii_centerline: bool,
+    /// See [`HexOutSettings::squeeze_repeats`].
+    pub squeeze_repeats: bool,
+    /// See [`HexOutSettings::show_end_address`].
+    pub show_end_address: bool,
+    /// See [`HexOutSettings::byte_histogram`].
+    pub byte_histogram: ByteHistogram,
+}
+
+impl CompactSettings {
+    /// A layout like `xxd`: 16 bytes per line in 2-byte groups, in memory order and without a centerline.  The ASCII
+    /// column keeps its bars, see [`HexOutSettings::ascii_open`] for the lead-in of `xxd`.
+    /// ```text
+    /// 00000000: 6865 7861 6d70 6c65 2069 6e20 7878 6420 |hexample in xxd |
+    /// ```
+    pub const XXD: Self = Self {
+        group_size: 2,
+        groups_per_line: 8,
+        endianness: Endianness::Big,
+        show_centerline: false,
+        ..Self::new()
+    };
+
+    /// The layout of `hexdump -C` as far as it fits in these settings: 16 single bytes per line with a centerline in
+    /// the hex area only, the ASCII column closed right after its last byte, repeated lines squeezed into a `*` line
+    /// and the end address on a last line of its own.
+    ///
+    /// The two spaces after the address and before the ASCII column are strings (see
+    /// [`HexOutSettings::address_separator`] and [`HexOutSettings::ascii_open`]), which this `Copy` struct can't hold,
+    /// so its lines keep the default `: ` and `|`.  [`HexOutSettings::hexdump_c`] adds them, for the output of
+    /// `hexdump -C` byte for byte.
+    /// ```text
+    /// 00000000: 68 65 78 64 75 6d 70 20  2d 43 20 6c 61 79 6f 75 |hexdump -C layou|
+    /// 00000010
+    /// ```
+    pub const HEXDUMP_C: Self = Self {
+        align_address: false,
+        group_size: 1,
+        groups_per_line: 16,
+        show_centerline: true,
+        pad_last_ascii: false,
+        ascii_centerline: false,
+        squeeze_repeats: true,
+        show_end_address: true,
+        ..Self::new()
+    };
+
+    /// A dense layout for logs: 16 bytes per line in 4-byte groups in memory order, without a centerline or the
+    /// ASCII column.
+    /// ```text
+    /// 00000000: 636f6d70 61637420 6c6f6720 6c696e65
+    /// ```
+    pub const COMPACT: Self = Self {
+        group_size: 4,
+        groups_per_line: 4,
+        endianness: Endianness::Big,
+        show_centerline: false,
+        show_ascii: false,
+        ..Self::new()
+    };
+
+    /// A layout for wide terminals: 32 single bytes per line with a centerline.
+    pub const WIDE32: Self = Self {
+        group_size: 1,
+        groups_per_line: 32,
+        show_centerline: true,
+        ..Self::new()
+    };
+
+    /// A layout like `xxd -b`: 6 bytes per line in binary, without a centerline.  With an `ascii_open` of a space and
+    /// an empty `ascii_close`, the output matches `xxd -b` exactly.
+    /// ```text
+    /// 00000000: 01111000 01111000 01100100 00100000 00101101 01100010  xxd -b
+    /// ```
+    pub const XXD_BITS: Self = Self {
+        group_size: 1,
+        groups_per_line: 6,
+        show_centerline: false,
+        binary: true,
+        ..Self::new()
+    };
+
+    /// Settings with the same defaults as [`HexOutSettings::default`].
+    pub const fn new() -> Self {
+        Self {
+            address_origin: 0,
+            address_width: 8,
+            align_address: true,
+            endianness: Endianness::Little,
+            group_size: 1,
+            groups_per_line: 16,
+            invalid_data_placeholder: '?',
+            show_ascii: true,
+            show_centerline: true,
+            show_header: false,
+            show_offset: true,
+            strict: false,
+            uppercase: false,
+            borders: Borders::None,
+            rule_interval: 0,
+            pad_lines: false,
+            pad_last_line: true,
+            show_byte_count: false,
+            show_line_number: false,
+            show_truncation: false,
+            header_interval: 0,
+            inline_chars: false,
+            dual_endianness: false,
+            stripe_groups: false,
+            ascii_placeholder: ' ',
+            text_mode: TextMode::Ascii,
+            show_base64: false,
+            binary: false,
+            group_radix: GroupRadix::Hex,
+            skip_fill: None,
+            guide_interval: 0,
+            guide_char: '┊',
+            styled_areas: StyledAreas::Standard,
+            pad_last_ascii: true,
+            ascii_centerline: true,
+            squeeze_repeats: false,
+            show_end_address: false,
+            byte_histogram: ByteHistogram::None,
+        }
+    }
+
+    /// Full settings with these layout options and the styles of `styles`.
+    pub fn with_styles(&self, styles: &StyleTable) -> HexOutSettings {
+        HexOutSettings {
+            address_style: styles.address.clone(),
+            cursor_style: styles.cursor.clone(),
+            selection_style: styles.selection.clone(),
+            highlight_style: styles.highlight.clone(),
+            flag_style: styles.flag.clone(),
+            error_style: styles.error.clone(),
+            stripe_style: styles.stripe.clone(),
+            guide_style: styles.guide.clone(),
+            ..HexOutSettings::from(*self)
+        }
+    }
+
+    /// A preset of these layout options and `styles`, to be shared between threads and cloned for free.
+    ///
+    /// `Arc<HexOutSettings>` dereferences to the settings, so it is passed to [`hex_out`] and the other renderers as
+    /// is.
+    pub fn share(&self, styles: &StyleTable) -> Arc<HexOutSettings> {
+        Arc::new(self.with_styles(styles))
+    }
+
+    /// Generate an unstyled hex dump, see [`hex_out`] for the parameters.
+    pub fn hex_out(&self, data: &[u8], offset: usize, start_line: usize, line_count: usize) -> Result<String, HexOutError> {
+        hex_out(data, &HexOutSettings::from(*self), offset, start_line, line_count)
+    }
+}
+
+impl Default for CompactSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<CompactSettings> for HexOutSettings {
+    fn from(settings: CompactSettings) -> Self {
+        HexOutSettings {
+            address_origin: settings.address_origin,
+            address_width: settings.address_width,
+            align_address: settings.align_address,
+            endianness: settings.endianness,
+            group_size: settings.group_size,
+            groups_per_line: settings.groups_per_line,
+            invalid_data_placeholder: settings.invalid_data_placeholder,
+            show_ascii: settings.show_ascii,
+            show_centerline: settings.show_centerline,
+            show_header: settings.show_header,
+            show_offset: settings.show_offset,
+            strict: settings.strict,
+            uppercase: settings.uppercase,
+            borders: settings.borders,
+            rule_interval: settings.rule_interval,
+            pad_lines: settings.pad_lines,
+            pad_last_line: settings.pad_last_line,
+            show_byte_count: settings.show_byte_count,
+            show_line_number: settings.show_line_number,
+            show_truncation: settings.show_truncation,
+            header_interval: settings.header_interval,
+            inline_chars: settings.inline_chars,
+            dual_endianness: settings.dual_endianness,
+            stripe_groups: settings.stripe_groups,
+            ascii_placeholder: settings.ascii_placeholder,
+            text_mode: settings.text_mode,
+            show_base64: settings.show_base64,
+            binary: settings.binary,
+            group_radix: settings.group_radix,
+            skip_fill: settings.skip_fill,
+            guide_interval: settings.guide_interval,
+            guide_char: settings.guide_char,
+            styled_areas: settings.styled_areas,
+            pad_last_ascii: settings.pad_last_ascii,
+            ascii_centerline: settings.ascii_centerline,
+            squeeze_repeats: settings.squeeze_repeats,
+            show_end_address: settings.show_end_address,
+            byte_histogram: settings.byte_histogram,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&HexOutSettings> for CompactSettings {
+    /// The layout options of `settings`, leaving out everything that doesn't fit a `Copy` struct.
+    fn from(settings: &HexOutSettings) -> Self {
+        CompactSettings {
+            address_origin: settings.address_origin,
+            address_width: settings.address_width,
+            align_address: settings.align_address,
+            endianness: settings.endianness,
+            group_size: settings.group_size,
+            groups_per_line: settings.groups_per_line,
+            invalid_data_placeholder: settings.invalid_data_placeholder,
+            show_ascii: settings.show_ascii,
+            show_centerline: settings.show_centerline,
+            show_header: settings.show_header,
+            show_offset: settings.show_offset,
+            strict: settings.strict,
+            uppercase: settings.uppercase,
+            borders: settings.borders,
+            rule_interval: settings.rule_interval,
+            pad_lines: settings.pad_lines,
+            pad_last_line: settings.pad_last_line,
+            show_byte_count: settings.show_byte_count,
+            show_line_number: settings.show_line_number,
+            show_truncation: settings.show_truncation,
+            header_interval: settings.header_interval,
+            inline_chars: settings.inline_chars,
+            dual_endianness: settings.dual_endianness,
+            stripe_groups: settings.stripe_groups,
+            ascii_placeholder: settings.ascii_placeholder,
+            text_mode: settings.text_mode,
+            show_base64: settings.show_base64,
+            binary: settings.binary,
+            group_radix: settings.group_radix,
+            skip_fill: settings.skip_fill,
+            guide_interval: settings.guide_interval,
+            guide_char: settings.guide_char,
+            styled_areas: settings.styled_areas,
+            pad_last_ascii: settings.pad_last_ascii,
+            ascii_centerline: settings.ascii_centerline,
+            squeeze_repeats: settings.squeeze_repeats,
+            show_end_address: settings.show_end_address,
+            byte_histogram: settings.byte_histogram,
+        }
+    }
+}
+
+/// The styles of [`HexOutSettings`], kept apart from the [`CompactSettings`] they apply to.
+///
+/// A style table is also a color theme, see [`Themes`](crate::Themes) for the built-in ones and those loaded from
+/// user theme files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct StyleTable {
+    /// See [`HexOutSettings::address_style`].
+    pub address: Option<Style>,
+    /// See [`HexOutSettings::cursor_style`].
+    pub cursor: Option<Style>,
+    /// See [`HexOutSettings::selection_style`].
+    pub selection: Option<Style>,
+    /// See [`HexOutSettings::highlight_style`].
+    pub highlight: Option<Style>,
+    /// See [`HexOutSettings::flag_style`].
+    pub flag: Option<Style>,
+    /// See [`HexOutSettings::error_style`].
+    pub error: Option<Style>,
+    /// See [`HexOutSettings::stripe_style`].
+    pub stripe: Option<Style>,
+    /// See [`HexOutSettings::guide_style`].
+    pub guide: Option<Style>,
+}
+
+impl From<&HexOutSettings> for StyleTable {
+    fn from(settings: &HexOutSettings) -> Self {
+        StyleTable {
+            address: settings.address_style.clone(),
+            cursor: settings.cursor_style.clone(),
+            selection: settings.selection_style.clone(),
+            highlight: settings.highlight_style.clone(),
+            flag: settings.flag_style.clone(),
+            error: settings.error_style.clone(),
+            stripe: settings.stripe_style.clone(),
+            guide: settings.guide_style.clone(),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    borrow::Cow,
+    ops::{Index, Range},
+};
+
+use crate::{render_dump, HexOutError, HexOutSettings, RenderContext};
+
+/// Generate a hex dump of several buffers as one, with the addresses running on from one buffer to the next, e.g. for
+/// data assembled from multiple reads.
+///
+/// The first byte of the first buffer is shown at `origin`, which replaces the `address_origin` setting.  The buffers
+/// are not copied into one, only the bytes of lines spanning two of them are.  With `Endianness::Auto`, the byte order
+/// is guessed from the first buffer.
+/// ```rust
+/// use hexout::{hex_dump_concat, HexOutSettings};
+/// let settings = HexOutSettings { groups_per_line: 4, show_ascii: false, ..Default::default() };
+/// assert_eq!(
+///     hex_dump_concat(&[b"\x01\x02\x03", b"", b"\x04\x05"], &settings, 0x100).unwrap(),
+///     "00000100: 01 02  03 04\n00000104: 05"
+/// );
+/// ```
+pub fn hex_dump_concat(parts: &[&[u8]], settings: &HexOutSettings, origin: u128) -> Result<String, HexOutError> {
+    let settings = HexOutSettings { address_origin: origin, ..settings.clone() };
+    render_dump(Source::Parts(parts), RenderContext::default(), &settings, 0, 0, 0)
+}
+
+/// The data of a dump: one slice, several parts forming one contiguous range, or the bytes of a range of data
+/// otherwise unread.
+#[derive(Clone, Copy)]
+pub(crate) enum Source<'a> {
+    Slice(&'a [u8]),
+    Parts(&'a [&'a [u8]]),
+    /// The bytes from data index `start` on of data of `len` bytes.
+    Partial { start: usize, bytes: &'a [u8], len: usize },
+}
+
+impl<'a> From<&'a [u8]> for Source<'a> {
+    fn from(data: &'a [u8]) -> Self {
+        Source::Slice(data)
+    }
+}
+
+impl<'a> Source<'a> {
+    /// Number of bytes of the data.
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Source::Slice(data) => data.len(),
+            Source::Parts(parts) => parts.iter().map(|part| part.len()).sum(),
+            Source::Partial { len, .. } => *len,
+        }
+    }
+
+    /// The bytes from data index `start` on, at least `min_len` of them as far as the data holds them.
+    ///
+    /// A slice is borrowed to its end, parts to the end of the part holding `start`; only when `min_len` bytes reach
+    /// past that part are they copied together.  Of partial data, the bytes read are borrowed from `start` on (or from
+    /// their start, if `start` is before it).
+    pub(crate) fn bytes_from(&self, start: usize, min_len: usize) -> Cow<'a, [u8]> {
+        let parts = match self {
+            Source::Slice(data) => return Cow::Borrowed(&data[start.min(data.len())..]),
+            Source::Parts(parts) => parts,
+            Source::Partial { start: bytes_start, bytes, .. } => {
+                return Cow::Borrowed(&bytes[start.saturating_sub(*bytes_start).min(bytes.len())..]);
+            }
+        };
+        let mut part_start = 0;
+        let mut copied: Option<Vec<u8>> = None;
+        for part in parts.iter() {
+            let part_end = part_start + part.len();
+            if part_end > start {
+                let from = start.max(part_start) - part_start;
+                match &mut copied {
+                    None if part_end - start >= min_len => return Cow::Borrowed(&part[from..]),
+                    None => copied = Some(part[from..].to_vec()),
+                    Some(bytes) => bytes.extend_from_slice(&part[..(min_len - bytes.len()).min(part.len())]),
+                }
+                if copied.as_ref().is_some_and(|bytes| bytes.len() >= min_len) {
+                    break;
+                }
+            }
+            part_start = part_end;
+        }
+        copied.map_or(Cow::Borrowed(&[]), Cow::Owned)
+    }
+}
+
+/// The bytes of a dump read by the renderer: all of the data for a slice, the bytes of the current line for parts, and
+/// the bytes read of partial data.
+///
+/// Indexing takes data indices.
+pub(crate) struct Window<'a> {
+    /// Data index of the first byte of `bytes`.
+    start: usize,
+    bytes: Cow<'a, [u8]>,
+    /// Length of the data as far as it is rendered; bytes at or past it are missing.
+    len: usize,
+}
+
+impl<'a> Window<'a> {
+    /// The window over the start of `source`.
+    pub(crate) fn new(source: Source<'a>) -> Self {
+        let start = match source {
+            Source::Partial { start, .. } => start,
+            _ => 0,
+        };
+        Self { start, bytes: source.bytes_from(start, 0), len: source.len() }
+    }
+
+    /// Move the window of parts over the bytes of `range`, as far as the data holds them.
+    pub(crate) fn load(&mut self, source: Source<'a>, range: Range<usize>) {
+        if let Source::Parts(_) = source {
+            self.start = range.start;
+            self.bytes = source.bytes_from(range.start, range.len());
+        }
+    }
+
+    /// Treat the data as ending at `len`, e.g. at the end of a record.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.len = len;
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The bytes of `range`, if they are all within the data.
+    pub(crate) fn get(&self, range: Range<usize>) -> Option<&[u8]> {
+        (range.end <= self.len).then(|| &self[range])
+    }
+}
+
+impl Index<usize> for Window<'_> {
+    type Output = u8;
+
+    fn index(&self, index: usize) -> &u8 {
+        &self.bytes[index - self.start]
+    }
+}
+
+impl Index<Range<usize>> for Window<'_> {
+    type Output = [u8];
+
+    fn index(&self, range: Range<usize>) -> &[u8] {
+        if range.is_empty() {
+            return &[];
+        }
+        &self.bytes[range.start - self.start..range.end - self.start]
+    }
+}
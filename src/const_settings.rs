@@ -0,0 +1,272 @@
+use core::fmt;
+
+#[cfg(feature = "std")]
+use crate::HexOutSettings;
+use crate::{HEX_DIGITS_LOWER, HEX_DIGITS_UPPER};
+
+/// Settings fixed at compile time: `GROUP` bytes per group, `GROUPS` groups per line and whether to show the `ASCII`
+/// column, with a renderer monomorphized for them.
+///
+/// This trades the flexibility of [`HexOutSettings`] for a tight loop without styles, callbacks or layout options,
+/// for hot paths and small embedded builds.  Groups are little-endian with a centerline, and the output is identical
+/// to that of [`hex_out`](crate::hex_out) with the equivalent settings (see [`ConstHexOutSettings::to_settings`]).
+/// Invalid dimensions (`GROUP` outside 1-16 or no `GROUPS`) are rejected at compile time.
+///
+/// Without the `std` feature, the crate is `no_std` and this is all it holds, with [`write_to`](Self::write_to) and
+/// [`render_array`](Self::render_array) to dump data on AVR and other 16-bit targets.
+/// ```rust
+/// use hexout::ConstHexOutSettings;
+/// const SETTINGS: ConstHexOutSettings<4, 2> = ConstHexOutSettings::new();
+/// let mut dump = String::new();
+/// SETTINGS.write_to(b"hexout!", &mut dump).unwrap();
+/// assert_eq!(dump, "00000000: 6f786568  ??217475 |hexo ut! |");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstHexOutSettings<const GROUP: usize, const GROUPS: usize, const ASCII: bool = true> {
+    /// The origin address to start from, see [`HexOutSettings::address_origin`].
+    pub address_origin: u128,
+    /// Width of the address field in characters (not validated, addresses wider than this are shown in full).
+    pub address_width: usize,
+    /// Whether to use uppercase letters for hex digits.
+    pub uppercase: bool,
+}
+
+impl<const GROUP: usize, const GROUPS: usize, const ASCII: bool> Default for ConstHexOutSettings<GROUP, GROUPS, ASCII> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const GROUP: usize, const GROUPS: usize, const ASCII: bool> ConstHexOutSettings<GROUP, GROUPS, ASCII> {
+    const VALID: () = assert!(GROUP >= 1 && GROUP <= 16 && GROUPS >= 1, "invalid group size or number of groups");
+    const BYTES_PER_LINE: usize = GROUP * GROUPS;
+    /// Width of the centerline, which is only drawn with at least two groups.
+    const CENTERLINE_WIDTH: usize = if GROUPS / 2 > 0 { 1 } else { 0 };
+    const HEX_WIDTH: usize = (GROUP * 2 + 1) * GROUPS + Self::CENTERLINE_WIDTH - 1;
+    const ASCII_WIDTH: usize = Self::BYTES_PER_LINE + Self::CENTERLINE_WIDTH;
+
+    /// Settings with the default address origin and width and lowercase hex digits.
+    pub const fn new() -> Self {
+        Self {
+            address_origin: 0,
+            address_width: 8,
+            uppercase: false,
+        }
+    }
+
+    /// The equivalent runtime settings.
+    #[cfg(feature = "std")]
+    pub fn to_settings(&self) -> HexOutSettings {
+        HexOutSettings {
+            address_origin: self.address_origin,
+            address_width: self.address_width,
+            group_size: GROUP,
+            groups_per_line: GROUPS,
+            show_ascii: ASCII,
+            uppercase: self.uppercase,
+            ..Default::default()
+        }
+    }
+
+    /// Length in bytes of the dump of `data_len` bytes, i.e. the array length for [`render_array`](Self::render_array).
+    pub const fn dump_len(&self, data_len: usize) -> usize {
+        let mut len = 0;
+        let mut line_start = 0;
+        while line_start < data_len {
+            if line_start > 0 {
+                len += 1;
+            }
+            let remaining = data_len - line_start;
+            let line_len = if remaining < Self::BYTES_PER_LINE { remaining } else { Self::BYTES_PER_LINE };
+            len += address_digits(self.address_origin.wrapping_add(line_start as u128), self.address_width) + 2;
+            len += if ASCII { Self::HEX_WIDTH + 2 + Self::ASCII_WIDTH + 1 } else { Self::hex_width(line_len) };
+            line_start += line_len;
+        }
+        len
+    }
+
+    /// Render a hex dump of all of `data` as UTF-8 into an array of `N` bytes, which must be
+    /// [`dump_len(data.len())`](Self::dump_len) long.
+    ///
+    /// Being a `const fn`, this turns data embedded with `include_bytes!` into a dump at compile time, e.g. for
+    /// diagnostics screens baked into firmware:
+    /// ```rust
+    /// use hexout::ConstHexOutSettings;
+    /// const SETTINGS: ConstHexOutSettings<1, 4> = ConstHexOutSettings::new();
+    /// const DATA: &[u8] = b"BOOT"; // e.g. include_bytes!("boot.bin")
+    /// const DUMP: [u8; SETTINGS.dump_len(DATA.len())] = SETTINGS.render_array(DATA);
+    /// const TEXT: &str = match std::str::from_utf8(&DUMP) {
+    ///     Ok(text) => text,
+    ///     Err(_) => panic!("dumps are ASCII"),
+    /// };
+    /// assert_eq!(TEXT, "00000000: 42 4f  4f 54 |BO OT|");
+    /// ```
+    pub const fn render_array<const N: usize>(&self, data: &[u8]) -> [u8; N] {
+        let () = Self::VALID;
+        assert!(N == self.dump_len(data.len()), "the array length must be dump_len(data.len())");
+        let digits = if self.uppercase { HEX_DIGITS_UPPER } else { HEX_DIGITS_LOWER };
+        // Padding is left as is
+        let mut out = [b' '; N];
+        let mut position = 0;
+        let mut line_start = 0;
+        while line_start < data.len() {
+            if line_start > 0 {
+                out[position] = b'\n';
+                position += 1;
+            }
+            let remaining = data.len() - line_start;
+            let line_len = if remaining < Self::BYTES_PER_LINE { remaining } else { Self::BYTES_PER_LINE };
+            let address = self.address_origin.wrapping_add(line_start as u128);
+            let width = address_digits(address, self.address_width);
+            let mut digit = 0;
+            while digit < width {
+                let nibble = if digit < 32 { (address >> (digit * 4)) & 0x0f } else { 0 };
+                // Addresses are lowercase, as with `hex_out`
+                out[position + width - 1 - digit] = HEX_DIGITS_LOWER[nibble as usize];
+                digit += 1;
+            }
+            position += width;
+            out[position] = b':';
+            position += 2;
+            let hex_start = position;
+            let groups = line_len.div_ceil(GROUP);
+            let mut group = 0;
+            while group < groups {
+                if group > 0 {
+                    position += 1;
+                }
+                // Most significant byte first, bytes past the end of the data as placeholders
+                let mut byte_position = GROUP;
+                while byte_position > 0 {
+                    byte_position -= 1;
+                    let index = group * GROUP + byte_position;
+                    if index < line_len {
+                        let byte = data[line_start + index];
+                        out[position] = digits[(byte >> 4) as usize];
+                        out[position + 1] = digits[(byte & 0x0f) as usize];
+                    } else {
+                        out[position] = b'?';
+                        out[position + 1] = b'?';
+                    }
+                    position += 2;
+                }
+                if group + 1 == GROUPS / 2 {
+                    position += 1;
+                }
+                group += 1;
+            }
+            if ASCII {
+                position = hex_start + Self::HEX_WIDTH + 1;
+                out[position] = b'|';
+                position += 1;
+                let ascii_start = position;
+                let mut index = 0;
+                while index < line_len {
+                    let byte = data[line_start + index];
+                    out[position] = if byte >= 0x20 && byte < 0x7f { byte } else { b'.' };
+                    position += 1;
+                    index += 1;
+                    if index == GROUP * (GROUPS / 2) {
+                        position += 1;
+                    }
+                }
+                position = ascii_start + Self::ASCII_WIDTH;
+                out[position] = b'|';
+                position += 1;
+            }
+            line_start += line_len;
+        }
+        out
+    }
+
+    /// Width of the hex area of a line of `bytes` bytes, without padding.
+    const fn hex_width(bytes: usize) -> usize {
+        let groups = bytes.div_ceil(GROUP);
+        let centerline = if groups >= GROUPS / 2 { Self::CENTERLINE_WIDTH } else { 0 };
+        groups * (GROUP * 2 + 1) - 1 + centerline
+    }
+
+    /// Generate a hex dump of all of `data`.
+    #[cfg(feature = "std")]
+    pub fn hex_out(&self, data: &[u8]) -> String {
+        let mut result = String::with_capacity(data.len().div_ceil(Self::BYTES_PER_LINE) * (Self::HEX_WIDTH + 32));
+        self.hex_out_into(data, &mut result);
+        result
+    }
+
+    /// Append a hex dump of all of `data` to `out`.
+    #[cfg(feature = "std")]
+    pub fn hex_out_into(&self, data: &[u8], out: &mut String) {
+        // Writing to a `String` can't fail
+        let _ = self.write_to(data, out);
+    }
+
+    /// Write a hex dump of all of `data` to `out` without allocating.
+    ///
+    /// This is the renderer left without the `std` feature, e.g. for AVR: lines and groups are taken as sub-slices of
+    /// `data` rather than by index arithmetic, so this is safe for slices reaching the end of the address space on
+    /// targets with a 16-bit `usize`, and addresses are computed and formatted in `usize` unless the origin puts them
+    /// past `usize::MAX`.
+    pub fn write_to(&self, data: &[u8], out: &mut impl fmt::Write) -> fmt::Result {
+        let () = Self::VALID;
+        let digits = if self.uppercase { HEX_DIGITS_UPPER } else { HEX_DIGITS_LOWER };
+        for (line, bytes) in data.chunks(Self::BYTES_PER_LINE).enumerate() {
+            if line > 0 {
+                out.write_char('\n')?;
+            }
+            let offset = line * Self::BYTES_PER_LINE;
+            let width = self.address_width;
+            match usize::try_from(self.address_origin).ok().and_then(|origin| origin.checked_add(offset)) {
+                Some(address) => write!(out, "{address:0width$x}: ")?,
+                None => write!(out, "{:0width$x}: ", self.address_origin.wrapping_add(offset as u128))?,
+            }
+            let groups = bytes.len().div_ceil(GROUP);
+            for (group, group_bytes) in bytes.chunks(GROUP).enumerate() {
+                if group > 0 {
+                    out.write_char(' ')?;
+                }
+                // Most significant byte first, bytes past the end of the data as placeholders
+                for position in (0..GROUP).rev() {
+                    match group_bytes.get(position) {
+                        Some(byte) => {
+                            out.write_char(digits[(byte >> 4) as usize] as char)?;
+                            out.write_char(digits[(byte & 0x0f) as usize] as char)?;
+                        }
+                        None => out.write_str("??")?,
+                    }
+                }
+                if group + 1 == GROUPS / 2 {
+                    out.write_char(' ')?;
+                }
+            }
+            if ASCII {
+                // Only the last line can be short, pad it to line up the ASCII column
+                write_spaces(out, Self::HEX_WIDTH - Self::hex_width(bytes.len()))?;
+                out.write_str(" |")?;
+                for (group, group_bytes) in bytes.chunks(GROUP).enumerate() {
+                    for byte in group_bytes {
+                        out.write_char(if (0x20..0x7f).contains(byte) { *byte as char } else { '.' })?;
+                    }
+                    write_spaces(out, GROUP - group_bytes.len())?;
+                    if group + 1 == GROUPS / 2 {
+                        out.write_char(' ')?;
+                    }
+                }
+                let centerline = if groups >= GROUPS / 2 { Self::CENTERLINE_WIDTH } else { 0 };
+                write_spaces(out, Self::ASCII_WIDTH - groups * GROUP - centerline)?;
+                out.write_char('|')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_spaces(out: &mut impl fmt::Write, count: usize) -> fmt::Result {
+    (0..count).try_for_each(|_| out.write_char(' '))
+}
+
+/// Number of digits of `address` in hex, at least `width`.
+const fn address_digits(address: u128, width: usize) -> usize {
+    let digits = if address == 0 { 1 } else { (u128::BITS - address.leading_zeros()).div_ceil(4) as usize };
+    if digits > width { digits } else { width }
+}
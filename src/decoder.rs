@@ -0,0 +1,62 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{concat::Source, display_address, HexOutSettings};
+
+/// Callback decoding the record at the start of its bytes, e.g. a disassembler or a TLV decoder, see
+/// `HexOutSettings::line_decoder`.
+///
+/// The callback receives the bytes from the start of the record to the end of the data and the displayed address of
+/// the record (including `address_origin`, or as mapped by `address_map`), and returns the number of bytes the record
+/// takes with its decoded text.  Returning `None` leaves the bytes undecoded, which then fill a whole line.
+/// ```rust
+/// use hexout::LineDecoder;
+/// let decoder = LineDecoder::new(|bytes, _| bytes.first().map(|len| (1 + *len as usize, format!("record of {len}"))));
+/// assert_eq!(decoder.decode(&[2, 0xaa, 0xbb], 0), Some((3, "record of 2".to_string())));
+/// ```
+#[derive(Clone)]
+pub struct LineDecoder(Arc<LineDecoderFn>);
+
+type LineDecoderFn = dyn Fn(&[u8], u128) -> Option<(usize, String)> + Send + Sync;
+
+impl LineDecoder {
+    /// Wrap a closure as a line decoder.
+    pub fn new(decoder: impl Fn(&[u8], u128) -> Option<(usize, String)> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(decoder))
+    }
+
+    /// Invoke the decoder for the record at the start of `bytes`, shown at `address`.
+    pub fn decode(&self, bytes: &[u8], address: u128) -> Option<(usize, String)> {
+        (self.0)(bytes, address)
+    }
+}
+
+impl Debug for LineDecoder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LineDecoder(..)")
+    }
+}
+
+/// Split the data from `offset` on into records, returning the end of each record with its decoded text.
+///
+/// Records take at least one byte and at most `bytes_per_line`, and undecoded ones a whole line.
+pub(crate) fn decode_records(
+    decoder: &LineDecoder,
+    data: Source,
+    offset: usize,
+    bytes_per_line: usize,
+    settings: &HexOutSettings,
+) -> Vec<(usize, Option<String>)> {
+    let mut records = Vec::new();
+    let mut start = offset;
+    let len = data.len();
+    while start < len {
+        let (length, text) = match decoder.decode(&data.bytes_from(start, bytes_per_line), display_address(settings, start)) {
+            Some((consumed, text)) => (consumed.clamp(1, bytes_per_line), Some(text)),
+            None => (bytes_per_line, None),
+        };
+        let end = start.saturating_add(length).min(len);
+        records.push((end, text));
+        start = end;
+    }
+    records
+}
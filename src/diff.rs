@@ -0,0 +1,179 @@
+use std::ops::Range;
+
+use crate::{
+    expand_caption, push_truncation, render_dump, render_header, validate, Borders, ByteHistogram, HexOutError,
+    HexOutSettings, RenderContext,
+};
+
+/// Generate a hex dump of the lines of `new` that differ from `old`, with `context_lines` unchanged lines around
+/// them, so diffs of large images stay short.
+///
+/// Changed bytes are flagged (see [`hex_out_flagged`](crate::hex_out_flagged)), so they are styled with `flag_style`
+/// and marked with `flag_marker` if set, and bytes past the end of `old` count as changed.  The caption and header
+/// (if enabled) are only emitted when at least one line changed, and with `show_truncation` the unchanged lines left
+/// out are noted, e.g. `… 12 lines unchanged …`.  Borders, `line_decoder`, `show_end_address` and `byte_histogram`
+/// don't apply.
+/// ```rust
+/// use hexout::{hex_out_changes, HexOutSettings};
+/// let settings = HexOutSettings { groups_per_line: 4, show_truncation: true, ..Default::default() };
+/// let old = [0u8; 32];
+/// let mut new = old;
+/// new[13] = 0xff;
+/// assert_eq!(
+///     hex_out_changes(&old, &new, &settings, 1).unwrap(),
+///     "… 2 lines unchanged …\n\
+///      00000008: 00 00  00 00 |.. ..|\n\
+///      0000000c: 00 ff  00 00 |.. ..|\n\
+///      00000010: 00 00  00 00 |.. ..|\n\
+///      … 3 lines unchanged …"
+/// );
+/// ```
+pub fn hex_out_changes(
+    old: &[u8],
+    new: &[u8],
+    settings: &HexOutSettings,
+    context_lines: usize,
+) -> Result<String, HexOutError> {
+    validate(settings, 0)?;
+    let flags = changed_bytes(old, new);
+    let shown = changed_lines(&flags, settings, context_lines);
+    render_lines(new, &flags, settings, &shown)
+}
+
+/// Renders only the lines of a buffer that changed since the previous call, for polling hardware registers or
+/// shared memory.
+///
+/// Changed bytes are flagged (see [`hex_out_flagged`](crate::hex_out_flagged)), so they are styled with
+/// `flag_style` and marked with `flag_marker` if set.  The first call renders every line.
+/// ```rust
+/// use hexout::{HexOutSettings, SnapshotDiffer};
+/// let mut differ = SnapshotDiffer::new(HexOutSettings { groups_per_line: 4, ..Default::default() });
+/// let mut registers = [0u8; 8];
+/// differ.diff(&registers).unwrap();
+/// registers[5] = 0xff;
+/// assert_eq!(differ.diff(&registers).unwrap(), "00000004: 00 ff  00 00 |.. ..|");
+/// assert_eq!(differ.diff(&registers).unwrap(), "");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SnapshotDiffer {
+    settings: HexOutSettings,
+    previous: Option<Vec<u8>>,
+    context_lines: usize,
+}
+
+impl SnapshotDiffer {
+    /// Create a differ rendering with the given settings.
+    pub fn new(settings: HexOutSettings) -> Self {
+        Self { settings, previous: None, context_lines: 0 }
+    }
+
+    /// Render `lines` unchanged lines around the changed ones, as [`hex_out_changes`] does.
+    pub fn with_context(mut self, lines: usize) -> Self {
+        self.context_lines = lines;
+        self
+    }
+
+    /// The settings used for rendering.
+    pub fn settings(&self) -> &HexOutSettings {
+        &self.settings
+    }
+
+    /// Forget the stored snapshot, so the next call renders every line again.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// Render the lines of `data` that changed since the previous call and store `data` as the new snapshot.
+    ///
+    /// Bytes past the end of the previous snapshot count as changed.  The caption and header (if enabled) are only
+    /// emitted when at least one line changed.
+    pub fn diff(&mut self, data: &[u8]) -> Result<String, HexOutError> {
+        let settings = &self.settings;
+        validate(settings, 0)?;
+        let result = match &self.previous {
+            Some(previous) => {
+                let flags = changed_bytes(previous, data);
+                render_lines(data, &flags, settings, &changed_lines(&flags, settings, self.context_lines))?
+            }
+            None => {
+                let all_lines = 0..data.len().div_ceil(settings.group_size * settings.groups_per_line);
+                render_lines(data, &[], settings, std::slice::from_ref(&all_lines))?
+            }
+        };
+        self.previous = Some(data.to_vec());
+        Ok(result)
+    }
+}
+
+/// Flags of the bytes of `new` that differ from `old`, including those past its end.
+fn changed_bytes(old: &[u8], new: &[u8]) -> Vec<u8> {
+    new.iter().enumerate().map(|(index, byte)| (old.get(index) != Some(byte)) as u8).collect()
+}
+
+/// The lines with flagged bytes and `context_lines` lines around them, as ordered ranges of line numbers.
+fn changed_lines(flags: &[u8], settings: &HexOutSettings, context_lines: usize) -> Vec<Range<usize>> {
+    let total_bytes_per_line = settings.group_size * settings.groups_per_line;
+    let total_lines = flags.len().div_ceil(total_bytes_per_line);
+    let mut shown: Vec<Range<usize>> = Vec::new();
+    for (line, line_flags) in flags.chunks(total_bytes_per_line).enumerate() {
+        if line_flags.iter().all(|flag| *flag == 0) {
+            continue;
+        }
+        let lines = line.saturating_sub(context_lines)..line.saturating_add(context_lines + 1).min(total_lines);
+        match shown.last_mut() {
+            Some(last) if last.end >= lines.start => last.end = lines.end,
+            _ => shown.push(lines),
+        }
+    }
+    shown
+}
+
+/// Render the `shown` ranges of lines of `data`, with the caption and header before the first one and, with
+/// `show_truncation`, notes on the lines left out.
+fn render_lines(
+    data: &[u8],
+    flags: &[u8],
+    settings: &HexOutSettings,
+    shown: &[Range<usize>],
+) -> Result<String, HexOutError> {
+    let line_settings = HexOutSettings {
+        caption: None,
+        show_header: false,
+        borders: Borders::None,
+        rule_interval: 0,
+        show_truncation: false,
+        line_decoder: None,
+        show_end_address: false,
+        byte_histogram: ByteHistogram::None,
+        ..settings.clone()
+    };
+    let total_lines = data.len().div_ceil(settings.group_size * settings.groups_per_line);
+    let mut result = String::new();
+    let mut next_line = 0;
+    for lines in shown {
+        if result.is_empty() {
+            if let Some(caption) = &settings.caption {
+                result.push_str(&expand_caption(caption, data.len(), 0, settings));
+                result.push('\n');
+            }
+            if settings.show_header {
+                render_header(settings, &mut result);
+                result.push('\n');
+            }
+        } else {
+            result.push('\n');
+        }
+        if settings.show_truncation && lines.start > next_line {
+            push_truncation(&line_settings, lines.start - next_line, "unchanged", &mut result);
+            result.push('\n');
+        }
+        let context = RenderContext { flags, ..Default::default() };
+        result.push_str(&render_dump(data.into(), context, &line_settings, 0, lines.start, lines.len())?);
+        next_line = lines.end;
+    }
+    if settings.show_truncation && !shown.is_empty() && next_line < total_lines {
+        result.push('\n');
+        push_truncation(&line_settings, total_lines - next_line, "unchanged", &mut result);
+    }
+    Ok(result)
+}
@@ -0,0 +1,41 @@
+/// Number of bytes hashed at a time by [`similarity_digest`].
+const WINDOW: usize = 4;
+
+/// Multiplier of the polynomial rolling hash.
+const BASE: u32 = 0x0100_0193;
+
+/// A digest of `bytes` that stays the same when they shift by a few bytes, shown per line by
+/// [`Column::Digest`](crate::Column::Digest) so two dumps can be matched even where data was inserted or removed.
+///
+/// Every run of four bytes is hashed with a rolling hash, and the smallest of those hashes is the digest (a min-hash):
+/// lines sharing most of their runs of bytes, as they do when shifted by a few bytes, most likely share it.  Fewer than
+/// four bytes are hashed as a whole, and no bytes have a digest of 0.
+/// ```rust
+/// use hexout::similarity_digest;
+/// let text = b"the quick brown fox jumps over the lazy dog";
+/// assert_eq!(similarity_digest(&text[..16]), similarity_digest(&text[2..18]));
+/// assert_ne!(similarity_digest(&text[..16]), similarity_digest(&text[16..32]));
+/// ```
+pub fn similarity_digest(bytes: &[u8]) -> u32 {
+    if bytes.is_empty() {
+        return 0;
+    }
+    let window = WINDOW.min(bytes.len());
+    // Weight of the byte leaving the window
+    let leaving = BASE.wrapping_pow(window as u32 - 1);
+    let mut hash = bytes[..window].iter().fold(0u32, |hash, byte| hash.wrapping_mul(BASE).wrapping_add(*byte as u32));
+    let mut digest = mix(hash);
+    for (index, byte) in bytes[window..].iter().enumerate() {
+        hash = hash.wrapping_sub(leaving.wrapping_mul(bytes[index] as u32));
+        hash = hash.wrapping_mul(BASE).wrapping_add(*byte as u32);
+        digest = digest.min(mix(hash));
+    }
+    digest
+}
+
+/// Spread the bits of a rolling hash, so the smallest hash doesn't favor runs of small bytes.
+fn mix(hash: u32) -> u32 {
+    let hash = (hash ^ (hash >> 16)).wrapping_mul(0x7feb_352d);
+    let hash = (hash ^ (hash >> 15)).wrapping_mul(0x846c_a68b);
+    hash ^ (hash >> 16)
+}
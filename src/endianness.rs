@@ -0,0 +1,74 @@
+/// Byte order of the values in a group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// Least significant byte first.
+    #[default]
+    Little,
+    /// Most significant byte first.
+    Big,
+    /// PDP-11 (middle-endian) order: 16-bit words most significant first, each word least significant byte first, so
+    /// `0x0a0b0c0d` is stored as `0b 0a 0d 0c`.  A trailing odd byte is taken as is.
+    Pdp,
+    /// Guessed from the dumped data with [`guess_endianness`].  Where there is no data to guess from (e.g. in
+    /// [`byte_offset`](Self::byte_offset) or for the fields of a [`Layout`](crate::Layout)) it is taken as
+    /// little-endian.
+    Auto,
+}
+
+impl Endianness {
+    /// Offset within a group of `group_size` bytes of the byte at `position`, counting from the most significant byte.
+    pub fn byte_offset(self, position: usize, group_size: usize) -> usize {
+        match self {
+            Endianness::Little | Endianness::Auto => group_size - 1 - position,
+            Endianness::Big => position,
+            Endianness::Pdp if position ^ 1 < group_size => position ^ 1,
+            Endianness::Pdp => position,
+        }
+    }
+
+    /// Value of the group `bytes` (at most 16 of them) in this byte order.
+    #[inline]
+    pub(crate) fn read(self, bytes: &[u8]) -> u128 {
+        let mut buffer = [0; 16];
+        let len = bytes.len();
+        match self {
+            Endianness::Little | Endianness::Auto => {
+                buffer[..len].copy_from_slice(bytes);
+                u128::from_le_bytes(buffer)
+            }
+            Endianness::Big => {
+                buffer[16 - len..].copy_from_slice(bytes);
+                u128::from_be_bytes(buffer)
+            }
+            Endianness::Pdp => {
+                // Swapping the bytes of each word gives the bytes most significant first
+                buffer[16 - len..].copy_from_slice(bytes);
+                buffer[16 - len..].chunks_exact_mut(2).for_each(|word| word.swap(0, 1));
+                u128::from_be_bytes(buffer)
+            }
+        }
+    }
+}
+
+/// Guess the byte order of the values of `group_size` bytes making up `data`, e.g. to inspect dumps of unknown 16- or
+/// 32-bit data.
+///
+/// Small values are far more common than large ones, so the most significant byte of a value tends to be zero: if the
+/// first byte of the groups is zero more often than their last one the values are taken as big-endian, otherwise as
+/// little-endian.  Bytes not filling a group at the end are ignored, and single bytes are taken as little-endian.
+/// ```rust
+/// use hexout::{guess_endianness, Endianness};
+/// assert_eq!(guess_endianness(&[0x00, 0x2a, 0x01, 0x00, 0x00, 0x07], 2), Endianness::Big);
+/// assert_eq!(guess_endianness(&[0x2a, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00], 4), Endianness::Little);
+/// ```
+pub fn guess_endianness(data: &[u8], group_size: usize) -> Endianness {
+    if group_size < 2 {
+        return Endianness::Little;
+    }
+    let (mut first, mut last) = (0, 0);
+    for group in data.chunks_exact(group_size) {
+        first += usize::from(group[0] == 0);
+        last += usize::from(group[group_size - 1] == 0);
+    }
+    if first > last { Endianness::Big } else { Endianness::Little }
+}
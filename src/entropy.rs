@@ -0,0 +1,118 @@
+use std::ops::Range;
+
+use crate::{hex_out_annotated, Annotation, HexOutError, HexOutSettings};
+
+/// Label of the annotations of [`high_entropy_regions`].
+const HIGH_ENTROPY_LABEL: &str = "likely compressed/encrypted";
+
+/// Shannon entropy of `bytes` in bits per byte, from 0 (a single byte value repeated) to 8 (all byte values equally
+/// frequent).
+/// ```rust
+/// use hexout::entropy;
+/// assert_eq!(entropy(&[0; 64]), 0.0);
+/// assert_eq!(entropy(&(0..=255).collect::<Vec<u8>>()), 8.0);
+/// ```
+pub fn entropy(bytes: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    bytes.iter().for_each(|byte| counts[*byte as usize] += 1);
+    let total = bytes.len() as f64;
+    let entropy = counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let probability = *count as f64 / total;
+            -probability * probability.log2()
+        })
+        .sum::<f64>();
+    // Avoid -0.0 for uniform data
+    entropy.max(0.0)
+}
+
+/// Find the regions of `data` whose entropy reaches `threshold` bits per byte, which are likely compressed or
+/// encrypted, and annotate each of them with its entropy, e.g. `likely compressed/encrypted=7.61 bits/byte`.
+///
+/// The data is measured in windows of `window` bytes (at least 1), and neighbouring windows at or above the threshold
+/// merge into one region.  Windows can only reach `log2(window)` bits per byte, and random data in windows of 256
+/// bytes measures about 7.2, so a threshold of 7 suits windows of 256 bytes or more.
+pub fn high_entropy_regions(data: &[u8], window: usize, threshold: f64) -> Vec<Annotation> {
+    let window = window.max(1);
+    let mut regions: Vec<Range<usize>> = Vec::new();
+    for (index, chunk) in data.chunks(window).enumerate() {
+        if entropy(chunk) < threshold {
+            continue;
+        }
+        let start = index * window;
+        match regions.last_mut() {
+            Some(region) if region.end == start => region.end = start + chunk.len(),
+            _ => regions.push(start..start + chunk.len()),
+        }
+    }
+    regions
+        .into_iter()
+        .map(|region| {
+            let value = format!("{} bits/byte", entropy_text(&data[region.clone()]));
+            Annotation::new(region, HIGH_ENTROPY_LABEL).with_value(value)
+        })
+        .collect()
+}
+
+/// The entropy of `bytes` with two decimals, from the platform's floating-point logarithm.
+#[cfg(not(feature = "stable-format"))]
+fn entropy_text(bytes: &[u8]) -> String {
+    format!("{:.2}", entropy(bytes))
+}
+
+/// The entropy of `bytes` with two decimals, computed in integer arithmetic so the digits are the same on every
+/// platform: `log2(n) - Σ c·log2(c) / n` for `n` bytes with value counts `c`, in fixed point with 32 fractional bits.
+#[cfg(feature = "stable-format")]
+fn entropy_text(bytes: &[u8]) -> String {
+    let mut counts = [0u64; 256];
+    bytes.iter().for_each(|byte| counts[*byte as usize] += 1);
+    let total = bytes.len() as u64;
+    if total == 0 {
+        return "0.00".to_string();
+    }
+    let sum = counts.iter().map(|count| *count as u128 * log2_fixed(*count)).sum::<u128>();
+    let entropy = (total as u128 * log2_fixed(total)).saturating_sub(sum) / total as u128;
+    let hundredths = (entropy * 100 + (1 << 31)) >> 32;
+    format!("{}.{:02}", hundredths / 100, hundredths % 100)
+}
+
+/// `log2(value)` in fixed point with 32 fractional bits (0 for 0), the fraction found bit by bit by squaring the
+/// mantissa.
+#[cfg(feature = "stable-format")]
+fn log2_fixed(value: u64) -> u128 {
+    if value == 0 {
+        return 0;
+    }
+    let integer = value.ilog2();
+    // The mantissa in [1, 2) with 62 fractional bits
+    let mut mantissa = ((value as u128) << 62) >> integer;
+    let mut log = (integer as u128) << 32;
+    for bit in (0..32).rev() {
+        mantissa = (mantissa * mantissa) >> 62;
+        if mantissa >= 2 << 62 {
+            mantissa >>= 1;
+            log |= 1 << bit;
+        }
+    }
+    log
+}
+
+/// Generate a hex dump with the likely compressed or encrypted regions found by [`high_entropy_regions`] annotated,
+/// see [`hex_out_annotated`].
+///
+/// `window` and `threshold` are the same as for [`high_entropy_regions`]; all other parameters are the same as for
+/// [`hex_out`](crate::hex_out).
+pub fn hex_out_entropy(
+    data: &[u8],
+    window: usize,
+    threshold: f64,
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<String, HexOutError> {
+    let regions = high_entropy_regions(data, window, threshold);
+    hex_out_annotated(data, &regions, settings, offset, start_line, line_count)
+}
@@ -0,0 +1,99 @@
+use crate::{push_spaces, visible_width, DumpLines, DumpStage, HexOutError, HexOutSettings, RenderContext};
+
+/// Spaces between the columns of a folded dump.
+const GAP: usize = 4;
+
+/// Generate a hex dump folded into `columns` side-by-side columns, so a wide terminal shows as many times the lines.
+///
+/// The lines are those of [`hex_out`](crate::hex_out), each with its own address, filled into the columns top to
+/// bottom and then left to right, as by `pr -2`, with the columns as even in height as possible.  Unlike `pr`, the
+/// folding follows the layout of the dump: the caption stays above all columns, each column gets the header and the
+/// frame of `borders`, and the notes on the lines left out above and below the line window (and the end address)
+/// stay with the first and the last line.  The columns are padded to the widest line and separated by four spaces.
+/// Fewer columns are used when there are not enough lines to fill them, and a `columns` of 0 is taken as 1.
+///
+/// All other parameters are the same as for [`hex_out`](crate::hex_out).
+/// ```rust
+/// use hexout::{hex_out_folded, HexOutSettings};
+/// let settings = HexOutSettings { groups_per_line: 4, ..Default::default() };
+/// assert_eq!(
+///     hex_out_folded(b"folded in two", &settings, 2, 0, 0, 0).unwrap(),
+///     "00000000: 66 6f  6c 64 |fo ld|    00000008: 6e 20  74 77 |n  tw|\n\
+///      00000004: 65 64  20 69 |ed  i|    0000000c: 6f           |o    |"
+/// );
+/// ```
+pub fn hex_out_folded(
+    data: &[u8],
+    settings: &HexOutSettings,
+    columns: usize,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<String, HexOutError> {
+    let mut lines = DumpLines::new(data.into(), RenderContext::default(), settings, offset, start_line, line_count)?;
+    let mut caption = Vec::new();
+    let mut head = Vec::new();
+    let mut foot = Vec::new();
+    // The lines of the body in units kept in one column: the notes of truncated lines go with the lines next to them
+    let mut body: Vec<Vec<String>> = Vec::new();
+    let mut note_above = false;
+    loop {
+        let mut line = String::new();
+        if !lines.next_line(&mut line) {
+            break;
+        }
+        match (lines.stage, body.last_mut()) {
+            (DumpStage::TopRule, _) => caption.push(line),
+            (DumpStage::Header | DumpStage::HeaderRule | DumpStage::LinesAbove, None) => head.push(line),
+            (DumpStage::Done, _) => foot.push(line),
+            (DumpStage::LinesBelow | DumpStage::Histogram | DumpStage::BottomRule, Some(unit)) => unit.push(line),
+            (_, Some(unit)) if note_above => unit.push(line),
+            _ => body.push(vec![line]),
+        }
+        note_above = lines.current_line.is_none() && lines.lines_rendered + lines.lines_dropped == 0;
+    }
+    let rows = body.iter().map(Vec::len).sum::<usize>();
+    let columns = columns.clamp(1, body.len().max(1));
+    // The lowest height fitting all units into the columns
+    let mut height = rows.div_ceil(columns);
+    let folded = loop {
+        let mut folded = Vec::new();
+        let mut column: Vec<&String> = Vec::new();
+        for unit in &body {
+            if !column.is_empty() && column.len() + unit.len() > height {
+                folded.push(std::mem::take(&mut column));
+            }
+            column.extend(unit);
+        }
+        folded.push(column);
+        if folded.len() <= columns {
+            break folded;
+        }
+        height += 1;
+    };
+    let width = head.iter().chain(body.iter().flatten()).chain(&foot).map(|line| visible_width(line)).max().unwrap_or(0);
+    let mut result = Vec::with_capacity(caption.len() + head.len() + height + foot.len());
+    result.extend(caption);
+    let fold_row = |cells: Vec<Option<&String>>| {
+        let last = cells.iter().rposition(Option::is_some).unwrap_or(0);
+        let mut row = String::new();
+        for (index, cell) in cells.into_iter().enumerate().take(last + 1) {
+            let cell = cell.map_or("", String::as_str);
+            row.push_str(cell);
+            if index < last {
+                push_spaces(&mut row, width.saturating_sub(visible_width(cell)) + GAP);
+            }
+        }
+        row
+    };
+    for line in &head {
+        result.push(fold_row(folded.iter().map(|_| Some(line)).collect()));
+    }
+    for index in 0..folded.iter().map(Vec::len).max().unwrap_or(0) {
+        result.push(fold_row(folded.iter().map(|column| column.get(index).copied()).collect()));
+    }
+    for line in &foot {
+        result.push(fold_row(folded.iter().map(|_| Some(line)).collect()));
+    }
+    Ok(result.join("\n"))
+}
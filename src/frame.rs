@@ -0,0 +1,129 @@
+use crate::{ascii_area_width, line_width, push_column_labels, push_spaces, Column, HexOutSettings};
+
+/// Style of the borders drawn around a dump and between its columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Borders {
+    /// No borders: the address is followed by `:` and the ASCII column is enclosed in `|`.
+    #[default]
+    None,
+    /// Borders drawn with `+`, `-` and `|`, e.g. for plain text documentation.
+    Ascii,
+    /// Borders drawn with Unicode box-drawing characters.
+    Unicode,
+}
+
+impl Borders {
+    /// Character separating columns, `None` if there are no borders.
+    pub(crate) fn vertical(self) -> Option<char> {
+        match self {
+            Borders::None => None,
+            Borders::Ascii => Some('|'),
+            Borders::Unicode => Some('│'),
+        }
+    }
+
+    fn horizontal(self) -> char {
+        match self {
+            Borders::None | Borders::Ascii => '-',
+            Borders::Unicode => '─',
+        }
+    }
+
+    /// Left end, column junction and right end of a rule.
+    fn corners(self, rule: Rule) -> (char, char, char) {
+        match (self, rule) {
+            (Borders::None | Borders::Ascii, _) => ('+', '+', '+'),
+            (Borders::Unicode, Rule::Top) => ('┌', '┬', '┐'),
+            (Borders::Unicode, Rule::Middle) => ('├', '┼', '┤'),
+            (Borders::Unicode, Rule::Bottom) => ('└', '┴', '┘'),
+        }
+    }
+}
+
+/// A horizontal line of the frame.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rule {
+    /// Above the first row.
+    Top,
+    /// Between the header and the lines, and between groups of lines.
+    Middle,
+    /// Below the last row.
+    Bottom,
+}
+
+/// The columns within the borders with their widths.
+fn framed_columns(settings: &HexOutSettings) -> Vec<(Column, usize)> {
+    settings
+        .effective_columns()
+        .into_iter()
+        .filter(|column| column.is_fixed_width())
+        .map(|column| match column {
+            Column::Ascii => (column, ascii_area_width(settings)),
+            _ => (column, column.width(settings)),
+        })
+        .collect()
+}
+
+/// Append a horizontal rule to `out`.  Without borders this is a plain line of dashes as wide as a dump line.
+pub(crate) fn push_rule(settings: &HexOutSettings, rule: Rule, out: &mut String) {
+    let borders = settings.borders;
+    let horizontal = borders.horizontal();
+    if borders == Borders::None {
+        out.extend(std::iter::repeat_n(horizontal, line_width(settings)));
+        return;
+    }
+    let (left, junction, right) = borders.corners(rule);
+    out.push(left);
+    for (index, (_, width)) in framed_columns(settings).into_iter().enumerate() {
+        if index > 0 {
+            out.push(junction);
+        }
+        out.extend(std::iter::repeat_n(horizontal, width + 2));
+    }
+    out.push(right);
+}
+
+/// Append the framed column header row to `out`, with the column separators drawn by `vertical`.
+pub(crate) fn push_header_row(settings: &HexOutSettings, vertical: char, out: &mut String) {
+    out.push(vertical);
+    out.push(' ');
+    for (index, (column, width)) in framed_columns(settings).into_iter().enumerate() {
+        if index > 0 {
+            push_separator(out, vertical);
+        }
+        let label = match column {
+            Column::LineNumber if width >= 4 => "Line",
+            Column::LineNumber => "#",
+            Column::Address if width >= 7 => "Address",
+            Column::Address if width >= 4 => "Addr",
+            Column::Hex => {
+                push_column_labels(settings, out);
+                continue;
+            }
+            _ => "",
+        };
+        out.push_str(label);
+        push_spaces(out, width.saturating_sub(label.chars().count()));
+    }
+    out.push(' ');
+    out.push(vertical);
+}
+
+/// Append a framed row with `text` spanning all columns to `out`.
+pub(crate) fn push_text_row(settings: &HexOutSettings, vertical: char, text: &str, out: &mut String) {
+    let columns = framed_columns(settings);
+    let width = columns.iter().map(|(_, width)| width).sum::<usize>() + 3 * columns.len().saturating_sub(1);
+    out.push(vertical);
+    out.push(' ');
+    out.push_str(text);
+    push_spaces(out, width.saturating_sub(text.chars().count()));
+    out.push(' ');
+    out.push(vertical);
+}
+
+/// Append a separator between two framed columns to `out`.
+pub(crate) fn push_separator(out: &mut String, vertical: char) {
+    out.push(' ');
+    out.push(vertical);
+    out.push(' ');
+}
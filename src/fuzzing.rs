@@ -0,0 +1,155 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{
+    AddressMap, Borders, ByteHistogram, Column, Endianness, GroupRadix, HexOutSettings, LineAnnotator, LineDecoder,
+    LineFilter, Style, StyledAreas, TextMode,
+};
+
+const ENDIANNESSES: [Endianness; 4] = [Endianness::Little, Endianness::Big, Endianness::Pdp, Endianness::Auto];
+const BORDERS: [Borders; 3] = [Borders::None, Borders::Ascii, Borders::Unicode];
+const BYTE_HISTOGRAMS: [ByteHistogram; 3] = [ByteHistogram::None, ByteHistogram::Buckets16, ByteHistogram::Buckets32];
+const GROUP_RADICES: [GroupRadix; 3] = [GroupRadix::Hex, GroupRadix::Octal, GroupRadix::Decimal];
+const TEXT_MODES: [TextMode; 4] = [
+    TextMode::Ascii,
+    TextMode::Escaped,
+    TextMode::Hexified { uppercase: false },
+    TextMode::Hexified { uppercase: true },
+];
+const STYLED_AREAS: [StyledAreas; 4] =
+    [StyledAreas::Standard, StyledAreas::Both, StyledAreas::HexOnly, StyledAreas::AsciiOnly];
+const COLUMNS: [Column; 10] = [
+    Column::LineNumber,
+    Column::Address,
+    Column::Hex,
+    Column::Ascii,
+    Column::Base64,
+    Column::Checksum,
+    Column::Digest,
+    Column::ByteCount,
+    Column::Decoded,
+    Column::Annotation,
+];
+
+/// Settings passing validation, so fuzzers exercise the renderer rather than the argument checks.
+///
+/// Only `strict` can still make a dump fail, for offsets not aligned to the group size.  Addresses stay within 64
+/// bits, so they can't overflow for any data in memory.
+impl<'a> Arbitrary<'a> for HexOutSettings {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let group_size = u.int_in_range(1..=16)?;
+        Ok(HexOutSettings {
+            address_origin: u64::arbitrary(u)? as u128,
+            address_width: u.int_in_range(2..=32)?,
+            align_address: u.arbitrary()?,
+            endianness: *u.choose(&ENDIANNESSES)?,
+            group_size,
+            groups_per_line: u.int_in_range(1..=32)?,
+            invalid_data_placeholder: u.arbitrary()?,
+            show_ascii: u.arbitrary()?,
+            show_centerline: u.arbitrary()?,
+            show_header: u.arbitrary()?,
+            show_offset: u.arbitrary()?,
+            strict: u.arbitrary()?,
+            uppercase: u.arbitrary()?,
+            caption: u.arbitrary()?,
+            line_annotator: if u.arbitrary()? { Some(arbitrary_annotator(u)?) } else { None },
+            hex_out_error_prefix: u.arbitrary()?,
+            hex_out_error_postfix: u.arbitrary()?,
+            cursor: u.arbitrary()?,
+            cursor_style: arbitrary_style(u)?,
+            selection: u.arbitrary()?,
+            selection_style: arbitrary_style(u)?,
+            highlights: u.arbitrary()?,
+            highlight_style: arbitrary_style(u)?,
+            flag_marker: u.arbitrary()?,
+            flag_style: arbitrary_style(u)?,
+            address_style: arbitrary_style(u)?,
+            address_link: u.arbitrary()?,
+            error_style: arbitrary_style(u)?,
+            borders: *u.choose(&BORDERS)?,
+            rule_interval: u.int_in_range(0..=8)?,
+            pad_lines: u.arbitrary()?,
+            pad_last_line: u.arbitrary()?,
+            show_byte_count: u.arbitrary()?,
+            show_line_number: u.arbitrary()?,
+            show_truncation: u.arbitrary()?,
+            header_interval: u.int_in_range(0..=8)?,
+            page_interval: u.int_in_range(0..=8)?,
+            page_separator: u.arbitrary()?,
+            columns: u
+                .arbitrary_iter::<u8>()?
+                .map(|index| Ok(COLUMNS[index? as usize % COLUMNS.len()]))
+                .collect::<Result<_>>()?,
+            inline_chars: u.arbitrary()?,
+            group_prefix: u.arbitrary()?,
+            group_suffix: u.arbitrary()?,
+            group_endianness: u
+                .arbitrary_iter::<u8>()?
+                .map(|index| Ok(ENDIANNESSES[index? as usize % ENDIANNESSES.len()]))
+                .collect::<Result<_>>()?,
+            dual_endianness: u.arbitrary()?,
+            stripe_style: arbitrary_style(u)?,
+            stripe_groups: u.arbitrary()?,
+            ascii_placeholder: u.arbitrary()?,
+            text_mode: *u.choose(&TEXT_MODES)?,
+            ascii_open: u.arbitrary()?,
+            ascii_close: u.arbitrary()?,
+            line_decoder: if u.arbitrary()? { Some(arbitrary_decoder(u)?) } else { None },
+            show_base64: u.arbitrary()?,
+            binary: u.arbitrary()?,
+            group_radix: *u.choose(&GROUP_RADICES)?,
+            line_filter: if u.arbitrary()? { Some(arbitrary_filter(u)?) } else { None },
+            skip_fill: u.arbitrary()?,
+            address_map: if u.arbitrary()? { Some(arbitrary_map(u)?) } else { None },
+            guide_interval: u.int_in_range(0..=16)?,
+            guide_char: u.arbitrary()?,
+            guide_style: arbitrary_style(u)?,
+            styled_areas: *u.choose(&STYLED_AREAS)?,
+            address_separator: u.arbitrary()?,
+            pad_last_ascii: u.arbitrary()?,
+            ascii_centerline: u.arbitrary()?,
+            squeeze_repeats: u.arbitrary()?,
+            show_end_address: u.arbitrary()?,
+            byte_histogram: *u.choose(&BYTE_HISTOGRAMS)?,
+        })
+    }
+}
+
+fn arbitrary_style(u: &mut Unstructured) -> Result<Option<Style>> {
+    let style: Option<(String, String)> = u.arbitrary()?;
+    Ok(style.map(|(prefix, suffix)| Style::new(prefix, suffix)))
+}
+
+/// An annotator noting the line offset and a fixed text, or nothing on some lines.
+fn arbitrary_annotator(u: &mut Unstructured) -> Result<LineAnnotator> {
+    let text: String = u.arbitrary()?;
+    let every: usize = u.int_in_range(1..=4)?;
+    Ok(LineAnnotator::new(move |line_offset, line_bytes| {
+        (line_offset % every == 0).then(|| format!("{line_offset:x} {} {text}", line_bytes.len()))
+    }))
+}
+
+/// A filter dropping lines without valid bytes and appending the line offset and a fixed text to the others.
+fn arbitrary_filter(u: &mut Unstructured) -> Result<LineFilter> {
+    let text: String = u.arbitrary()?;
+    Ok(LineFilter::new(move |line_offset, line_bytes, line| {
+        (!line_bytes.is_empty()).then(|| format!("{line} {line_offset:x} {text}"))
+    }))
+}
+
+/// A map moving offsets past a boundary to a base address, as for a loaded section.
+fn arbitrary_map(u: &mut Unstructured) -> Result<AddressMap> {
+    let (boundary, base): (usize, u64) = u.arbitrary()?;
+    Ok(AddressMap::new(move |offset| {
+        if offset >= boundary { base.wrapping_add((offset - boundary) as u64) } else { offset as u64 }
+    }))
+}
+
+/// A decoder taking records of the length given by their first byte (possibly 0 or past the end of the data), or
+/// leaving bytes with the high bit set undecoded.
+fn arbitrary_decoder(u: &mut Unstructured) -> Result<LineDecoder> {
+    let text: String = u.arbitrary()?;
+    Ok(LineDecoder::new(move |bytes, address| {
+        (bytes[0] < 0x80).then(|| (bytes[0] as usize, format!("{address:x} {text}")))
+    }))
+}
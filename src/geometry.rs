@@ -0,0 +1,92 @@
+use std::ops::Range;
+
+use crate::{ascii_area_width, columns::ordered_columns, visible_width, Column, HexOutSettings};
+
+/// Width of a full dump line in characters, including the borders if drawing them but not the columns of variable
+/// width (e.g. line annotations), which are appended after it.
+///
+/// Only visible characters are counted: style prefixes and suffixes and hyperlink escape sequences take no columns.
+/// ```rust
+/// use hexout::{line_width, HexOutSettings};
+/// assert_eq!(line_width(&HexOutSettings::default()), 78);
+/// ```
+pub fn line_width(settings: &HexOutSettings) -> usize {
+    column_spans(settings).1
+}
+
+/// The number of groups per line, up to `groups_per_line`, for which lines fit in `max_line_width` characters (see
+/// [`line_width`]), e.g. to fit a dump to the width of a terminal or a log viewer.  At least one group is always
+/// shown, so the lines may still be wider.
+/// ```rust
+/// use hexout::{groups_per_line_for_width, HexOutSettings};
+/// assert_eq!(groups_per_line_for_width(&HexOutSettings::default(), 80), 16);
+/// assert_eq!(groups_per_line_for_width(&HexOutSettings::default(), 60), 11);
+/// ```
+pub fn groups_per_line_for_width(settings: &HexOutSettings, max_line_width: usize) -> usize {
+    let mut settings = settings.clone();
+    while settings.groups_per_line > 1 && line_width(&settings) > max_line_width {
+        settings.groups_per_line -= 1;
+    }
+    settings.groups_per_line
+}
+
+/// Character columns of the hex area within a line (from the first group to the end of the last one on a full line),
+/// `None` if the hex column is not shown.
+/// ```rust
+/// use hexout::{hex_area_span, HexOutSettings};
+/// assert_eq!(hex_area_span(&HexOutSettings::default()), Some(10..58));
+/// ```
+pub fn hex_area_span(settings: &HexOutSettings) -> Option<Range<usize>> {
+    column_span(settings, Column::Hex)
+}
+
+/// Character columns of the ASCII area within a line, excluding `ascii_open` and `ascii_close` or the borders, `None`
+/// if the ASCII column is not shown.
+/// ```rust
+/// use hexout::{ascii_area_span, HexOutSettings};
+/// assert_eq!(ascii_area_span(&HexOutSettings::default()), Some(60..77));
+/// ```
+pub fn ascii_area_span(settings: &HexOutSettings) -> Option<Range<usize>> {
+    column_span(settings, Column::Ascii)
+}
+
+fn column_span(settings: &HexOutSettings, column: Column) -> Option<Range<usize>> {
+    column_spans(settings).0.into_iter().find(|(spanned, _)| *spanned == column).map(|(_, span)| span)
+}
+
+/// Character columns taken by the fixed width columns of a line, in the order they are rendered, and the width of the
+/// line.
+pub(crate) fn column_spans(settings: &HexOutSettings) -> (Vec<(Column, Range<usize>)>, usize) {
+    let framed = settings.borders.vertical().is_some();
+    // Borders start with the left edge and a space
+    let mut position = if framed { 2 } else { 0 };
+    let mut previous: Option<Column> = None;
+    let mut spans = Vec::new();
+    for column in ordered_columns(settings).into_iter().filter(|column| column.is_fixed_width()) {
+        match previous {
+            Some(_) if framed => position += 3,
+            Some(previous) => position += visible_width(previous.separator(column, settings)),
+            None => {}
+        }
+        let span = match column {
+            Column::Ascii if framed => position..position + ascii_area_width(settings),
+            // The ASCII characters are enclosed in `ascii_open` and `ascii_close`
+            Column::Ascii => {
+                let start = position + visible_width(&settings.ascii_open);
+                start..start + ascii_area_width(settings)
+            }
+            _ => position..position + column.width(settings),
+        };
+        position = match column {
+            Column::Ascii if !framed => span.end + visible_width(&settings.ascii_close),
+            _ => span.end,
+        };
+        spans.push((column, span));
+        previous = Some(column);
+    }
+    // Borders end with a space and the right edge
+    if framed {
+        position += 2;
+    }
+    (spans, position)
+}
@@ -0,0 +1,49 @@
+/// Bars of the sparkline of [`ByteHistogram`], from the least to the most frequent byte values.
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Whether to end a dump with a histogram of its byte values, drawn as a sparkline (see
+/// [`HexOutSettings::byte_histogram`](crate::HexOutSettings::byte_histogram)).
+///
+/// The byte values are split into 16 or 32 buckets of equal range, each drawn as a bar from `▁` to `█` scaled to the
+/// most frequent bucket, or as a space for no bytes at all.  This tells text (bars in the middle buckets only) from
+/// binary data (a tall bar at 00) and compressed or encrypted data (bars of even height) at a glance, e.g. for a line
+/// of English text:
+/// ```text
+/// 00   ▅▄ ▁█▅         ff
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ByteHistogram {
+    /// No histogram.
+    #[default]
+    None,
+    /// 16 buckets of 16 byte values each, one per high nibble.
+    Buckets16,
+    /// 32 buckets of 8 byte values each.
+    Buckets32,
+}
+
+impl ByteHistogram {
+    /// Number of buckets drawn, 0 for no histogram.
+    fn buckets(self) -> usize {
+        match self {
+            ByteHistogram::None => 0,
+            ByteHistogram::Buckets16 => 16,
+            ByteHistogram::Buckets32 => 32,
+        }
+    }
+
+    /// The sparkline of the bytes counted in `counts` (indexed by byte value), between the first and the last byte
+    /// value.
+    pub(crate) fn sparkline(self, counts: &[usize; 256]) -> String {
+        let buckets = self.buckets();
+        let totals = counts.chunks(256 / buckets.max(1)).take(buckets).map(|chunk| chunk.iter().sum());
+        let totals = totals.collect::<Vec<usize>>();
+        let max = totals.iter().copied().max().unwrap_or(0);
+        let bars = totals.iter().map(|total| match total {
+            0 => ' ',
+            // Any bytes at all show at least the lowest bar
+            _ => BARS[(total * BARS.len()).div_ceil(max) - 1],
+        });
+        format!("00 {} ff", bars.collect::<String>())
+    }
+}
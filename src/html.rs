@@ -0,0 +1,207 @@
+use std::fmt::Write;
+
+use crate::{
+    columns::address_position, display_address, expand_caption, strip_ansi, Annotation, DumpLines, HexOutError,
+    HexOutSettings, RenderContext, Style,
+};
+
+/// Number of distinct annotation colors in the report's stylesheet.
+const ANNOTATION_COLORS: usize = 6;
+
+const STYLESHEET: &str = "\
+body { font-family: sans-serif; margin: 2em; }
+pre.dump { font-family: monospace; line-height: 1.3; }
+.line:target { background: #ffef9f; }
+a.addr { color: #666; text-decoration: none; }
+a.ann { color: inherit; text-decoration: none; }
+.ann0 { background: #cfe8ff; } .ann1 { background: #ffd9cf; } .ann2 { background: #d6f5d6; }
+.ann3 { background: #f3dcff; } .ann4 { background: #fff2c2; } .ann5 { background: #d4f1f1; }
+.err { color: #c00; } .cursor { outline: 1px solid #000; } .sel { background: #b3d4ff; }
+.hl { background: #ffe066; } .flag { color: #c60; font-weight: bold; } .stripe { background: #f0f0f0; }
+.guide { color: #ccc; }
+ol.annotations li:target { background: #ffef9f; }
+ol.annotations .value { font-family: monospace; }
+";
+
+/// A self-contained HTML report of one or more hex dumps, for sharing binary analysis results.
+///
+/// Each section is a collapsible region with its own dump and annotation list.  Every line has an anchor derived
+/// from its address (e.g. `#off-0000a0`), annotated bytes link to their entry in the annotation list, and list entries
+/// link back to the line where the annotation starts.  Annotated bytes carry the label and value of their annotation
+/// in `data-label` and `data-value` attributes, and as a `title` tooltip shown when hovering them.
+/// ```rust
+/// use hexout::{Annotation, HexOutSettings, HtmlReport};
+/// let data = (0u8..64).collect::<Vec<u8>>();
+/// let annotations = [Annotation::new(4..8, "length").with_value("0x07060504")];
+/// let mut report = HtmlReport::new("Packet capture");
+/// report.add_section("Frame 1", &data, &annotations, &HexOutSettings::default(), true).unwrap();
+/// let html = report.finish();
+/// assert!(html.contains("id=\"off-000010\""));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HtmlReport {
+    title: String,
+    body: String,
+    sections: usize,
+}
+
+impl HtmlReport {
+    /// Start an empty report with the given document title.
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            body: String::new(),
+            sections: 0,
+        }
+    }
+
+    /// Add a collapsible section dumping `data` with `annotations` overlaid, initially expanded if `open` is set.
+    ///
+    /// ANSI-oriented styles of the settings are replaced by CSS classes; the caption, if any, is shown above the dump.
+    pub fn add_section(
+        &mut self,
+        title: &str,
+        data: &[u8],
+        annotations: &[Annotation],
+        settings: &HexOutSettings,
+        open: bool,
+    ) -> Result<&mut Self, HexOutError> {
+        // Anchors of later sections are prefixed so they stay unique within the document
+        let prefix = if self.sections == 0 { String::new() } else { format!("s{}-", self.sections) };
+        let overlays = annotations
+            .iter()
+            .enumerate()
+            .map(|(index, annotation)| {
+                // Hovering an annotated byte shows the field name and decoded value
+                let label = escape(&annotation.label);
+                let (title, data_value) = match &annotation.value {
+                    Some(value) => {
+                        let value = escape(value);
+                        (format!("{label} = {value}"), format!(" data-value=\"{value}\""))
+                    }
+                    None => (label.clone(), String::new()),
+                };
+                let style = Style::new(
+                    format!(
+                        "<a class=\"ann ann{}\" href=\"#{prefix}ann-{index}\" title=\"{title}\" data-label=\"{label}\"{data_value}>",
+                        index % ANNOTATION_COLORS
+                    ),
+                    "</a>",
+                );
+                (annotation.range.clone(), style)
+            })
+            .collect::<Vec<_>>();
+        let caption = settings.caption.as_ref().map(|caption| expand_caption(caption, data.len(), 0, settings));
+        let class = |name: &str| Some(Style::new(format!("<span class=\"{name}\">"), "</span>"));
+        let settings = HexOutSettings {
+            caption: None,
+            address_style: None,
+            address_link: None,
+            error_style: class("err"),
+            hex_out_error_prefix: None,
+            hex_out_error_postfix: None,
+            cursor_style: class("cursor"),
+            selection_style: class("sel"),
+            highlight_style: class("hl"),
+            flag_style: class("flag"),
+            stripe_style: settings.stripe_style.as_ref().and(class("stripe")),
+            guide_style: class("guide"),
+            ascii_open: strip_ansi(&settings.ascii_open),
+            ascii_close: strip_ansi(&settings.ascii_close),
+            ..settings.clone()
+        };
+        let context = RenderContext {
+            overlays: &overlays,
+            escape_html: true,
+            ..Default::default()
+        };
+        let mut lines = DumpLines::new(data.into(), context, &settings, 0, 0, 0)?;
+
+        let body = &mut self.body;
+        let _ = writeln!(body, "<details class=\"section\"{}>", if open { " open" } else { "" });
+        let _ = writeln!(body, "<summary>{}</summary>", escape(title));
+        body.push_str("<pre class=\"dump\">");
+        if let Some(caption) = &caption {
+            body.push_str(&escape(caption));
+            body.push('\n');
+        }
+        let address_position = address_position(&settings);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if !lines.next_line(&mut line) {
+                break;
+            }
+            // A `line_filter` may have rewritten the line, so the address is linked only if still in its place
+            let address_end = address_position.map(|position| position + settings.address_width);
+            let address = address_position.zip(address_end).and_then(|(position, end)| line.get(position..end));
+            let anchored = address.is_some_and(|address| address.bytes().all(|byte| byte.is_ascii_hexdigit()));
+            match lines.current_line.zip(address_position).filter(|_| anchored) {
+                Some((line_start, position)) => {
+                    let anchor = format!("{prefix}off-{:06x}", display_address(&settings, line_start));
+                    let (number, rest) = line.split_at(position);
+                    let (address, rest) = rest.split_at(settings.address_width);
+                    let _ = writeln!(
+                        body,
+                        "<span class=\"line\" id=\"{anchor}\">{number}<a class=\"addr\" href=\"#{anchor}\">{address}</a>{rest}</span>"
+                    );
+                }
+                None => {
+                    body.push_str(&line);
+                    body.push('\n');
+                }
+            }
+        }
+        body.push_str("</pre>\n");
+        if !annotations.is_empty() {
+            body.push_str("<ol class=\"annotations\">\n");
+            for (index, annotation) in annotations.iter().enumerate() {
+                let line_start = annotation.range.start - annotation.range.start % (settings.group_size * settings.groups_per_line).max(1);
+                let _ = write!(
+                    body,
+                    "<li id=\"{prefix}ann-{index}\"><a href=\"#{prefix}off-{:06x}\">{}</a>",
+                    display_address(&settings, line_start),
+                    escape(&annotation.label)
+                );
+                if let Some(value) = &annotation.value {
+                    let _ = write!(body, " = <span class=\"value\">{}</span>", escape(value));
+                }
+                let _ = writeln!(
+                    body,
+                    " <span class=\"range\">[{:#x}..{:#x})</span></li>",
+                    display_address(&settings, annotation.range.start),
+                    display_address(&settings, annotation.range.end)
+                );
+            }
+            body.push_str("</ol>\n");
+        }
+        body.push_str("</details>\n");
+        self.sections += 1;
+        Ok(self)
+    }
+
+    /// Produce the complete HTML document.
+    pub fn finish(&self) -> String {
+        let title = escape(&self.title);
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{STYLESHEET}</style>\n</head>\n<body>\n<h1>{title}</h1>\n{}</body>\n</html>\n",
+            self.body
+        )
+    }
+}
+
+/// Generate a self-contained HTML report with a single, expanded section dumping `data` with `annotations` overlaid.
+///
+/// See [`HtmlReport`] for reports with several sections.
+pub fn hex_out_html(data: &[u8], annotations: &[Annotation], settings: &HexOutSettings) -> Result<String, HexOutError> {
+    let mut report = HtmlReport::new("Hex dump");
+    report.add_section("Hex dump", data, annotations, settings, true)?;
+    Ok(report.finish())
+}
+
+/// Escape text for inclusion in HTML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    text.chars().for_each(|c| crate::push_char(&mut escaped, c, true));
+    escaped
+}
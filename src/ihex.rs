@@ -0,0 +1,52 @@
+use std::fmt::Write;
+
+/// Largest number of data bytes in a record of [`hex_dump_ihex`], as the length field of a record is a single byte.
+const MAX_RECORD_LEN: usize = 255;
+
+/// Write `data` as Intel HEX records for flashing, starting at the address `origin`, with up to `record_len` bytes per
+/// data record.
+///
+/// The records are data records that never cross a 64 KiB boundary, an extended linear address record before the
+/// first data record of each 64 KiB segment but the one at address 0, and the end of file record, each on a line of
+/// its own in uppercase hex digits ending in its checksum, so flash tools and `objcopy -I ihex` read them back.  A
+/// `record_len` of 0 is taken as 1, and lengths over 255 as 255.  Addresses past 4 GiB wrap around, as the format has
+/// no room for them.
+/// ```rust
+/// use hexout::hex_dump_ihex;
+/// assert_eq!(
+///     hex_dump_ihex(b"\x12\x34\x56\x78", 0x0001_fffe, 16),
+///     ":020000040001F9\n:02FFFE001234BB\n:020000040002F8\n:02000000567830\n:00000001FF\n"
+/// );
+/// ```
+pub fn hex_dump_ihex(data: &[u8], origin: u32, record_len: usize) -> String {
+    let record_len = record_len.clamp(1, MAX_RECORD_LEN);
+    let mut text = String::new();
+    // Addresses below 64 KiB need no extended linear address record
+    let mut segment = 0;
+    let mut index = 0;
+    while index < data.len() {
+        let address = origin.wrapping_add(index as u32);
+        let (upper, lower) = ((address >> 16) as u16, address as u16);
+        if upper != segment {
+            push_record(&mut text, 0, 0x04, &upper.to_be_bytes());
+            segment = upper;
+        }
+        let len = record_len.min(data.len() - index).min(0x10000 - lower as usize);
+        push_record(&mut text, lower, 0x00, &data[index..index + len]);
+        index += len;
+    }
+    push_record(&mut text, 0, 0x01, &[]);
+    text
+}
+
+/// Append a record of the given type with its checksum, the two's complement of the sum of all its other bytes.
+fn push_record(text: &mut String, address: u16, record_type: u8, bytes: &[u8]) {
+    let [high, low] = address.to_be_bytes();
+    let fields = [bytes.len() as u8, high, low, record_type];
+    let sum = fields.iter().chain(bytes).fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    text.push(':');
+    for byte in fields.iter().chain(bytes).chain([&sum.wrapping_neg()]) {
+        let _ = write!(text, "{byte:02X}");
+    }
+    text.push('\n');
+}
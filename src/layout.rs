@@ -0,0 +1,275 @@
+use std::{
+    fmt::{Debug, Write},
+    sync::Arc,
+};
+
+use crate::{Annotation, Endianness};
+
+/// Number of elements of an array or bytes of a blob, either fixed or taken from a previously decoded field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Count {
+    Fixed(usize),
+    /// The value of an earlier integer field, looked up by name (first relative to the enclosing array element,
+    /// then as a full path).
+    Field(String),
+}
+
+impl From<usize> for Count {
+    fn from(count: usize) -> Self {
+        Count::Fixed(count)
+    }
+}
+
+impl From<&str> for Count {
+    fn from(field: &str) -> Self {
+        Count::Field(field.to_string())
+    }
+}
+
+/// Type of a field in a [`Layout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Unsigned integer of 1 to 16 bytes.
+    Unsigned { size: usize, endianness: Endianness },
+    /// Two's complement signed integer of 1 to 16 bytes.
+    Signed { size: usize, endianness: Endianness },
+    /// Raw bytes, shown as hex.
+    Bytes(Count),
+    /// Text bytes, shown as a quoted string.
+    Text(Count),
+}
+
+/// Condition on the value of an earlier field, see [`Layout::when`].
+#[derive(Clone)]
+struct Condition(Arc<dyn Fn(u128) -> bool + Send + Sync>);
+
+impl Debug for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Condition(..)")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Item {
+    Field { name: String, kind: FieldKind },
+    Array { name: String, count: Count, layout: Layout },
+    When { field: String, condition: Condition, layout: Layout },
+}
+
+/// A declarative description of a binary structure, turning hexout into a lightweight template viewer.
+///
+/// Layouts are built from fields, arrays of nested layouts and conditional parts, and applied to data with
+/// [`Layout::apply`], producing [`Annotation`]s that can be rendered with [`hex_out_annotated`](crate::hex_out_annotated):
+/// ```rust
+/// use hexout::{hex_out_annotated, HexOutSettings, Layout};
+/// let layout = Layout::new()
+///     .u16_le("magic")
+///     .u8("count")
+///     .array("entries", "count", Layout::new().u8("tag").bytes("data", 2))
+///     .when("count", |count| count > 1, Layout::new().text("trailer", 3));
+/// let data = [0x4d, 0x5a, 2, 1, 0xaa, 0xbb, 2, 0xcc, 0xdd, b'e', b'n', b'd'];
+/// let annotations = layout.apply(&data);
+/// assert_eq!(annotations[0].label, "magic");
+/// assert_eq!(annotations[0].value.as_deref(), Some("23117"));
+/// assert_eq!(annotations[3].label, "entries[0].data");
+/// assert_eq!(annotations[3].value.as_deref(), Some("aabb"));
+/// let dump = hex_out_annotated(&data, &annotations, &HexOutSettings::default(), 0, 0, 0).unwrap();
+/// println!("{dump}");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Layout {
+    items: Vec<Item>,
+}
+
+/// Decoded integer fields by full path, used to resolve counts and conditions.
+type Values = Vec<(String, u128)>;
+
+impl Layout {
+    /// Create an empty layout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a field of the given kind.
+    pub fn field(mut self, name: &str, kind: FieldKind) -> Self {
+        self.items.push(Item::Field { name: name.to_string(), kind });
+        self
+    }
+
+    /// Append an unsigned integer field of `size` bytes.
+    pub fn unsigned(self, name: &str, size: usize, endianness: Endianness) -> Self {
+        self.field(name, FieldKind::Unsigned { size, endianness })
+    }
+
+    /// Append a signed integer field of `size` bytes.
+    pub fn signed(self, name: &str, size: usize, endianness: Endianness) -> Self {
+        self.field(name, FieldKind::Signed { size, endianness })
+    }
+
+    /// Append an 8-bit unsigned integer field.
+    pub fn u8(self, name: &str) -> Self {
+        self.unsigned(name, 1, Endianness::Little)
+    }
+
+    /// Append a little-endian 16-bit unsigned integer field.
+    pub fn u16_le(self, name: &str) -> Self {
+        self.unsigned(name, 2, Endianness::Little)
+    }
+
+    /// Append a big-endian 16-bit unsigned integer field.
+    pub fn u16_be(self, name: &str) -> Self {
+        self.unsigned(name, 2, Endianness::Big)
+    }
+
+    /// Append a little-endian 32-bit unsigned integer field.
+    pub fn u32_le(self, name: &str) -> Self {
+        self.unsigned(name, 4, Endianness::Little)
+    }
+
+    /// Append a big-endian 32-bit unsigned integer field.
+    pub fn u32_be(self, name: &str) -> Self {
+        self.unsigned(name, 4, Endianness::Big)
+    }
+
+    /// Append a little-endian 64-bit unsigned integer field.
+    pub fn u64_le(self, name: &str) -> Self {
+        self.unsigned(name, 8, Endianness::Little)
+    }
+
+    /// Append a big-endian 64-bit unsigned integer field.
+    pub fn u64_be(self, name: &str) -> Self {
+        self.unsigned(name, 8, Endianness::Big)
+    }
+
+    /// Append a field of raw bytes.
+    pub fn bytes(self, name: &str, count: impl Into<Count>) -> Self {
+        self.field(name, FieldKind::Bytes(count.into()))
+    }
+
+    /// Append a text field.
+    pub fn text(self, name: &str, count: impl Into<Count>) -> Self {
+        self.field(name, FieldKind::Text(count.into()))
+    }
+
+    /// Append an array of `count` elements, each described by `layout`.  Fields are named `name[index].field`.
+    pub fn array(mut self, name: &str, count: impl Into<Count>, layout: Layout) -> Self {
+        self.items.push(Item::Array { name: name.to_string(), count: count.into(), layout });
+        self
+    }
+
+    /// Append `layout` only if the value of the earlier integer `field` satisfies `condition`.
+    pub fn when(
+        mut self,
+        field: &str,
+        condition: impl Fn(u128) -> bool + Send + Sync + 'static,
+        layout: Layout,
+    ) -> Self {
+        self.items.push(Item::When { field: field.to_string(), condition: Condition(Arc::new(condition)), layout });
+        self
+    }
+
+    /// Decode `data` according to the layout, returning one annotation per field in data order.
+    ///
+    /// Decoding stops at the end of the data; a field cut short is still reported, with the value `<truncated>`.
+    pub fn apply(&self, data: &[u8]) -> Vec<Annotation> {
+        let mut annotations = Vec::new();
+        let mut values = Values::new();
+        let mut position = 0;
+        self.walk(data, &mut position, "", &mut values, &mut annotations);
+        annotations
+    }
+
+    /// Decode the items of this layout starting at `position`, returning `false` once the data is exhausted.
+    fn walk(
+        &self,
+        data: &[u8],
+        position: &mut usize,
+        prefix: &str,
+        values: &mut Values,
+        annotations: &mut Vec<Annotation>,
+    ) -> bool {
+        for item in &self.items {
+            match item {
+                Item::Field { name, kind } => {
+                    let path = format!("{prefix}{name}");
+                    let size = match kind {
+                        FieldKind::Unsigned { size, .. } | FieldKind::Signed { size, .. } => (*size).clamp(1, 16),
+                        FieldKind::Bytes(count) | FieldKind::Text(count) => resolve(count, prefix, values),
+                    };
+                    let end = position.saturating_add(size);
+                    let Some(bytes) = data.get(*position..end) else {
+                        let range = *position..end.min(data.len());
+                        if !range.is_empty() {
+                            annotations.push(Annotation::new(range, path).with_value("<truncated>"));
+                        }
+                        return false;
+                    };
+                    let value = match kind {
+                        FieldKind::Unsigned { endianness, .. } => {
+                            let value = endianness.read(bytes);
+                            values.push((path.clone(), value));
+                            value.to_string()
+                        }
+                        FieldKind::Signed { endianness, .. } => {
+                            let value = endianness.read(bytes);
+                            values.push((path.clone(), value));
+                            // Sign-extend from the field size
+                            let shift = 128 - 8 * size as u32;
+                            (((value << shift) as i128) >> shift).to_string()
+                        }
+                        FieldKind::Bytes(_) => {
+                            let mut hex = String::with_capacity(bytes.len().min(8) * 2 + 3);
+                            for byte in bytes.iter().take(8) {
+                                let _ = write!(hex, "{byte:02x}");
+                            }
+                            if bytes.len() > 8 {
+                                hex.push('…');
+                            }
+                            hex
+                        }
+                        FieldKind::Text(_) => format!("\"{}\"", bytes.escape_ascii()),
+                    };
+                    annotations.push(Annotation::new(*position..end, path).with_value(value));
+                    *position = end;
+                }
+                Item::Array { name, count, layout } => {
+                    for index in 0..resolve(count, prefix, values) {
+                        let element_start = *position;
+                        if !layout.walk(data, position, &format!("{prefix}{name}[{index}]."), values, annotations) {
+                            return false;
+                        }
+                        // Elements taking up no bytes would repeat forever
+                        if *position == element_start {
+                            break;
+                        }
+                    }
+                }
+                Item::When { field, condition, layout } => {
+                    if lookup(field, prefix, values).is_some_and(|value| (condition.0)(value))
+                        && !layout.walk(data, position, prefix, values, annotations)
+                    {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Look up a decoded integer field, first relative to `prefix` and then as a full path.
+fn lookup(field: &str, prefix: &str, values: &Values) -> Option<u128> {
+    let relative = format!("{prefix}{field}");
+    let find = |path: &str| values.iter().rev().find(|(name, _)| name == path).map(|(_, value)| *value);
+    find(&relative).or_else(|| find(field))
+}
+
+/// Resolve a count, treating unknown fields as zero.
+fn resolve(count: &Count, prefix: &str, values: &Values) -> usize {
+    match count {
+        Count::Fixed(count) => *count,
+        Count::Field(field) => {
+            lookup(field, prefix, values).map_or(0, |value| usize::try_from(value).unwrap_or(usize::MAX))
+        }
+    }
+}
@@ -1,4 +1,205 @@
-use std::{fmt::{Display, Debug}};
+// Without `std`, only the allocation-free `ConstHexOutSettings` renderer is built, e.g. for AVR and other 16-bit targets
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+use std::{fmt::{Display, Debug, Write}, ops::Range, sync::Arc};
+
+#[cfg(feature = "std")]
+mod annotation;
+#[cfg(feature = "std")]
+mod base64;
+#[cfg(feature = "std")]
+mod bitfield;
+#[cfg(feature = "std")]
+mod bookmarks;
+#[cfg(feature = "std")]
+mod bits;
+#[cfg(feature = "std")]
+mod cells;
+#[cfg(feature = "std")]
+mod checksum;
+#[cfg(feature = "std")]
+mod classify;
+#[cfg(feature = "std")]
+mod color;
+#[cfg(feature = "std")]
+mod columns;
+#[cfg(feature = "std")]
+mod compact;
+#[cfg(feature = "std")]
+mod concat;
+mod const_settings;
+#[cfg(feature = "std")]
+mod decoder;
+#[cfg(feature = "std")]
+mod digest;
+#[cfg(feature = "std")]
+mod diff;
+#[cfg(feature = "std")]
+mod endianness;
+#[cfg(feature = "std")]
+mod entropy;
+#[cfg(feature = "std")]
+mod fold;
+#[cfg(feature = "std")]
+mod frame;
+#[cfg(feature = "arbitrary")]
+mod fuzzing;
+#[cfg(feature = "std")]
+mod geometry;
+#[cfg(feature = "std")]
+mod histogram;
+#[cfg(feature = "std")]
+mod html;
+#[cfg(feature = "std")]
+mod ihex;
+#[cfg(feature = "std")]
+mod layout;
+#[cfg(feature = "std")]
+mod options;
+#[cfg(feature = "std")]
+mod parallel;
+#[cfg(feature = "std")]
+mod parse;
+#[cfg(feature = "std")]
+mod patch;
+#[cfg(feature = "std")]
+mod pipeline;
+#[cfg(feature = "std")]
+mod pattern;
+#[cfg(feature = "std")]
+mod presets;
+#[cfg(feature = "std")]
+mod preview;
+#[cfg(feature = "std")]
+mod radix;
+#[cfg(feature = "std")]
+mod records;
+#[cfg(feature = "std")]
+mod regions;
+#[cfg(feature = "std")]
+mod search;
+#[cfg(feature = "std")]
+mod seekable;
+#[cfg(feature = "std")]
+mod sink;
+#[cfg(feature = "std")]
+mod state;
+#[cfg(feature = "proptest")]
+pub mod strategies;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "std")]
+mod style;
+#[cfg(feature = "std")]
+mod telescope;
+#[cfg(feature = "std")]
+mod text;
+#[cfg(feature = "std")]
+mod theme;
+#[cfg(feature = "std")]
+mod tlv;
+#[cfg(feature = "std")]
+mod xxd;
+
+#[cfg(feature = "std")]
+use columns::ordered_columns;
+#[cfg(feature = "std")]
+use concat::{Source, Window};
+#[cfg(feature = "std")]
+use frame::{push_separator, Rule};
+
+#[cfg(feature = "std")]
+pub use annotation::{hex_out_annotated, Annotation, AnnotationIndex};
+#[cfg(feature = "std")]
+pub use bitfield::{hex_out_registers, BitField, Register};
+#[cfg(feature = "std")]
+pub use bookmarks::{bookmarks_csv, bookmarks_json, bookmarks_json_tagged};
+#[cfg(feature = "std")]
+pub use bits::bit_dump;
+#[cfg(feature = "std")]
+pub use cells::{changed_rows, hex_out_cells, hex_out_segments, HexCell, HexCellClass, HexSegment};
+#[cfg(feature = "std")]
+pub use checksum::{verify_checksums, ChecksumAlgorithm, ChecksumField};
+#[cfg(feature = "std")]
+pub use classify::{GroupClassifier, GroupKind};
+#[cfg(feature = "std")]
+pub use color::color_enabled;
+#[cfg(feature = "windows-console")]
+pub use color::enable_virtual_terminal;
+#[cfg(feature = "std")]
+pub use columns::Column;
+#[cfg(feature = "std")]
+pub use compact::{CompactSettings, StyleTable};
+#[cfg(feature = "std")]
+pub use concat::hex_dump_concat;
+pub use const_settings::ConstHexOutSettings;
+#[cfg(feature = "std")]
+pub use decoder::LineDecoder;
+#[cfg(feature = "std")]
+pub use digest::similarity_digest;
+#[cfg(feature = "std")]
+pub use diff::{hex_out_changes, SnapshotDiffer};
+#[cfg(feature = "std")]
+pub use endianness::{guess_endianness, Endianness};
+#[cfg(feature = "std")]
+pub use entropy::{entropy, high_entropy_regions, hex_out_entropy};
+#[cfg(feature = "std")]
+pub use fold::hex_out_folded;
+#[cfg(feature = "std")]
+pub use frame::Borders;
+#[cfg(feature = "std")]
+pub use geometry::{ascii_area_span, groups_per_line_for_width, hex_area_span, line_width};
+#[cfg(feature = "std")]
+pub use histogram::ByteHistogram;
+#[cfg(feature = "std")]
+pub use html::{hex_out_html, HtmlReport};
+#[cfg(feature = "std")]
+pub use ihex::hex_dump_ihex;
+#[cfg(feature = "std")]
+pub use layout::{Count, FieldKind, Layout};
+#[cfg(feature = "std")]
+pub use options::{hex_out_with_options, RenderOptions};
+#[cfg(feature = "std")]
+pub use parallel::dump_many;
+#[cfg(feature = "std")]
+pub use parse::parse_hex_str;
+#[cfg(feature = "std")]
+pub use patch::{apply_patch, Patch, PatchEntry};
+#[cfg(feature = "std")]
+pub use pipeline::Pipeline;
+#[cfg(feature = "std")]
+pub use pattern::parse_pattern_csv;
+#[cfg(feature = "std")]
+pub use preview::preview;
+#[cfg(feature = "std")]
+pub use radix::GroupRadix;
+#[cfg(feature = "std")]
+pub use records::{hex_dump_records, hex_dump_values, HexLineRecord};
+#[cfg(feature = "std")]
+pub use regions::{hex_out_regions, MemoryRegion};
+#[cfg(feature = "std")]
+pub use search::{find_all, hit_windows};
+#[cfg(feature = "std")]
+pub use seekable::SeekableHexSource;
+#[cfg(feature = "std")]
+pub use sink::LineSink;
+#[cfg(feature = "std")]
+pub use state::RenderState;
+#[cfg(feature = "std")]
+pub use stream::hex_dump_reader;
+#[cfg(feature = "std")]
+pub use style::{plain, strip_ansi, Style, StyledAreas};
+#[cfg(feature = "std")]
+pub use telescope::{hex_out_telescope, PointerResolver};
+#[cfg(feature = "std")]
+pub use text::TextMode;
+#[cfg(feature = "std")]
+pub use theme::Themes;
+#[cfg(feature = "std")]
+pub use tlv::{hex_out_tlv, Tlv};
+#[cfg(feature = "std")]
+pub use xxd::{undump, xxd_export, xxd_include};
 
 /// A compact and dependency-free, flexible and customizable hex dump library for Rust 
 /// that provides beautiful, configurable binary data visualization.
@@ -6,7 +207,7 @@ use std::{fmt::{Display, Debug}};
 /// # Features
 /// - Configurable group sizes to any arbitrary byte count (up to 16 bytes)
 /// - Adjustable number of groups per line
-/// - Options for big-endian or little-endian byte order
+/// - Options for big-endian, little-endian or PDP-11 byte order
 /// - Toggleable ASCII representation alongside hex output
 /// - Customizable display options (offsets, centerline, uppercase hex)
 /// # Usage
@@ -17,32 +218,42 @@ use std::{fmt::{Display, Debug}};
 /// ```
 /// Then use it in your Rust code:
 /// ```rust
-/// use hexout::{hex_out, HexOutSettings};
+/// use hexout::{hex_out, Endianness, HexOutSettings};
 /// let data = vec![0u8, 1, 2, 3, 4, 5, 6, 7, 8, 9];
 /// let settings = HexOutSettings {
 ///     group_size: 2,
 ///     groups_per_line: 8,
-///     big_endian: false,
+///     endianness: Endianness::Little,
 ///     show_ascii: true,
 ///     ..Default::default()
 /// };
 /// let dump = hex_out(&data, &settings, 0, 0, 1).unwrap();
 /// println!("{}", dump);
 /// ```
+/// # Output Stability
+/// Dumps don't depend on the locale, the platform or the environment, and their text stays byte-identical for the same
+/// data and settings across minor and patch releases.  The entropy values of [`high_entropy_regions`] come from the
+/// platform's floating-point logarithm and may differ in the last digit, unless the `stable-format` feature computes
+/// them in integer arithmetic.
 /// # License
 /// This project is licensed under the MIT License. See the [LICENSE](LICENSE) file for details.
 
 #[derive(Debug, Clone)]
 /// Settings to customize the hex dump output.
+#[cfg(feature = "std")]
 pub struct HexOutSettings {
     /// The origin address to start from.  Does not change the actual offset of the data, just the displayed address.  (default is 0).
-    pub address_origin: usize,
-    /// Width of the address field in characters (default is 8 for 32-bit addresses).
+    ///
+    /// Addresses are 128-bit, so address spaces beyond 64 bits (e.g. capability or GUID-partitioned spaces) can be
+    /// shown with an `address_width` of up to 32.
+    pub address_origin: u128,
+    /// Width of the address field in characters (default is 8 for 32-bit addresses, up to 32 for 128-bit ones).
     pub address_width: usize,
     /// Whether to align the address to the nearest group boundary.
     pub align_address: bool,
-    /// Whether to use big-endian byte order.
-    pub big_endian: bool,
+    /// Byte order of the values in a group (default is little-endian), or `Endianness::Auto` to guess it from the
+    /// data.  See also `group_endianness`.
+    pub endianness: Endianness,
     /// Number of bytes in each group.  This can be arbitrary number up to 16, doesn't have to be 1, 2, 4, or 8.
     pub group_size: usize,
     /// Number of groups to display per line.
@@ -61,19 +272,189 @@ pub struct HexOutSettings {
     pub strict: bool,
     /// Whether to use uppercase letters for hex digits.
     pub uppercase: bool,
+    /// Optional caption line rendered above the dump (and above the header, if shown).
+    ///
+    /// The following placeholders are substituted:
+    /// - `{len}`: number of bytes in the data (decimal)
+    /// - `{offset}`: the starting offset into the data (hex, `address_width` digits)
+    /// - `{address}`: the displayed address of the starting offset, i.e. `address_origin + offset` (hex, `address_width` digits)
+    pub caption: Option<String>,
+    /// Optional callback producing a note for each line, appended after the ASCII column.
+    pub line_annotator: Option<LineAnnotator>,
     /// Optional prefix to add to HexOutError indicators.  This is useful for things like ANSI color codes.
     pub hex_out_error_prefix: Option<String>,
     /// Optional postfix to add to HexOutError indicators.  This is useful for things like ANSI color codes.
     pub hex_out_error_postfix: Option<String>,
+    /// Optional index of the byte under the cursor, highlighted in both the hex and ASCII areas.
+    pub cursor: Option<usize>,
+    /// Optional style for the byte under the cursor.
+    pub cursor_style: Option<Style>,
+    /// Optional range of selected bytes, highlighted in both the hex and ASCII areas.
+    pub selection: Option<Range<usize>>,
+    /// Optional style for selected bytes.
+    pub selection_style: Option<Style>,
+    /// Ranges of bytes to highlight in both the hex and ASCII areas (e.g. search matches).
+    pub highlights: Vec<Range<usize>>,
+    /// Optional style for highlighted bytes.
+    pub highlight_style: Option<Style>,
+    /// Optional character used to draw a marker row under flagged bytes (see [`hex_out_flagged`]).
+    pub flag_marker: Option<char>,
+    /// Optional style for flagged bytes (see [`hex_out_flagged`]).
+    pub flag_style: Option<Style>,
+    /// Optional style for the address at the start of each line.
+    pub address_style: Option<Style>,
+    /// Optional URL template turning each line's address into an OSC 8 terminal hyperlink.
+    ///
+    /// `{offset}` (the data index of the line) and `{address}` (its displayed address) are substituted, both in hex
+    /// with `address_width` digits, e.g. `https://viewer.example/file.bin#{offset}`.
+    pub address_link: Option<String>,
+    /// Optional style for HexOutError indicators.  Takes precedence over `hex_out_error_prefix`/`hex_out_error_postfix`.
+    pub error_style: Option<Style>,
+    /// Borders drawn around the dump and between the address, hex and ASCII columns.
+    ///
+    /// The caption stays above the borders and line annotations follow their right edge.
+    pub borders: Borders,
+    /// Draw a horizontal rule after every this many lines (0 for none).
+    pub rule_interval: usize,
+    /// Whether to pad every line (including the caption, header and marker rows) with spaces to the width of a full
+    /// line, so the dump stays rectangular.  Line annotations are appended after the padding.
+    pub pad_lines: bool,
+    /// Whether to pad the last line to a full line when the ASCII column or a line annotation follows (default is
+    /// true).  If false, the last line ends without trailing whitespace and the ASCII column is closed right after its
+    /// last byte.  Ignored when drawing `borders` or with `pad_lines`.
+    pub pad_last_line: bool,
+    /// Whether to append the number of valid bytes on each line, e.g. `(10 bytes)`, ahead of any line annotation.
+    pub show_byte_count: bool,
+    /// Whether to show the line number (in decimal, right-aligned to `address_width`) at the start of each line, ahead
+    /// of the address.  Lines are counted from the start of the data, or from the offset if `align_address` is false,
+    /// so with fixed-size records the number is the record index.  Turn off `show_offset` to show it instead of the
+    /// address.
+    pub show_line_number: bool,
+    /// Whether to note the lines left out before and after a window selected with `start_line`/`line_count`, e.g.
+    /// `… 3 lines above …`.
+    pub show_truncation: bool,
+    /// Repeat the header (if `show_header` is set) after every this many lines, keeping a ruler in sight in long dumps
+    /// (0 to show it once).
+    pub header_interval: usize,
+    /// Insert `page_separator` as a line of its own after every this many lines (0 for none).
+    pub page_interval: usize,
+    /// Separator inserted between pages (default is a form feed).
+    pub page_separator: String,
+    /// Columns of each line, in order.  If empty (the default), the columns are chosen by `show_line_number`,
+    /// `show_offset`, `show_ascii`, `show_base64`, `line_decoder`, `show_byte_count` and `line_annotator`.  Setting it
+    /// allows other layouts, such as the ASCII column on the left or the address on the right, and columns like
+    /// [`Column::Checksum`] and [`Column::Digest`].
+    pub columns: Vec<Column>,
+    /// Whether to follow every group with its characters in parentheses, e.g. `41(A)`, as some embedded log
+    /// conventions require.  Usually combined with `show_ascii: false`.
+    pub inline_chars: bool,
+    /// Text before the digits of every group, e.g. `0x`.
+    pub group_prefix: String,
+    /// Text after every group, e.g. `,` (together with `group_prefix: "0x"` this produces initializer lists for C or
+    /// Rust).
+    pub group_suffix: String,
+    /// Byte order of each group of a line, overriding `endianness` (which applies to groups past the end of the list).
+    /// This suits records mixing byte orders, e.g. `vec![Endianness::Big, Endianness::Little]` for a big-endian field
+    /// followed by a little-endian one.
+    pub group_endianness: Vec<Endianness>,
+    /// Whether to show every group both as little-endian and as big-endian, e.g. `0201/0102`, for data of unknown
+    /// byte order.  Overrides `endianness` and `group_endianness`.
+    pub dual_endianness: bool,
+    /// Optional style for the bytes of every other line (or group, see `stripe_groups`), e.g. a background color
+    /// guiding the eye across wide dumps.  Bytes styled otherwise (cursor, selection, highlights, flags) take
+    /// precedence.
+    pub stripe_style: Option<Style>,
+    /// Whether `stripe_style` applies to every other group of a line instead of every other line.
+    pub stripe_groups: bool,
+    /// Character shown in the ASCII column (and with `inline_chars`) for bytes before the offset or past the end of
+    /// the data (default is a space).  A dot or a middle dot (`·`) tells them apart from real spaces.  Spaces for bytes
+    /// past the end of the data are left out of an ASCII column ending the last line, as `xxd` does.
+    pub ascii_placeholder: char,
+    /// How bytes are shown in the ASCII column and with `inline_chars`, e.g. [`TextMode::Escaped`] for control
+    /// characters as escape sequences or [`TextMode::Hexified`] for hex digits (default is [`TextMode::Ascii`]).
+    pub text_mode: TextMode,
+    /// Text opening the ASCII column, following the separator from the previous column (default is `|`).  It may
+    /// include ANSI escape sequences, e.g. for a dimmed bar, which take no width.  A space with an empty
+    /// `ascii_close` gives the two-space lead-in of `xxd`.  Replaced by the borders when drawing them.
+    pub ascii_open: String,
+    /// Text closing the ASCII column (default is `|`), see `ascii_open`.  Without it, the ASCII column of the last
+    /// line is only padded if another column follows.
+    pub ascii_close: String,
+    /// Optional callback decoding the data into records of variable length, e.g. instructions or TLV entries.  Each
+    /// line then shows one record (at most a full line of bytes) starting at the offset regardless of
+    /// `align_address`, padded like the last line, with the decoded text in the [`Column::Decoded`] column.  Line
+    /// numbers and the lines selected by `start_line` and `line_count` count records.
+    pub line_decoder: Option<LineDecoder>,
+    /// Whether to show the valid bytes of each line in base64 (RFC 4648, padded with `=`) after the ASCII column, for
+    /// copying data through channels that only take text.
+    pub show_base64: bool,
+    /// Whether to show each byte as 8 binary digits instead of 2 hex digits, as `xxd -b` does (see
+    /// [`CompactSettings::XXD_BITS`]).
+    pub binary: bool,
+    /// The radix the groups of the hex area are shown in (default is hex).  Octal and decimal groups take the width of
+    /// the largest value of `group_size` bytes, so they stay lined up.  They are styled as a whole, after the first of
+    /// their bytes with a style other than `stripe_style`, and marked as a whole if any of their bytes is flagged.
+    /// Groups not wholly within the data are shown as placeholders.  Ignored with `binary`.
+    pub group_radix: GroupRadix,
+    /// Optional callback rewriting each line of data as rendered (including its styles and columns), or dropping it
+    /// by returning `None`, e.g. to keep only lines with non-zero bytes.  Marker rows of dropped lines are dropped with
+    /// them, and dropped lines count neither for `rule_interval`, `header_interval` and `page_interval` nor as lines
+    /// left out by `show_truncation`.
+    pub line_filter: Option<LineFilter>,
+    /// Skip the lines whose valid bytes all equal this fill byte, e.g. `0xff` for blank EEPROM images, noting each run
+    /// of skipped lines as `… 3 lines of ff …`.  Skipped lines count neither for the intervals nor as lines left out by
+    /// `show_truncation`.
+    pub skip_fill: Option<u8>,
+    /// Optional callback mapping the offset of each line into the data to the address displayed for it, in place of
+    /// `address_origin + offset`, e.g. the virtual addresses of file offsets computed from the section table of an
+    /// executable.  It maps the address column, the `{address}` placeholders, the addresses passed to `line_decoder`
+    /// and the anchors of HTML reports.
+    pub address_map: Option<AddressMap>,
+    /// Draw an alignment guide in the hex area every this many bytes of a line (0 for none), e.g. 4 or 8 to follow
+    /// word boundaries across long lines without counting.  Guides take the place of the space between two groups, so
+    /// they only fall between groups and are left out at the centerline.
+    pub guide_interval: usize,
+    /// Character drawn as an alignment guide (default is `┊`).  A space with a `guide_style` setting a background
+    /// color draws the guides as color only.
+    pub guide_char: char,
+    /// Optional style for the alignment guides, e.g. a faint color.
+    pub guide_style: Option<Style>,
+    /// Areas the styles of bytes apply to (default is [`StyledAreas::Standard`]: all styles in the hex area, all but
+    /// the error and flag styles in the ASCII column).  [`StyledAreas::Both`] also wraps the ASCII characters of
+    /// missing bytes in `error_style` (or `hex_out_error_prefix`/`hex_out_error_postfix`) and those of flagged bytes
+    /// in `flag_style`.
+    pub styled_areas: StyledAreas,
+    /// Text between the address (or the line number, without an address) and the next column (default is `": "`),
+    /// e.g. two spaces as in the output of `hexdump -C`.  Replaced by the borders when drawing them.
+    pub address_separator: String,
+    /// Whether the padding of the last line (see `pad_last_line`) extends to the ASCII column (default is true).  If
+    /// false, the ASCII column is closed right after its last byte, while the columns before it stay lined up, as in
+    /// the output of `hexdump -C`.
+    pub pad_last_ascii: bool,
+    /// Whether the centerline (see `show_centerline`) also splits the ASCII column (default is true).  `hexdump -C`
+    /// only splits the hex area.
+    pub ascii_centerline: bool,
+    /// Whether to replace each run of lines repeating the bytes of the line before with a single `*` line, as
+    /// `hexdump` and `od` do.  Only full lines are compared, and squeezed lines count neither for the intervals nor as
+    /// lines left out by `show_truncation`.
+    pub squeeze_repeats: bool,
+    /// Whether to end the dump with a line showing the address of the end of the bytes dumped, as `hexdump` and `od`
+    /// do.  Dumps without any bytes don't get it.
+    pub show_end_address: bool,
+    /// Whether to end the dump with a histogram of the byte values of its lines as a sparkline, to tell text, binary
+    /// and compressed data apart at a glance (default is none).  The lines left out by `skip_fill`, `squeeze_repeats`
+    /// or the `line_filter` count too.  Dumps without any bytes don't get it.
+    pub byte_histogram: ByteHistogram,
 }
 
+#[cfg(feature = "std")]
 impl Default for HexOutSettings {
     fn default() -> Self {
         Self {
             address_origin: 0,
             address_width: 8,
             align_address: true,
-            big_endian: false,
+            endianness: Endianness::Little,
             group_size: 1,
             groups_per_line: 16,
             invalid_data_placeholder: '?',
@@ -83,22 +464,103 @@ impl Default for HexOutSettings {
             show_offset: true,
             strict: false,
             uppercase: false,
+            caption: None,
+            line_annotator: None,
             hex_out_error_prefix: None,
             hex_out_error_postfix: None,
+            cursor: None,
+            cursor_style: None,
+            selection: None,
+            selection_style: None,
+            highlights: Vec::new(),
+            highlight_style: None,
+            flag_marker: None,
+            flag_style: None,
+            address_style: None,
+            address_link: None,
+            error_style: None,
+            borders: Borders::None,
+            rule_interval: 0,
+            pad_lines: false,
+            pad_last_line: true,
+            show_byte_count: false,
+            show_line_number: false,
+            show_truncation: false,
+            header_interval: 0,
+            page_interval: 0,
+            page_separator: "\x0c".to_string(),
+            columns: Vec::new(),
+            inline_chars: false,
+            group_prefix: String::new(),
+            group_suffix: String::new(),
+            group_endianness: Vec::new(),
+            dual_endianness: false,
+            stripe_style: None,
+            stripe_groups: false,
+            ascii_placeholder: ' ',
+            text_mode: TextMode::Ascii,
+            ascii_open: "|".to_string(),
+            ascii_close: "|".to_string(),
+            line_decoder: None,
+            show_base64: false,
+            binary: false,
+            group_radix: GroupRadix::Hex,
+            line_filter: None,
+            skip_fill: None,
+            address_map: None,
+            guide_interval: 0,
+            guide_char: '┊',
+            guide_style: None,
+            styled_areas: StyledAreas::Standard,
+            address_separator: ": ".to_string(),
+            pad_last_ascii: true,
+            ascii_centerline: true,
+            squeeze_repeats: false,
+            show_end_address: false,
+            byte_histogram: ByteHistogram::None,
         }
     }
 }
 
 /// Errors that can occur during hex dump generation.
+#[cfg(feature = "std")]
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum HexOutError {
     /// The specified group size is invalid (must be between 1 and 16).
     InvalidGroupSize,
     /// The specified offset does not align with the group size in strict mode.
     UnalignedOffset { offset: usize, group_size: usize },
+    /// The specified address width is invalid (must be between 2 and 32).
     InvalidAddressWidth,
+    /// The specified number of groups per line is invalid (must be at least 1).
+    InvalidGroupsPerLine,
+    /// A bit field of a [`Register`] is empty or extends past the bits of a group.
+    InvalidBitField { register: String, field: String },
+    /// The line window, or the addresses of the lines in it, do not fit in a `usize`.
+    RangeOverflow,
+    /// The bit group size of a [`bit_dump`] is invalid (must be between 1 and 128).
+    InvalidBitGroupSize,
+    /// A character passed to [`parse_hex_str`] is neither a hex digit nor a separator (`position` is its byte index).
+    InvalidHexDigit { position: usize, character: char },
+    /// The string passed to [`parse_hex_str`] ends with half a byte.
+    OddHexDigitCount,
+    /// The data doesn't hold the old bytes of the [`Patch`] entry at `offset`, or the entry is out of order.
+    PatchMismatch { offset: usize },
+    /// Line `line` (counting from 1) of a patch read by [`Patch::from_text`] is not of the form `offset: old -> new`,
+    /// or its entry is out of order.
+    InvalidPatchLine { line: usize },
+    /// Line `line` (counting from 1) of a table read by [`parse_pattern_csv`] is missing a column or holds an offset or
+    /// size that is not a number, or the header on line 1 lacks a name, offset or size column.
+    InvalidPatternLine { line: usize },
+    /// Line `line` (counting from 1) of a dump read by [`undump`] is neither a comment nor of the form
+    /// `address: hex  ascii`, or its header holds an invalid setting.
+    InvalidDumpLine { line: usize },
+    /// The [`fmt::Write`](std::fmt::Write) passed to [`hex_dump_to`] failed.
+    WriteFailed,
 }
 
+#[cfg(feature = "std")]
 impl Display for HexOutError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -110,11 +572,27 @@ impl Display for HexOutError {
                     offset, group_size, offset % group_size
                 )
             },
-            HexOutError::InvalidAddressWidth => write!(f, "Invalid address width (must be 2-16)"),
+            HexOutError::InvalidAddressWidth => write!(f, "Invalid address width (must be 2-32)"),
+            HexOutError::InvalidGroupsPerLine => write!(f, "Invalid number of groups per line (must be at least 1)"),
+            HexOutError::InvalidBitField { register, field } => {
+                write!(f, "Bit field {field} of register {register} is empty or does not fit in a group")
+            }
+            HexOutError::RangeOverflow => write!(f, "Line window or addresses out of range"),
+            HexOutError::InvalidBitGroupSize => write!(f, "Invalid bit group size (must be 1-128)"),
+            HexOutError::InvalidHexDigit { position, character } => {
+                write!(f, "Invalid hex digit {character:?} at position {position}")
+            }
+            HexOutError::OddHexDigitCount => write!(f, "Odd number of hex digits"),
+            HexOutError::PatchMismatch { offset } => write!(f, "Data does not match the patch at offset {offset:#x}"),
+            HexOutError::InvalidPatchLine { line } => write!(f, "Invalid patch entry on line {line}"),
+            HexOutError::InvalidPatternLine { line } => write!(f, "Invalid pattern field on line {line}"),
+            HexOutError::InvalidDumpLine { line } => write!(f, "Invalid dump line {line}"),
+            HexOutError::WriteFailed => write!(f, "Writing the dump failed"),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl Debug for HexOutError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -123,13 +601,39 @@ impl Debug for HexOutError {
                 write!(f, "HexOutError::UnalignedOffset {{ offset: {offset}, group_size: {group_size} }}")
             }
             HexOutError::InvalidAddressWidth => write!(f, "HexOutError::InvalidAddressWidth"),
+            HexOutError::InvalidGroupsPerLine => write!(f, "HexOutError::InvalidGroupsPerLine"),
+            HexOutError::InvalidBitField { register, field } => {
+                write!(f, "HexOutError::InvalidBitField {{ register: {register:?}, field: {field:?} }}")
+            }
+            HexOutError::RangeOverflow => write!(f, "HexOutError::RangeOverflow"),
+            HexOutError::InvalidBitGroupSize => write!(f, "HexOutError::InvalidBitGroupSize"),
+            HexOutError::InvalidHexDigit { position, character } => {
+                write!(f, "HexOutError::InvalidHexDigit {{ position: {position}, character: {character:?} }}")
+            }
+            HexOutError::OddHexDigitCount => write!(f, "HexOutError::OddHexDigitCount"),
+            HexOutError::PatchMismatch { offset } => write!(f, "HexOutError::PatchMismatch {{ offset: {offset} }}"),
+            HexOutError::InvalidPatchLine { line } => write!(f, "HexOutError::InvalidPatchLine {{ line: {line} }}"),
+            HexOutError::InvalidPatternLine { line } => write!(f, "HexOutError::InvalidPatternLine {{ line: {line} }}"),
+            HexOutError::InvalidDumpLine { line } => write!(f, "HexOutError::InvalidDumpLine {{ line: {line} }}"),
+            HexOutError::WriteFailed => write!(f, "HexOutError::WriteFailed"),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for HexOutError {}
 
+#[cfg(feature = "std")]
+impl From<HexOutError> for std::io::Error {
+    /// Wrap the error as an [`InvalidInput`](std::io::ErrorKind::InvalidInput) I/O error, so dumps can be written with
+    /// `?` in functions returning `io::Result`.
+    fn from(error: HexOutError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, error)
+    }
+}
+
 /// A trait to provide functionality for byte slices.
+#[cfg(feature = "std")]
 pub trait HexOut {
     fn hex_out(&self) -> Result<String, HexOutError>;
     fn hex_out_lines(&self, start_line: usize, line_count: usize) -> Result<String, HexOutError>;
@@ -142,6 +646,7 @@ pub trait HexOut {
     ) -> Result<String, HexOutError>;
 }
 
+#[cfg(feature = "std")]
 impl HexOut for &[u8] {
     fn hex_out(&self) -> Result<String, HexOutError> {
         hex_out(self, &HexOutSettings::default(), 0, 0, 0)
@@ -165,6 +670,7 @@ impl HexOut for &[u8] {
     }
 }
 
+#[cfg(feature = "std")]
 impl HexOut for Vec<u8> {
     fn hex_out(&self) -> Result<String, HexOutError> {
         self.as_slice().hex_out()
@@ -188,6 +694,17 @@ impl HexOut for Vec<u8> {
     }
 }
 
+/// Version of the output formats, as a semantic version: the layout of dumps and the structure of JSON output (see
+/// [`bookmarks_json_tagged`]) change incompatibly only with a new major version, and a new minor version only adds to
+/// them, e.g. new fields or settings.  Parsers of the output can check it to handle layout changes.
+/// ```rust
+/// assert!(hexout::format_version().starts_with("1."));
+/// ```
+#[cfg(feature = "std")]
+pub fn format_version() -> &'static str {
+    "1.0.0"
+}
+
 /// Generate a hex dump of the given data with the specified settings.
 /// 
 /// # Parameters
@@ -203,6 +720,7 @@ impl HexOut for Vec<u8> {
 /// 
 /// # Returns
 /// Returns a formatted string representing the hex dump.
+#[cfg(feature = "std")]
 pub fn hex_out(
     data: &[u8],
     settings: &HexOutSettings,
@@ -210,227 +728,1616 @@ pub fn hex_out(
     start_line: usize,
     line_count: usize,
 ) -> Result<String, HexOutError> {
+    render_dump(data.into(), RenderContext::default(), settings, offset, start_line, line_count)
+}
+
+/// Generate a hex dump of the given data, marking the bytes whose entry in `flags` is non-zero.
+///
+/// `flags` runs parallel to `data` (entries past its end count as unflagged).  Flagged bytes are wrapped in
+/// `flag_style`, and if `flag_marker` is set a marker row is emitted under every line containing
+/// flagged bytes.  This is intended for debugger frontends showing breakpoints, watchpoints or dirty bytes.
+///
+/// All other parameters are the same as for [`hex_out`].
+#[cfg(feature = "std")]
+pub fn hex_out_flagged(
+    data: &[u8],
+    flags: &[u8],
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<String, HexOutError> {
+    let context = RenderContext { flags, ..Default::default() };
+    render_dump(data.into(), context, settings, offset, start_line, line_count)
+}
+
+#[cfg(feature = "std")]
+fn render_dump(
+    data: Source,
+    context: RenderContext,
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<String, HexOutError> {
+    let mut lines = DumpLines::new(data, context, settings, offset, start_line, line_count)?;
+    // Allocate result string with estimated capacity
+    let total_bytes_per_line = settings.group_size * settings.groups_per_line;
+    let mut result = String::with_capacity(total_bytes_per_line * line_count * 5);
+    let mut first = true;
+    loop {
+        let length = result.len();
+        // Add newline if not the first line
+        if !first {
+            result.push('\n');
+        }
+        if !lines.next_line(&mut result) {
+            result.truncate(length);
+            break;
+        }
+        first = false;
+    }
+    Ok(result)
+}
+
+/// Generate a hex dump of the given data with the specified settings, returning each line separately.
+///
+/// The lines are the same as those of [`hex_out`] (including the caption, header and marker rows), without the
+/// newlines in between.
+#[cfg(feature = "std")]
+pub fn hex_out_vec(
+    data: &[u8],
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<Vec<String>, HexOutError> {
+    let mut lines = DumpLines::new(data.into(), RenderContext::default(), settings, offset, start_line, line_count)?;
+    let mut result = Vec::new();
+    loop {
+        let mut line = String::new();
+        if !lines.next_line(&mut line) {
+            break;
+        }
+        result.push(line);
+    }
+    Ok(result)
+}
+
+/// Write a hex dump of the given data to `out` line by line, e.g. straight into a log record, without building the
+/// whole dump in a `String` first: only the line being rendered is held in memory.
+///
+/// The text is the same as that of [`hex_out`], without a newline after the last line.  Fails with
+/// [`HexOutError::WriteFailed`] if `out` does, after the lines before have been written.
+///
+/// All other parameters are the same as for [`hex_out`].
+/// ```rust
+/// use hexout::{hex_dump_to, HexOutSettings};
+/// let settings = HexOutSettings { groups_per_line: 4, show_ascii: false, ..Default::default() };
+/// let mut out = String::from("dump:\n");
+/// hex_dump_to(&[1, 2, 3, 4, 5], &settings, &mut out, 0, 0, 0).unwrap();
+/// assert_eq!(out, "dump:\n00000000: 01 02  03 04\n00000004: 05");
+/// ```
+#[cfg(feature = "std")]
+pub fn hex_dump_to(
+    data: &[u8],
+    settings: &HexOutSettings,
+    out: &mut impl Write,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<(), HexOutError> {
+    write_lines(data, settings, offset, start_line, line_count, |text| {
+        out.write_str(text).map_err(|_| HexOutError::WriteFailed)
+    })
+}
+
+/// Write a hex dump of the given data to `writer` line by line, e.g. to standard output or a file, without building
+/// the whole dump in a `String` first.  Each line is written with a call to
+/// [`write_all`](std::io::Write::write_all), so unbuffered writers such as files are best wrapped in a
+/// [`BufWriter`](std::io::BufWriter).
+///
+/// The text is the same as that of [`hex_out`], without a newline after the last line.  Fails with the I/O error of
+/// the writer, after the lines before have been written, or with a [`HexOutError`] wrapped as an I/O error (see
+/// [`HexOutError`]'s conversion) for invalid settings.
+///
+/// All other parameters are the same as for [`hex_out`].
+#[cfg(feature = "std")]
+pub fn hex_dump_to_writer<W: std::io::Write>(
+    data: &[u8],
+    settings: &HexOutSettings,
+    mut writer: W,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> std::io::Result<()> {
+    write_lines(data, settings, offset, start_line, line_count, |text| writer.write_all(text.as_bytes()))
+}
+
+/// Pass the lines of a dump to `write` one at a time, with the newlines in between.
+#[cfg(feature = "std")]
+fn write_lines<E: From<HexOutError>>(
+    data: &[u8],
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+    mut write: impl FnMut(&str) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut lines = DumpLines::new(data.into(), RenderContext::default(), settings, offset, start_line, line_count)?;
+    let mut line = String::new();
+    let mut first = true;
+    while lines.next_line(&mut line) {
+        if !first {
+            write("\n")?;
+        }
+        write(&line)?;
+        line.clear();
+        first = false;
+    }
+    Ok(())
+}
+
+/// A lending iterator over the lines of a hex dump that reuses a single buffer for all lines.
+///
+/// Unlike [`hex_out_vec`], no allocation is made per line, which suits high-frequency logging of small packets.
+/// Call [`advance`](HexOutLines::advance) to move to the next line and [`get`](HexOutLines::get) to borrow it:
+/// ```rust
+/// use hexout::{HexOutLines, HexOutSettings};
+/// let data = (0u8..40).collect::<Vec<u8>>();
+/// let settings = HexOutSettings::default();
+/// let mut lines = HexOutLines::new(&data, &settings, 0, 0, 0).unwrap();
+/// while let Some(line) = lines.next_line() {
+///     println!("{line}");
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub struct HexOutLines<'a> {
+    lines: DumpLines<'a>,
+    buffer: String,
+    valid: bool,
+}
+
+#[cfg(feature = "std")]
+impl<'a> HexOutLines<'a> {
+    /// Prepare the lines of a dump; the parameters are the same as for [`hex_out`].
+    pub fn new(
+        data: &'a [u8],
+        settings: &'a HexOutSettings,
+        offset: usize,
+        start_line: usize,
+        line_count: usize,
+    ) -> Result<Self, HexOutError> {
+        let total_bytes_per_line = settings.group_size * settings.groups_per_line;
+        Ok(Self {
+            lines: DumpLines::new(data.into(), RenderContext::default(), settings, offset, start_line, line_count)?,
+            buffer: String::with_capacity(total_bytes_per_line * 5 + settings.address_width + 4),
+            valid: false,
+        })
+    }
+
+    /// Move to the next line, returning `false` once all lines have been produced.
+    pub fn advance(&mut self) -> bool {
+        self.buffer.clear();
+        self.valid = self.lines.next_line(&mut self.buffer);
+        self.valid
+    }
+
+    /// The current line, or `None` before the first call to [`advance`](HexOutLines::advance) or after the end.
+    pub fn get(&self) -> Option<&str> {
+        self.valid.then_some(self.buffer.as_str())
+    }
+
+    /// Advance and return the new current line.
+    pub fn next_line(&mut self) -> Option<&str> {
+        self.advance();
+        self.get()
+    }
+}
+
+/// Check the settings and offset for a dump.
+#[cfg(feature = "std")]
+fn validate(settings: &HexOutSettings, offset: usize) -> Result<(), HexOutError> {
     // Validate group_size
     if settings.group_size == 0 || settings.group_size > 16 {
         return Err(HexOutError::InvalidGroupSize);
     }
     // Validate address_width
-    if settings.address_width < 2 || settings.address_width > 16 {
+    if settings.address_width < 2 || settings.address_width > 32 {
         return Err(HexOutError::InvalidAddressWidth);
     }
+    // Validate groups_per_line
+    if settings.groups_per_line == 0 {
+        return Err(HexOutError::InvalidGroupsPerLine);
+    }
     // If strict mode is enabled, ensure we don't start in the middle of a group
-    if settings.strict && (offset % settings.group_size != 0) {
+    if settings.strict && !offset.is_multiple_of(settings.group_size) {
         return Err(HexOutError::UnalignedOffset { 
             offset, 
             group_size: settings.group_size 
         });
     }
-    let total_bytes_per_line = settings.group_size * settings.groups_per_line;
-    // Setup buffers
-    let mut line = String::with_capacity(total_bytes_per_line * 3);
-    let mut ascii = String::with_capacity(total_bytes_per_line);
-    // Calculate total lines
-    let last_line_offset = if line_count == 0 {
-        data.len()
-    } else {
-        ((start_line + line_count) * total_bytes_per_line + offset).min(data.len())
-    };
-    // Align last_line_offset to group boundary
-    let last_line_offset = last_line_offset
-        + (settings.group_size - (last_line_offset % settings.group_size)) % settings.group_size;
+    Ok(())
+}
 
-    // Calculate starting index
-    // Allocate result string with estimated capacity
-    let mut result = String::with_capacity(total_bytes_per_line * line_count * 5);
-    if settings.show_header {
-        // Generate header line
-        let mut header = String::with_capacity(total_bytes_per_line * 3);
-        if settings.show_offset {
-            if settings.address_width >= 8 {
-                header.push_str("Address: ");
-                header.push_str(&" ".repeat(settings.address_width - 8));
-            } else if settings.address_width >= 4 {
-                header.push_str("Addr: ");
-                header.push_str(&" ".repeat(settings.address_width - 4));
-            } else {
-                header.push_str(&" ".repeat(settings.address_width));
-            }
+/// Append the column address header to `result`.
+///
+/// Only the columns up to the hex area are labelled, columns of variable width are skipped.
+#[cfg(feature = "std")]
+fn render_header(settings: &HexOutSettings, result: &mut String) {
+    let mut previous: Option<Column> = None;
+    for column in settings.effective_columns().into_iter().filter(|column| column.is_fixed_width()) {
+        if let Some(previous) = previous.filter(|previous| *previous != Column::Address) {
+            push_spaces(result, visible_width(previous.separator(column, settings)));
         }
-        for group in 0..settings.groups_per_line {
-            if group > 0 {
-                header.push(' ');
+        match column {
+            Column::LineNumber => {
+                result.push_str(if settings.address_width >= 4 { "Line" } else { "#" });
+                push_spaces(result, settings.address_width.saturating_sub(4));
             }
-            if group == settings.groups_per_line / 2 && settings.show_centerline {
-                header.push(' ');
+            Column::Address => {
+                // The address label includes its separator
+                if settings.address_width >= 8 {
+                    result.push_str("Address");
+                    result.push_str(&settings.address_separator);
+                    result.push_str(&" ".repeat(settings.address_width - 8));
+                } else if settings.address_width >= 4 {
+                    result.push_str("Addr");
+                    result.push_str(&settings.address_separator);
+                    result.push_str(&" ".repeat(settings.address_width - 4));
+                } else {
+                    result.push_str(&" ".repeat(settings.address_width));
+                }
             }
-            let col_index = group * settings.group_size;
-            header.push_str(&" ".repeat(settings.group_size * 2 - 2));
-            if settings.uppercase {
-                header.push_str(&format!("{col_index:02X}"));
-            } else {
-                header.push_str(&format!("{col_index:02x}"));
+            Column::Hex => {
+                push_column_labels(settings, result);
+                break;
             }
+            _ => push_spaces(result, column.width(settings)),
         }
-        result.push_str(&header);
-        result.push('\n');
-    }
-
-    let mut cursor = if settings.align_address { 0 } else { offset };
-    let mut group_index = 0;
-    let mut group_byte_index = 0;
-    let mut group_value: u128 = 0;
-    let mut out_of_bounds_count = 0;
-    // Move cursor to the start line
-    cursor += start_line * total_bytes_per_line;
-    // Setup the starting address
-    let mut addr = if settings.align_address {
-        cursor - (cursor % (settings.group_size * settings.groups_per_line))
-    } else {
-        cursor
-    } + settings.address_origin;
-    // Main loop to process each byte
-    while cursor < last_line_offset {
-        let byte = if let Some(b) = data.get(cursor) {
-            *b
+        previous = Some(column);
+    }
+}
+
+/// Append the column offsets of the hex area, each right-aligned over its group.
+#[cfg(feature = "std")]
+fn push_column_labels(settings: &HexOutSettings, result: &mut String) {
+    for group in 0..settings.groups_per_line {
+        if group > 0 {
+            result.push(' ');
+        }
+        if group > 0 && group == settings.groups_per_line / 2 && settings.show_centerline {
+            result.push(' ');
+        }
+        let col_index = group * settings.group_size;
+        let prefix_width = settings.group_prefix.chars().count();
+        let digits_width = group_digits(settings);
+        result.push_str(&" ".repeat(prefix_width + digits_width - 2));
+        if settings.uppercase {
+            result.push_str(&format!("{col_index:02X}"));
+        } else {
+            result.push_str(&format!("{col_index:02x}"));
+        }
+        // Inline characters and the group suffix are not labelled
+        push_spaces(result, group_width(settings) - prefix_width - digits_width);
+    }
+}
+
+/// Which part of a dump is produced next.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DumpStage {
+    Caption,
+    TopRule,
+    Header,
+    HeaderRule,
+    LinesAbove,
+    Lines,
+    EndAddress,
+    LinesBelow,
+    Histogram,
+    BottomRule,
+    Done,
+}
+
+/// Produces the lines of a dump one at a time.
+#[cfg(feature = "std")]
+struct DumpLines<'a> {
+    data: Source<'a>,
+    renderer: LineRenderer<'a>,
+    stage: DumpStage,
+    caption: Option<String>,
+    /// Data index of the next line to render.
+    line_start: usize,
+    /// Whether the marker row of the previous line is still to be emitted.
+    pending_marker_row: bool,
+    /// Number of data lines produced so far.
+    lines_rendered: usize,
+    /// Number of data lines dropped by `skip_fill` or the `line_filter` so far.
+    lines_dropped: usize,
+    /// Number of lines skipped by `skip_fill` since the last line rendered.
+    pending_gap: usize,
+    /// Whether a horizontal rule is due before the next line (see `rule_interval`).
+    pending_rule: bool,
+    /// Whether the header is to be repeated before the next line (see `header_interval`).
+    pending_header: bool,
+    /// Whether a page separator is due before the next line (see `page_interval`).
+    pending_page: bool,
+    /// Valid bytes of the line rendered last, to compare the next lines with (see `squeeze_repeats`).
+    previous_line: Vec<u8>,
+    /// Whether the lines repeating the one rendered last have been noted with a `*` line.
+    squeezing: bool,
+    /// Number of each byte value in the lines so far (see `byte_histogram`).
+    byte_counts: [usize; 256],
+    /// Whether more data follows the end of the lines, as for a chunk of a stream, so the lines stop before the notes
+    /// and rows due at the end of the data.
+    open_ended: bool,
+    /// Index of the first line in the window, the number of lines asked for (0 for all) and the number of lines in the
+    /// whole dump.
+    start_line: usize,
+    line_count: usize,
+    total_lines: usize,
+    /// Data index of the line produced last, `None` if it wasn't a line of data (e.g. the header).
+    current_line: Option<usize>,
+    /// End and decoded text of each record when decoding records (see `line_decoder`), otherwise empty.
+    records: Vec<(usize, Option<String>)>,
+    /// Index of the next record to render.
+    record: usize,
+}
+
+#[cfg(feature = "std")]
+impl<'a> DumpLines<'a> {
+    fn new(
+        data: Source<'a>,
+        context: RenderContext<'a>,
+        settings: &'a HexOutSettings,
+        offset: usize,
+        start_line: usize,
+        line_count: usize,
+    ) -> Result<Self, HexOutError> {
+        validate(settings, offset)?;
+        let total_bytes_per_line =
+            settings.group_size.checked_mul(settings.groups_per_line).ok_or(HexOutError::RangeOverflow)?;
+        // The line grid starts at 0 when aligning addresses, otherwise at the offset itself
+        let grid_start = if settings.align_address { 0 } else { offset };
+        // Calculate the end of the dump
+        let last_line_offset = if line_count == 0 {
+            data.len()
         } else {
-            out_of_bounds_count += 1;
-            0
+            start_line
+                .checked_add(line_count)
+                .and_then(|end_line| end_line.checked_mul(total_bytes_per_line))
+                .and_then(|end| end.checked_add(offset))
+                .ok_or(HexOutError::RangeOverflow)?
+                .min(data.len())
         };
-        // If enabled, store ASCII representation of each byte
-        if settings.show_ascii {
-            if out_of_bounds_count > 0 || cursor < offset {
-                ascii.push(' ');
-            } else if (0x20..0x7F).contains(&byte) {
-                ascii.push(byte as char);
-            } else {
-                ascii.push('.');
+        // Align last_line_offset to group boundary
+        let last_line_offset = last_line_offset.max(grid_start);
+        let last_line_offset = last_line_offset
+            .checked_add((settings.group_size - ((last_line_offset - grid_start) % settings.group_size)) % settings.group_size)
+            .ok_or(HexOutError::RangeOverflow)?;
+        let line_start = start_line
+            .checked_mul(total_bytes_per_line)
+            .and_then(|start| start.checked_add(grid_start))
+            .ok_or(HexOutError::RangeOverflow)?;
+        // Every displayed address must be representable (mapped addresses always are)
+        if settings.address_map.is_none() {
+            (last_line_offset as u128).checked_add(settings.address_origin).ok_or(HexOutError::RangeOverflow)?;
+        }
+        if let Some(decoder) = &settings.line_decoder {
+            return Ok(Self::decoded(data, context, settings, decoder, offset, start_line, line_count));
+        }
+        Ok(Self {
+            data,
+            renderer: LineRenderer::new(data, context, settings, offset, last_line_offset),
+            stage: DumpStage::Caption,
+            caption: context
+                .caption
+                .or(settings.caption.as_deref())
+                .map(|caption| expand_caption(caption, data.len(), offset, settings)),
+            // Move to the start line
+            line_start,
+            pending_marker_row: false,
+            lines_rendered: 0,
+            lines_dropped: 0,
+            pending_gap: 0,
+            pending_rule: false,
+            pending_header: false,
+            pending_page: false,
+            previous_line: Vec::new(),
+            squeezing: false,
+            byte_counts: [0; 256],
+            open_ended: false,
+            start_line,
+            line_count,
+            total_lines: (data.len().max(grid_start) - grid_start).div_ceil(total_bytes_per_line),
+            current_line: None,
+            records: Vec::new(),
+            record: 0,
+        })
+    }
+
+    /// Lines of a dump with one record per line, as decoded by `decoder`.
+    fn decoded(
+        data: Source<'a>,
+        context: RenderContext<'a>,
+        settings: &'a HexOutSettings,
+        decoder: &LineDecoder,
+        offset: usize,
+        start_line: usize,
+        line_count: usize,
+    ) -> Self {
+        let total_bytes_per_line = settings.group_size * settings.groups_per_line;
+        let records = decoder::decode_records(decoder, data, offset, total_bytes_per_line, settings);
+        let first = start_line.min(records.len());
+        let last = match line_count {
+            0 => records.len(),
+            _ => start_line.saturating_add(line_count).min(records.len()),
+        };
+        let line_start = records[..first].last().map_or(offset, |(end, _)| *end);
+        let end = records[..last].last().map_or(offset, |(end, _)| *end);
+        Self {
+            data,
+            renderer: LineRenderer::new(data, context, settings, offset, end),
+            stage: DumpStage::Caption,
+            caption: context
+                .caption
+                .or(settings.caption.as_deref())
+                .map(|caption| expand_caption(caption, data.len(), offset, settings)),
+            line_start,
+            pending_marker_row: false,
+            lines_rendered: 0,
+            lines_dropped: 0,
+            pending_gap: 0,
+            pending_rule: false,
+            pending_header: false,
+            pending_page: false,
+            previous_line: Vec::new(),
+            squeezing: false,
+            byte_counts: [0; 256],
+            open_ended: false,
+            start_line,
+            line_count,
+            total_lines: records.len(),
+            current_line: None,
+            records,
+            record: first,
+        }
+    }
+
+    /// Append the next line of the dump to `out`, returning `false` (and leaving `out` untouched) at the end.
+    fn next_line(&mut self, out: &mut String) -> bool {
+        let settings = self.renderer.settings;
+        self.current_line = None;
+        loop {
+            match self.stage {
+                DumpStage::Caption => {
+                    self.stage = DumpStage::TopRule;
+                    if let Some(caption) = &self.caption {
+                        let start = out.len();
+                        out.push_str(caption);
+                        pad_line(settings, out, start);
+                        return true;
+                    }
+                }
+                DumpStage::TopRule => {
+                    self.stage = DumpStage::Header;
+                    if settings.borders != Borders::None {
+                        frame::push_rule(settings, Rule::Top, out);
+                        return true;
+                    }
+                }
+                DumpStage::Header => {
+                    self.stage = DumpStage::HeaderRule;
+                    if settings.show_header {
+                        if let Some(vertical) = settings.borders.vertical() {
+                            frame::push_header_row(settings, vertical, out);
+                        } else {
+                            let start = out.len();
+                            render_header(settings, out);
+                            pad_line(settings, out, start);
+                        }
+                        return true;
+                    }
+                }
+                DumpStage::HeaderRule => {
+                    self.stage = DumpStage::LinesAbove;
+                    if settings.show_header && settings.borders != Borders::None {
+                        frame::push_rule(settings, Rule::Middle, out);
+                        return true;
+                    }
+                }
+                DumpStage::LinesAbove => {
+                    self.stage = DumpStage::Lines;
+                    let above = self.start_line.min(self.total_lines);
+                    if settings.show_truncation && above > 0 && self.lines_rendered == 0 {
+                        push_truncation(settings, above, "above", out);
+                        return true;
+                    }
+                }
+                DumpStage::Lines => {
+                    if self.pending_marker_row {
+                        self.pending_marker_row = false;
+                        out.push_str(&self.renderer.marker_row);
+                        return true;
+                    }
+                    if self.line_start >= self.renderer.end {
+                        if self.open_ended {
+                            return false;
+                        }
+                        if self.pending_gap > 0 {
+                            push_gap(settings, std::mem::take(&mut self.pending_gap), out);
+                            return true;
+                        }
+                        self.stage = DumpStage::EndAddress;
+                        continue;
+                    }
+                    if self.pending_rule {
+                        self.pending_rule = false;
+                        frame::push_rule(settings, Rule::Middle, out);
+                        return true;
+                    }
+                    if self.pending_page {
+                        self.pending_page = false;
+                        out.push_str(&settings.page_separator);
+                        return true;
+                    }
+                    if self.pending_header {
+                        self.pending_header = false;
+                        self.stage = DumpStage::Header;
+                        continue;
+                    }
+                    let line_start = self.line_start;
+                    let total_bytes_per_line = settings.group_size * settings.groups_per_line;
+                    let (line_end, next_line_start) = match self.records.get(self.record) {
+                        Some(&(record_end, _)) => {
+                            // The record's bytes are the only valid ones, its last group is completed with placeholders
+                            self.renderer.data.truncate(record_end);
+                            let length = (record_end - line_start).next_multiple_of(settings.group_size);
+                            (line_start + length, record_end)
+                        }
+                        None => {
+                            let line_end = (line_start + total_bytes_per_line).min(self.renderer.end);
+                            // Stop processing once we're past the data length
+                            let next_line_start = if line_end > self.renderer.data.len() {
+                                self.renderer.end
+                            } else {
+                                line_start + total_bytes_per_line
+                            };
+                            (line_end, next_line_start)
+                        }
+                    };
+                    self.renderer.data.load(self.data, line_start..line_end);
+                    let valid = self.renderer.valid_range(line_start, line_end);
+                    let bytes = &self.renderer.data[valid.clone()];
+                    if settings.byte_histogram != ByteHistogram::None {
+                        bytes.iter().for_each(|byte| self.byte_counts[*byte as usize] += 1);
+                    }
+                    let fill_only = |fill| !bytes.is_empty() && bytes.iter().all(|byte| *byte == fill);
+                    if settings.skip_fill.is_some_and(fill_only) {
+                        self.line_start = next_line_start;
+                        self.record += usize::from(!self.records.is_empty());
+                        self.lines_dropped += 1;
+                        self.pending_gap += 1;
+                        continue;
+                    }
+                    if self.pending_gap > 0 {
+                        push_gap(settings, std::mem::take(&mut self.pending_gap), out);
+                        return true;
+                    }
+                    if settings.squeeze_repeats {
+                        if bytes.len() == total_bytes_per_line && *bytes == *self.previous_line {
+                            self.line_start = next_line_start;
+                            self.record += usize::from(!self.records.is_empty());
+                            self.lines_dropped += 1;
+                            if !self.squeezing {
+                                self.squeezing = true;
+                                push_note(settings, "*", out);
+                                return true;
+                            }
+                            continue;
+                        }
+                        self.previous_line.clear();
+                        self.previous_line.extend_from_slice(bytes);
+                        self.squeezing = false;
+                    }
+                    if let Some((_, text)) = self.records.get_mut(self.record) {
+                        self.renderer.record = Some((self.record, line_start));
+                        self.renderer.decoded = text.take();
+                        self.record += 1;
+                    }
+                    let start = out.len();
+                    self.renderer.render(out, line_start, line_end);
+                    self.line_start = next_line_start;
+                    if let Some(filter) = &settings.line_filter {
+                        let line = filter.filter(valid.start, &self.renderer.data[valid], &out[start..]);
+                        out.truncate(start);
+                        match line {
+                            Some(line) => out.push_str(&line),
+                            None => {
+                                self.lines_dropped += 1;
+                                continue;
+                            }
+                        }
+                    }
+                    self.current_line = Some(line_start);
+                    self.pending_marker_row = !self.renderer.marker_row.is_empty();
+                    self.lines_rendered += 1;
+                    self.pending_rule =
+                        settings.rule_interval > 0 && self.lines_rendered.is_multiple_of(settings.rule_interval);
+                    self.pending_header = settings.show_header
+                        && settings.header_interval > 0
+                        && self.lines_rendered.is_multiple_of(settings.header_interval);
+                    self.pending_page =
+                        settings.page_interval > 0 && self.lines_rendered.is_multiple_of(settings.page_interval);
+                    // Borders close the lines above a repeated header
+                    self.pending_rule |= self.pending_header && settings.borders != Borders::None;
+                    return true;
+                }
+                DumpStage::EndAddress => {
+                    self.stage = DumpStage::LinesBelow;
+                    if settings.show_end_address && self.lines_rendered + self.lines_dropped > 0 {
+                        let end = display_address(settings, self.renderer.end.min(self.data.len()));
+                        let address = format!("{end:0width$x}", width = settings.address_width);
+                        match &settings.address_style {
+                            Some(style) => push_note(settings, &style.apply(&address), out),
+                            None => push_note(settings, &address, out),
+                        }
+                        return true;
+                    }
+                }
+                DumpStage::LinesBelow => {
+                    self.stage = DumpStage::Histogram;
+                    let passed = self.lines_rendered + self.lines_dropped;
+                    let below = self.total_lines.saturating_sub(self.start_line + passed);
+                    if settings.show_truncation && below > 0 {
+                        push_truncation(settings, below, "below", out);
+                        return true;
+                    }
+                }
+                DumpStage::Histogram => {
+                    self.stage = DumpStage::BottomRule;
+                    if settings.byte_histogram != ByteHistogram::None && self.lines_rendered + self.lines_dropped > 0 {
+                        push_note(settings, &settings.byte_histogram.sparkline(&self.byte_counts), out);
+                        return true;
+                    }
+                }
+                DumpStage::BottomRule => {
+                    self.stage = DumpStage::Done;
+                    if settings.borders != Borders::None {
+                        frame::push_rule(settings, Rule::Bottom, out);
+                        return true;
+                    }
+                }
+                DumpStage::Done => return false,
+            }
+        }
+    }
+}
+
+/// Append a note on `count` lines left out `position` ("above" or "below") the lines of a dump.
+#[cfg(feature = "std")]
+fn push_truncation(settings: &HexOutSettings, count: usize, position: &str, out: &mut String) {
+    let note = format!("… {count} line{} {position} …", if count == 1 { "" } else { "s" });
+    push_note(settings, &note, out);
+}
+
+/// Append a line of its own holding `note`, within the borders if drawing them.
+#[cfg(feature = "std")]
+fn push_note(settings: &HexOutSettings, note: &str, out: &mut String) {
+    match settings.borders.vertical() {
+        Some(vertical) => frame::push_text_row(settings, vertical, note, out),
+        None => {
+            let start = out.len();
+            out.push_str(note);
+            pad_line(settings, out, start);
+        }
+    }
+}
+
+/// Append the note for `count` lines skipped by `skip_fill`, e.g. `… 3 lines of 00 …`.
+#[cfg(feature = "std")]
+fn push_gap(settings: &HexOutSettings, count: usize, out: &mut String) {
+    let fill = settings.skip_fill.unwrap_or_default();
+    let position = if settings.uppercase { format!("of {fill:02X}") } else { format!("of {fill:02x}") };
+    push_truncation(settings, count, &position, out);
+}
+
+/// Callback returning an optional note for a line, appended after the ASCII column.
+///
+/// The callback receives the offset of the first valid byte on the line and the valid bytes of
+/// that line (bytes before the dump offset or past the end of the data are not included).
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct LineAnnotator(Arc<LineAnnotatorFn>);
+
+#[cfg(feature = "std")]
+type LineAnnotatorFn = dyn Fn(usize, &[u8]) -> Option<String> + Send + Sync;
+
+#[cfg(feature = "std")]
+impl LineAnnotator {
+    /// Wrap a closure as a line annotator.
+    pub fn new(annotator: impl Fn(usize, &[u8]) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(annotator))
+    }
+
+    /// Invoke the annotator for the line starting at `line_offset`.
+    pub fn annotate(&self, line_offset: usize, line_bytes: &[u8]) -> Option<String> {
+        (self.0)(line_offset, line_bytes)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Debug for LineAnnotator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LineAnnotator(..)")
+    }
+}
+
+/// Callback rewriting or dropping each rendered line of a dump, see `HexOutSettings::line_filter`.
+///
+/// The callback receives the offset of the first valid byte on the line, the valid bytes of that line (as for
+/// [`LineAnnotator`]) and the rendered line, and returns the line to emit in its place, or `None` to drop it.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct LineFilter(Arc<LineFilterFn>);
+
+#[cfg(feature = "std")]
+type LineFilterFn = dyn Fn(usize, &[u8], &str) -> Option<String> + Send + Sync;
+
+#[cfg(feature = "std")]
+impl LineFilter {
+    /// Wrap a closure as a line filter.
+    pub fn new(filter: impl Fn(usize, &[u8], &str) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(filter))
+    }
+
+    /// Invoke the filter for the line starting at `line_offset`, rendered as `line`.
+    pub fn filter(&self, line_offset: usize, line_bytes: &[u8], line: &str) -> Option<String> {
+        (self.0)(line_offset, line_bytes, line)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Debug for LineFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LineFilter(..)")
+    }
+}
+
+/// Callback mapping offsets into the data to displayed addresses, see `HexOutSettings::address_map`.
+///
+/// The callback receives the offset of a line into the data (a file offset when the whole file is dumped) and
+/// returns the address to show for it, so file-backed data can be shown at its load addresses without splitting it
+/// into regions first.
+/// ```rust
+/// use hexout::AddressMap;
+/// // A section at file offset 0x400 loaded at 0x401000
+/// let map = AddressMap::new(|offset| if offset >= 0x400 { offset as u64 - 0x400 + 0x40_1000 } else { offset as u64 });
+/// assert_eq!(map.map(0x410), 0x40_1010);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct AddressMap(Arc<AddressMapFn>);
+
+#[cfg(feature = "std")]
+type AddressMapFn = dyn Fn(usize) -> u64 + Send + Sync;
+
+#[cfg(feature = "std")]
+impl AddressMap {
+    /// Wrap a closure as an address map.
+    pub fn new(map: impl Fn(usize) -> u64 + Send + Sync + 'static) -> Self {
+        Self(Arc::new(map))
+    }
+
+    /// Invoke the map for the line at `offset`.
+    pub fn map(&self, offset: usize) -> u64 {
+        (self.0)(offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Debug for AddressMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AddressMap(..)")
+    }
+}
+
+/// Width of the hex area of a full line in characters, including group separators and the centerline.
+#[cfg(feature = "std")]
+fn hex_area_width(settings: &HexOutSettings) -> usize {
+    ((group_width(settings) + 1) * settings.groups_per_line + centerline_width(settings)).saturating_sub(1)
+}
+
+/// Width of a group in the hex area, including its inline characters, prefix and suffix.
+#[cfg(feature = "std")]
+fn group_width(settings: &HexOutSettings) -> usize {
+    let digits = group_digits(settings);
+    let digits = if settings.dual_endianness { digits * 2 + 1 } else { digits };
+    let digits = if settings.inline_chars { digits + inline_chars_width(settings) } else { digits };
+    digits + settings.group_prefix.chars().count() + settings.group_suffix.chars().count()
+}
+
+/// Number of digits of each group in the hex area, see `group_radix`.
+#[cfg(feature = "std")]
+fn group_digits(settings: &HexOutSettings) -> usize {
+    match settings.binary {
+        true => settings.group_size * 8,
+        false => settings.group_radix.group_digits(settings.group_size),
+    }
+}
+
+/// Number of digits of each byte in the hex area: 8 with `binary`, otherwise 2.
+#[cfg(feature = "std")]
+fn digits_per_byte(settings: &HexOutSettings) -> usize {
+    if settings.binary { 8 } else { 2 }
+}
+
+/// Width of the characters following a group with `inline_chars`, including the parentheses.
+#[cfg(feature = "std")]
+fn inline_chars_width(settings: &HexOutSettings) -> usize {
+    settings.group_size * settings.text_mode.cell_width() + 2
+}
+
+/// Width of the ASCII area of a full line in characters, excluding the surrounding bars.
+#[cfg(feature = "std")]
+fn ascii_area_width(settings: &HexOutSettings) -> usize {
+    let centerline = if settings.ascii_centerline { centerline_width(settings) } else { 0 };
+    settings.group_size * settings.groups_per_line * settings.text_mode.cell_width() + centerline
+}
+
+/// Number of characters taken by the centerline (0 or 1).
+#[cfg(feature = "std")]
+fn centerline_width(settings: &HexOutSettings) -> usize {
+    if settings.show_centerline && settings.groups_per_line / 2 > 0 { 1 } else { 0 }
+}
+
+/// Classification of a single byte in the hex area, used to pick its styling.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CellClass {
+    Normal,
+    /// On a striped line or group, see `stripe_style`.
+    Striped,
+    /// Before the offset or past the end of the data.
+    Missing,
+    Flagged,
+    /// Inside the overlay with the given index.
+    Overlay(usize),
+    Highlighted,
+    Selected,
+    Cursor,
+}
+
+#[cfg(feature = "std")]
+impl CellClass {
+    /// The class used in the hex area, where all classes are styled unless `styled_areas` leaves the area out.
+    fn in_hex_area(self, settings: &HexOutSettings) -> CellClass {
+        match settings.styled_areas {
+            StyledAreas::AsciiOnly => CellClass::Normal,
+            _ => self,
+        }
+    }
+
+    /// The class used in the ASCII area, where by default only the cursor, selection, highlights, overlays and stripes
+    /// are styled, see `styled_areas`.
+    fn in_ascii_area(self, settings: &HexOutSettings) -> CellClass {
+        match (settings.styled_areas, self) {
+            (StyledAreas::Both | StyledAreas::AsciiOnly, _) => self,
+            (StyledAreas::HexOnly, _) => CellClass::Normal,
+            (StyledAreas::Standard, CellClass::Missing | CellClass::Flagged) => CellClass::Normal,
+            (StyledAreas::Standard, _) => self,
+        }
+    }
+
+    /// Prefix and suffix strings wrapping a run of cells of this class.
+    fn style<'a>(self, settings: &'a HexOutSettings, context: &RenderContext<'a>) -> (&'a str, &'a str) {
+        let style = match self {
+            CellClass::Normal => None,
+            CellClass::Striped => settings.stripe_style.as_ref(),
+            CellClass::Missing => {
+                if settings.error_style.is_none() {
+                    return (
+                        settings.hex_out_error_prefix.as_deref().unwrap_or_default(),
+                        settings.hex_out_error_postfix.as_deref().unwrap_or_default(),
+                    );
+                }
+                settings.error_style.as_ref()
             }
+            CellClass::Flagged => settings.flag_style.as_ref(),
+            CellClass::Overlay(overlay) => Some(&context.overlays[overlay].1),
+            CellClass::Highlighted => settings.highlight_style.as_ref(),
+            CellClass::Selected => settings.selection_style.as_ref(),
+            CellClass::Cursor => settings.cursor_style.as_ref(),
+        };
+        style.map_or(("", ""), |style| (style.prefix.as_str(), style.suffix.as_str()))
+    }
+}
+
+/// Close the styling of a run of `from` cells in `buffer` and open the styling for `to` cells.
+#[cfg(feature = "std")]
+fn switch_style(settings: &HexOutSettings, context: &RenderContext, buffer: &mut String, from: CellClass, to: CellClass) {
+    if from != to {
+        buffer.push_str(from.style(settings, context).1);
+        buffer.push_str(to.style(settings, context).0);
+    }
+}
+
+/// Per-call inputs of a render beyond the data and the settings.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Default)]
+struct RenderContext<'a> {
+    /// Per-byte flags, parallel to the data (see [`hex_out_flagged`]).
+    flags: &'a [u8],
+    /// Ranges of bytes styled individually, e.g. annotations in HTML output.
+    overlays: &'a [(Range<usize>, Style)],
+    /// Whether characters taken from the data or from callbacks must be escaped for HTML.
+    escape_html: bool,
+    /// Cursor and selection taking precedence over those of the settings, and highlights added to theirs (see
+    /// [`RenderOptions`]).
+    cursor: Option<usize>,
+    selection: Option<&'a Range<usize>>,
+    highlights: &'a [Range<usize>],
+    /// Caption taking precedence over that of the settings.
+    caption: Option<&'a str>,
+}
+
+/// Append a character to `buffer`, escaping it for HTML if requested.
+#[cfg(feature = "std")]
+fn push_char(buffer: &mut String, c: char, escape_html: bool) {
+    match c {
+        '<' if escape_html => buffer.push_str("&lt;"),
+        '>' if escape_html => buffer.push_str("&gt;"),
+        '&' if escape_html => buffer.push_str("&amp;"),
+        '"' if escape_html => buffer.push_str("&quot;"),
+        _ => buffer.push(c),
+    }
+}
+
+/// Append `text` to `buffer`, escaping it for HTML if requested.
+#[cfg(feature = "std")]
+fn push_str_escaped(buffer: &mut String, text: &str, escape_html: bool) {
+    text.chars().for_each(|c| push_char(buffer, c, escape_html));
+}
+
+/// Append the ASCII representation of `byte` to `buffer`: printable characters as is, others as `.`.
+#[cfg(feature = "std")]
+fn push_ascii(buffer: &mut String, byte: u8, escape_html: bool) {
+    if (0x20..0x7F).contains(&byte) {
+        push_char(buffer, byte as char, escape_html);
+    } else {
+        buffer.push('.');
+    }
+}
+
+const HEX_DIGITS_LOWER: &[u8; 16] = b"0123456789abcdef";
+const HEX_DIGITS_UPPER: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Renders individual lines of a dump, writing each column straight into the output.
+#[cfg(feature = "std")]
+struct LineRenderer<'a> {
+    /// The bytes of the data read so far, by data index.
+    data: Window<'a>,
+    context: RenderContext<'a>,
+    settings: &'a HexOutSettings,
+    /// Offset of the first valid byte; bytes before it are rendered as blanks.
+    offset: usize,
+    /// End of the dump (aligned to a group boundary); nothing at or past it is rendered.
+    end: usize,
+    /// Columns in the order they are rendered.
+    columns: Vec<Column>,
+    /// Marker row for the last rendered line, empty if it has no flagged bytes.
+    marker_row: String,
+    /// Whether all valid bytes are of class [`CellClass::Normal`] (no cursor, selection, highlights, overlays, flags
+    /// or stripes).
+    unstyled: bool,
+    /// Index and data index of the start of the record on the line when decoding records (see `line_decoder`).
+    record: Option<(usize, usize)>,
+    /// Decoded text of the record on the line, if any.
+    decoded: Option<String>,
+    /// Byte order of groups in `Endianness::Auto` order, guessed from the data.
+    guessed_endianness: Endianness,
+}
+
+#[cfg(feature = "std")]
+impl<'a> LineRenderer<'a> {
+    fn new(data: Source<'a>, context: RenderContext<'a>, settings: &'a HexOutSettings, offset: usize, end: usize) -> Self {
+        Self {
+            data: Window::new(data),
+            context,
+            settings,
+            offset,
+            end,
+            columns: ordered_columns(settings),
+            marker_row: String::new(),
+            unstyled: settings.cursor.or(context.cursor).is_none()
+                && settings.selection.as_ref().or(context.selection).is_none()
+                && settings.highlights.is_empty()
+                && context.highlights.is_empty()
+                && context.overlays.is_empty()
+                && context.flags.is_empty()
+                && settings.stripe_style.is_none(),
+            record: None,
+            decoded: None,
+            guessed_endianness: guessed_endianness(data, settings, offset),
         }
-        if settings.big_endian {
-            // Big-endian: fill the group from the end
-            group_value = (group_value << 8) | byte as u128;
+    }
+
+    fn cell_class(&self, index: usize) -> CellClass {
+        let settings = self.settings;
+        let context = &self.context;
+        if index < self.offset || index >= self.data.len() {
+            CellClass::Missing
+        } else if context.cursor.or(settings.cursor) == Some(index) {
+            CellClass::Cursor
+        } else if context.selection.or(settings.selection.as_ref()).is_some_and(|selection| selection.contains(&index)) {
+            CellClass::Selected
+        } else if settings.highlights.iter().chain(context.highlights).any(|highlight| highlight.contains(&index)) {
+            CellClass::Highlighted
+        } else if let Some(overlay) = context.overlays.iter().position(|(range, _)| range.contains(&index)) {
+            CellClass::Overlay(overlay)
+        } else if context.flags.get(index).is_some_and(|flag| *flag != 0) {
+            CellClass::Flagged
+        } else if self.is_striped(index) {
+            CellClass::Striped
         } else {
-            // Little-endian: fill the group from the start
-            group_value |= (byte as u128) << (8 * group_byte_index);
+            CellClass::Normal
+        }
+    }
+
+    /// Whether the byte at data index `index` is on a striped line or group.
+    fn is_striped(&self, index: usize) -> bool {
+        let settings = self.settings;
+        if settings.stripe_style.is_none() {
+            return false;
+        }
+        let grid_start = if settings.align_address { 0 } else { self.offset };
+        let position = index - grid_start;
+        let stripe = match self.record {
+            // Groups of records are counted from the start of the record
+            Some((_, record_start)) if settings.stripe_groups => (index - record_start) / settings.group_size,
+            Some((record, _)) => record,
+            None if settings.stripe_groups => position / settings.group_size % settings.groups_per_line,
+            None => position / (settings.group_size * settings.groups_per_line),
+        };
+        stripe % 2 == 1
+    }
+
+    /// Data indices of the bytes of the line from `line_start` to `line_end` that are neither before the offset nor past
+    /// the end of the data.
+    fn valid_range(&self, line_start: usize, line_end: usize) -> Range<usize> {
+        let valid_end = line_end.min(self.data.len());
+        line_start.max(self.offset).min(valid_end)..valid_end
+    }
+
+    /// Render the line from data index `line_start` to `line_end` into `result` (without a trailing newline).
+    ///
+    /// The line ends with a whole group, possibly reaching past the data.  Returns `false` if it did and no further
+    /// lines should follow.
+    fn render(&mut self, result: &mut String, line_start: usize, line_end: usize) -> bool {
+        let settings = self.settings;
+        let total_bytes_per_line = settings.group_size * settings.groups_per_line;
+        let out_of_bounds = line_end > self.data.len();
+        // Records are padded like the last line, lining up the columns after them
+        let is_last_line = line_end >= self.end || out_of_bounds || self.record.is_some();
+        let digits = if settings.uppercase { HEX_DIGITS_UPPER } else { HEX_DIGITS_LOWER };
+        let context = self.context;
+        let Range { start: valid_start, end: valid_end } = self.valid_range(line_start, line_end);
+        let annotation = settings
+            .line_annotator
+            .as_ref()
+            .and_then(|annotator| annotator.annotate(valid_start, &self.data[valid_start..valid_end]));
+        let frame = settings.borders.vertical();
+        // Columns producing output on this line
+        let shown = |column: &Column| match column {
+            Column::Decoded => self.decoded.is_some(),
+            Column::Annotation => annotation.is_some(),
+            _ => true,
+        };
+        // The last line may need padding (borders and `pad_lines` always need full lines)
+        let full = frame.is_some() || settings.pad_lines;
+        let followed = |area: Column| self.columns.iter().skip_while(|column| **column != area).skip(1).any(shown);
+        let pad_hex = is_last_line && (full || (settings.pad_last_line && followed(Column::Hex)));
+        let ascii_closed = !settings.ascii_close.is_empty() || followed(Column::Ascii);
+        let pad_ascii = is_last_line && (full || (settings.pad_last_line && settings.pad_last_ascii && ascii_closed));
+        // Base64 is only written for the valid bytes, so lines with bytes before the offset need padding too
+        let pad_base64 = full || (followed(Column::Base64) && (settings.pad_last_line || !is_last_line));
+        // Without padding, a last line ending at the centerline ends without it, leaving no trailing whitespace
+        let area = LineArea {
+            start: line_start,
+            end: line_end,
+            drop_centerline: is_last_line && !full && !settings.pad_last_line,
+            drop_ascii_placeholders: is_last_line && !full && !ascii_closed && settings.ascii_placeholder == ' ',
+        };
+        // Append the columns, the ones of fixed width within the borders if drawing them
+        let mut frame_open = frame.is_some();
+        if let Some(vertical) = frame {
+            result.push(vertical);
+            result.push(' ');
+        }
+        let mut previous: Option<Column> = None;
+        for &column in self.columns.iter().filter(|column| shown(column)) {
+            match (frame, previous) {
+                (Some(vertical), Some(_)) if frame_open && column.is_fixed_width() => push_separator(result, vertical),
+                // The first column of variable width follows the borders
+                (Some(vertical), Some(_)) if frame_open => {
+                    result.push(' ');
+                    result.push(vertical);
+                    result.push(' ');
+                    frame_open = false;
+                }
+                (Some(_), Some(_)) => result.push(' '),
+                (None, Some(previous)) => {
+                    push_str_escaped(result, previous.separator(column, settings), context.escape_html)
+                }
+                (_, None) => {}
+            }
+            match column {
+                Column::LineNumber => {
+                    let grid_start = if settings.align_address { 0 } else { self.offset };
+                    let number = match self.record {
+                        Some((record, _)) => record,
+                        None => (line_start - grid_start) / total_bytes_per_line,
+                    };
+                    let _ = write!(result, "{number:>width$}", width = settings.address_width);
+                }
+                Column::Address => {
+                    let style = settings.address_style.as_ref();
+                    if let Some(template) = &settings.address_link {
+                        result.push_str("\x1b]8;;");
+                        result.push_str(&expand_offsets(template, line_start, settings));
+                        result.push_str("\x1b\\");
+                    }
+                    result.push_str(style.map_or("", |style| &style.prefix));
+                    let _ =
+                        write!(result, "{:0width$x}", display_address(settings, line_start), width = settings.address_width);
+                    result.push_str(style.map_or("", |style| &style.suffix));
+                    if settings.address_link.is_some() {
+                        result.push_str("\x1b]8;;\x1b\\");
+                    }
+                }
+                Column::Hex => {
+                    let width = self.push_hex_area(result, &area, false);
+                    if pad_hex {
+                        push_spaces(result, hex_area_width(settings).saturating_sub(width));
+                    }
+                }
+                Column::Ascii => {
+                    if frame.is_none() {
+                        push_str_escaped(result, &settings.ascii_open, context.escape_html);
+                    }
+                    let width = self.push_ascii_area(result, &area, false);
+                    if pad_ascii {
+                        push_spaces(result, ascii_area_width(settings).saturating_sub(width));
+                    }
+                    if frame.is_none() {
+                        push_str_escaped(result, &settings.ascii_close, context.escape_html);
+                    }
+                }
+                Column::Base64 => {
+                    base64::push_base64(result, &self.data[valid_start..valid_end]);
+                    if pad_base64 {
+                        push_spaces(result, Column::Base64.width(settings) - base64::base64_width(valid_end - valid_start));
+                    }
+                }
+                Column::Checksum => {
+                    let sum = self.data[valid_start..valid_end].iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+                    result.push(digits[(sum >> 4) as usize] as char);
+                    result.push(digits[(sum & 0x0f) as usize] as char);
+                }
+                Column::Digest => {
+                    let digest = digest::similarity_digest(&self.data[valid_start..valid_end]);
+                    for shift in (0..32).step_by(4).rev() {
+                        result.push(digits[(digest >> shift) as usize & 0x0f] as char);
+                    }
+                }
+                Column::ByteCount => {
+                    let count = valid_end - valid_start;
+                    let _ = write!(result, "({count} byte{})", if count == 1 { "" } else { "s" });
+                }
+                Column::Decoded => {
+                    if let Some(decoded) = &self.decoded {
+                        push_str_escaped(result, decoded, context.escape_html);
+                    }
+                }
+                Column::Annotation => {
+                    if let Some(annotation) = &annotation {
+                        push_str_escaped(result, annotation, context.escape_html);
+                    }
+                }
+            }
+            previous = Some(column);
         }
-        group_byte_index += 1;
-        // Check if the group is full
-        if group_byte_index >= settings.group_size {
+        if let Some(vertical) = frame.filter(|_| frame_open) {
+            result.push(' ');
+            result.push(vertical);
+        }
+        // Prepare the marker row to go underneath, lined up with the flagged bytes
+        let mut marker_row = std::mem::take(&mut self.marker_row);
+        marker_row.clear();
+        let has_markers = settings.flag_marker.is_some()
+            && (valid_start..valid_end).any(|index| self.cell_class(index) == CellClass::Flagged);
+        if has_markers {
+            if let Some(vertical) = frame {
+                marker_row.push(vertical);
+                marker_row.push(' ');
+            }
+            let mut previous: Option<Column> = None;
+            for &column in self.columns.iter().filter(|column| column.is_fixed_width()) {
+                match (frame, previous) {
+                    (Some(vertical), Some(_)) => push_separator(&mut marker_row, vertical),
+                    (None, Some(previous)) => {
+                        push_spaces(&mut marker_row, visible_width(previous.separator(column, settings)))
+                    }
+                    (_, None) => {}
+                }
+                match column {
+                    Column::Hex => {
+                        let width = self.push_hex_area(&mut marker_row, &area, true);
+                        if pad_hex {
+                            push_spaces(&mut marker_row, hex_area_width(settings).saturating_sub(width));
+                        }
+                    }
+                    Column::Ascii => {
+                        if frame.is_none() {
+                            push_spaces(&mut marker_row, visible_width(&settings.ascii_open));
+                        }
+                        let width = self.push_ascii_area(&mut marker_row, &area, true);
+                        if pad_ascii {
+                            push_spaces(&mut marker_row, ascii_area_width(settings).saturating_sub(width));
+                        }
+                        if frame.is_none() {
+                            push_spaces(&mut marker_row, visible_width(&settings.ascii_close));
+                        }
+                    }
+                    _ => push_spaces(&mut marker_row, column.width(settings)),
+                }
+                previous = Some(column);
+            }
+            if let Some(vertical) = frame {
+                marker_row.push(' ');
+                marker_row.push(vertical);
+            } else if !settings.pad_lines {
+                marker_row.truncate(marker_row.trim_end().len());
+            }
+        }
+        self.marker_row = marker_row;
+        !out_of_bounds
+    }
+
+    /// Bytes of the group starting at data index `group_start`, if they are all valid.
+    fn group_bytes(&self, group_start: usize) -> Option<&[u8]> {
+        self.data.get(group_start..group_start + self.settings.group_size).filter(|_| group_start >= self.offset)
+    }
+
+    /// Class shared by all bytes of the valid group starting at data index `group_start`, if there is one.
+    fn group_class(&self, group_start: usize) -> Option<CellClass> {
+        if self.unstyled {
+            return Some(CellClass::Normal);
+        }
+        let class = self.cell_class(group_start);
+        let group_end = group_start + self.settings.group_size;
+        (group_start + 1..group_end).all(|index| self.cell_class(index) == class).then_some(class)
+    }
+
+    /// Whether the centerline follows the group with index `group_index` of `area`.
+    fn centerline_after(&self, group_index: usize, area: &LineArea) -> bool {
+        let settings = self.settings;
+        let is_last_group = area.start + (group_index + 1) * settings.group_size >= area.end;
+        settings.show_centerline
+            && group_index + 1 == settings.groups_per_line / 2
+            && !(area.drop_centerline && is_last_group)
+    }
+
+    /// Whether an alignment guide precedes the group with index `group_index` of `area`.
+    fn guide_before(&self, group_index: usize, area: &LineArea) -> bool {
+        let settings = self.settings;
+        settings.guide_interval > 0
+            && (group_index * settings.group_size).is_multiple_of(settings.guide_interval)
+            && !self.centerline_after(group_index - 1, area)
+    }
+
+    /// Append the hex area of `area` to `out`, or with `markers` set, the part of the marker row underneath it (the
+    /// flag marker under flagged bytes, spaces elsewhere).  Returns the number of characters taken up, without the
+    /// style prefixes and suffixes.
+    fn push_hex_area(&self, out: &mut String, area: &LineArea, markers: bool) -> usize {
+        let settings = self.settings;
+        let context = self.context;
+        let group_size = settings.group_size;
+        let digits = if settings.uppercase { HEX_DIGITS_UPPER } else { HEX_DIGITS_LOWER };
+        let digits_per_byte = digits_per_byte(settings);
+        // Octal and decimal groups are converted as a whole, as their digits don't map to bytes
+        let radix = Some(settings.group_radix).filter(|radix| *radix != GroupRadix::Hex && !settings.binary);
+        let text_mode = settings.text_mode;
+        let marker = settings.flag_marker.unwrap_or(' ');
+        let mut width = 0;
+        for (group_index, group_start) in (area.start..area.end).step_by(group_size).enumerate() {
             // Add space before group if not the first group
-            if !line.is_empty() {
-                line.push(' ');
+            if group_index > 0 {
+                if !markers && self.guide_before(group_index, area) {
+                    let style = settings.guide_style.as_ref();
+                    out.push_str(style.map_or("", |style| &style.prefix));
+                    push_char(out, settings.guide_char, context.escape_html);
+                    out.push_str(style.map_or("", |style| &style.suffix));
+                } else {
+                    out.push(' ');
+                }
+                width += 1;
             }
-            if cursor < offset {
-                // If before the offset, just add spaces
-                line.push_str("  ".repeat(settings.group_size).as_str());
-            // Group is full, output it
+            width += group_width(settings);
+            if group_start + group_size <= self.offset {
+                // If the whole group is before the offset, just add spaces
+                push_spaces(out, group_width(settings));
             } else {
-                let mut value = if settings.uppercase {
-                    format!("{group_value:0width$X}", width = settings.group_size * 2)
+                if markers {
+                    push_spaces(out, settings.group_prefix.chars().count());
                 } else {
-                    format!("{group_value:0width$x}", width = settings.group_size * 2)
+                    push_str_escaped(out, &settings.group_prefix, context.escape_html);
+                }
+                // Output the bytes most significant first, merging neighbouring cells with the same styling
+                let bytes = self.group_bytes(group_start);
+                let group_class = bytes.and_then(|_| self.group_class(group_start));
+                let endianness = match settings.group_endianness.get(group_index).copied().unwrap_or(settings.endianness) {
+                    Endianness::Auto => self.guessed_endianness,
+                    endianness => endianness,
                 };
-                // If cursor - group_byte_index + 1 < offset, we are still before the offset
-                // replace the leading digits (or trailing if big-endian) with question marks
-                if cursor.saturating_sub(group_byte_index) < offset || out_of_bounds_count > 0 {
-                    let missing_bytes = if out_of_bounds_count > 0 {
-                        out_of_bounds_count
-                    } else {
-                        settings.group_size - (group_byte_index - cursor % settings.group_size)
-                    };
-                    let replace_chars = (missing_bytes * 2).min(value.len());
-                    let mut replacement = settings.invalid_data_placeholder.to_string().repeat(replace_chars);
-                    if let Some(prefix) = &settings.hex_out_error_prefix 
-                    {
-                        replacement = format!("{prefix}{replacement}");
-                    }
-                    if let Some(postfix) = &settings.hex_out_error_postfix 
-                    {
-                        replacement = format!("{replacement}{postfix}");
-                    }
-                    // The decision to replace leading or trailing characters is based on endiannes and whether we are missing bytes
-                    // The following condition represents an XOR operation for the following table:
-                    // Big-endian  | Out of bounds bytes | Replace leading chars
-                    //    false    |        false        |       false
-                    //    false    |         true        |        true
-                    //     true    |        false        |        true
-                    //     true    |         true        |       false
-                    if settings.big_endian != (out_of_bounds_count > 0) {
-                        // Big-endian: replace leading characters
-                        value.replace_range(0..replace_chars, &replacement);
-                    } else {
-                        // Little-endian: replace trailing characters
-                        let start = value.len() - replace_chars;
-                        value.replace_range(start..value.len(), &replacement);
-                    }
-                }
-                line.push_str(&value);
+                let orders: &[Endianness] =
+                    if settings.dual_endianness { &[Endianness::Little, Endianness::Big] } else { &[endianness] };
+                let mut run_class = CellClass::Normal;
+                for (order, endianness) in orders.iter().enumerate() {
+                    if order > 0 {
+                        if markers {
+                            out.push(' ');
+                        } else {
+                            switch_style(settings, &context, out, run_class, CellClass::Normal);
+                            run_class = CellClass::Normal;
+                            out.push('/');
+                        }
+                    }
+                    match (bytes, group_class, radix) {
+                        // Whole groups in a single style are converted at once
+                        (Some(bytes), Some(class), radix) if !markers => {
+                            let hex_class = class.in_hex_area(settings);
+                            switch_style(settings, &context, out, run_class, hex_class);
+                            run_class = hex_class;
+                            match radix {
+                                Some(radix) => radix.push_value(out, endianness.read(bytes), group_size),
+                                None => push_group_hex(out, bytes, *endianness, digits, settings.binary),
+                            }
+                        }
+                        (bytes, _, Some(radix)) => {
+                            let group = group_start..group_start + group_size;
+                            let mut classes = group.map(|index| self.cell_class(index));
+                            if markers {
+                                let marker = if classes.any(|class| class == CellClass::Flagged) { marker } else { ' ' };
+                                out.extend(std::iter::repeat_n(marker, group_digits(settings)));
+                            } else {
+                                // The group takes the style of the first of its bytes standing out from the line
+                                let class = classes
+                                    .find(|class| !matches!(class, CellClass::Normal | CellClass::Striped))
+                                    .unwrap_or_else(|| self.cell_class(group_start));
+                                let hex_class = class.in_hex_area(settings);
+                                switch_style(settings, &context, out, run_class, hex_class);
+                                run_class = hex_class;
+                                match bytes {
+                                    Some(bytes) => radix.push_value(out, endianness.read(bytes), group_size),
+                                    None => {
+                                        for _ in 0..group_digits(settings) {
+                                            push_char(out, settings.invalid_data_placeholder, context.escape_html);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        _ => {
+                            for position in 0..group_size {
+                                let index = group_start + endianness.byte_offset(position, group_size);
+                                let class = self.cell_class(index);
+                                if markers {
+                                    let marker = if class == CellClass::Flagged { marker } else { ' ' };
+                                    out.extend(std::iter::repeat_n(marker, digits_per_byte));
+                                    continue;
+                                }
+                                let hex_class = class.in_hex_area(settings);
+                                switch_style(settings, &context, out, run_class, hex_class);
+                                run_class = hex_class;
+                                if class == CellClass::Missing {
+                                    for _ in 0..digits_per_byte {
+                                        push_char(out, settings.invalid_data_placeholder, context.escape_html);
+                                    }
+                                } else {
+                                    push_byte_digits(out, self.data[index], digits, settings.binary);
+                                }
+                            }
+                        }
+                    }
+                }
+                switch_style(settings, &context, out, run_class, CellClass::Normal);
+                if settings.inline_chars && markers {
+                    push_spaces(out, inline_chars_width(settings));
+                } else if settings.inline_chars {
+                    // The characters follow in memory order, like in the ASCII area
+                    out.push('(');
+                    match bytes {
+                        Some(bytes) => bytes.iter().for_each(|byte| text_mode.push_byte(out, *byte, context.escape_html)),
+                        None => {
+                            for index in group_start..group_start + group_size {
+                                if index < self.offset || index >= self.data.len() {
+                                    text_mode.push_placeholder(out, settings.ascii_placeholder, context.escape_html);
+                                } else {
+                                    text_mode.push_byte(out, self.data[index], context.escape_html);
+                                }
+                            }
+                        }
+                    }
+                    out.push(')');
+                }
+                if markers {
+                    push_spaces(out, settings.group_suffix.chars().count());
+                } else {
+                    push_str_escaped(out, &settings.group_suffix, context.escape_html);
+                }
             }
-            group_index += 1;
-            let is_last_line = cursor + 1 == last_line_offset;
-            // Check if we need to add a centerline
-            if group_index == settings.groups_per_line / 2 && settings.show_centerline {
-                if settings.show_ascii {
-                    ascii.push(' ');
-                }
-                line.push(' ');
+            if self.centerline_after(group_index, area) {
+                out.push(' ');
+                width += 1;
             }
-            if group_index == settings.groups_per_line || is_last_line || out_of_bounds_count > 0 {
-                // End of line or last line
-                // Add the address offset if enabled
-                if settings.show_offset {
-                    result.push_str(&format!(
-                        "{:0width$x}: ",
-                        addr,
-                        width = settings.address_width
-                    ));
-                }
-                // If this is the last line, we may need to pad the line
-                if (is_last_line || out_of_bounds_count > 0) && settings.show_ascii {
-                    // If centerline is shown, account for that space
-                    let centerline_size = if settings.show_centerline { 1 } else { 0 };
-                    // Calculate padding needed
-                    let pad_length = ((settings.group_size * 2 + 1) * settings.groups_per_line + centerline_size - 1).saturating_sub(line.len());
-                    let ascii_pad_length = (total_bytes_per_line + centerline_size).saturating_sub(ascii.len());
-                    // Pad both hex and ASCII parts
-                    line.push_str(
-                        &" ".repeat(pad_length),
-                    );
-                    if settings.show_ascii {
-                        ascii.push_str(
-                            &" ".repeat(ascii_pad_length),
-                        );
+        }
+        width
+    }
+
+    /// Append the ASCII area of `area` (without the enclosing bars) to `out`, or with `markers` set, the part of the
+    /// marker row underneath it.  Returns the number of characters taken up, without the style prefixes and suffixes.
+    fn push_ascii_area(&self, out: &mut String, area: &LineArea, markers: bool) -> usize {
+        let settings = self.settings;
+        let context = self.context;
+        let group_size = settings.group_size;
+        let text_mode = settings.text_mode;
+        let cell_width = text_mode.cell_width();
+        let marker = settings.flag_marker.unwrap_or(' ');
+        let mut width = 0;
+        // Styles run across groups
+        let mut run_class = CellClass::Normal;
+        for (group_index, group_start) in (area.start..area.end).step_by(group_size).enumerate() {
+            let bytes = self.group_bytes(group_start);
+            match (bytes, bytes.and_then(|_| self.group_class(group_start))) {
+                // Whole groups in a single style are copied at once
+                (Some(bytes), Some(class)) if !markers => {
+                    let ascii_class = class.in_ascii_area(settings);
+                    switch_style(settings, &context, out, run_class, ascii_class);
+                    run_class = ascii_class;
+                    bytes.iter().for_each(|byte| text_mode.push_byte(out, *byte, context.escape_html));
+                    width += group_size * cell_width;
+                }
+                _ => {
+                    for index in group_start..group_start + group_size {
+                        if area.drop_ascii_placeholders && index >= self.data.len() {
+                            break;
+                        }
+                        let class = self.cell_class(index);
+                        width += cell_width;
+                        if markers {
+                            let marker = if class == CellClass::Flagged { marker } else { ' ' };
+                            out.extend(std::iter::repeat_n(marker, cell_width));
+                            continue;
+                        }
+                        let ascii_class = class.in_ascii_area(settings);
+                        switch_style(settings, &context, out, run_class, ascii_class);
+                        run_class = ascii_class;
+                        if class == CellClass::Missing {
+                            text_mode.push_placeholder(out, settings.ascii_placeholder, context.escape_html);
+                        } else {
+                            text_mode.push_byte(out, self.data[index], context.escape_html);
+                        }
                     }
                 }
-                // Append the line and ASCII representation to the result
-                result.push_str(&line);
-                if settings.show_ascii {
-                    result.push(' ');
-                    result.push('|');
-                    result.push_str(&ascii);
-                    result.push('|');
-                }
-                // Add newline if not the last line
-                if !is_last_line {
-                    result.push('\n');
-                }
-                // Stop processing if we're past the data length
-                if out_of_bounds_count > 0 {
-                    break;
-                }
-                // Rinse and repeat
-                line.clear();
-                ascii.clear();
-                addr += total_bytes_per_line;
-                group_index = 0;
             }
-            group_value = 0;
-            group_byte_index = 0;
+            if settings.ascii_centerline && self.centerline_after(group_index, area) {
+                switch_style(settings, &context, out, run_class, CellClass::Normal);
+                run_class = CellClass::Normal;
+                out.push(' ');
+                width += 1;
+            }
         }
-        cursor += 1;
+        switch_style(settings, &context, out, run_class, CellClass::Normal);
+        width
+    }
+}
+
+/// The groups of a line rendered by [`LineRenderer`].
+#[cfg(feature = "std")]
+struct LineArea {
+    /// Data index of the first byte of the line.
+    start: usize,
+    /// End of the last group, possibly past the end of the data.
+    end: usize,
+    /// Whether to leave out a centerline following the last group.
+    drop_centerline: bool,
+    /// Whether to leave out the ASCII placeholders of bytes past the end of the data, which would only be trailing
+    /// spaces of an ASCII column ending the line, as in the output of `xxd`.
+    drop_ascii_placeholders: bool,
+}
+
+/// The byte order of the groups of a dump of `data` from `offset` on, if any of them are in `Endianness::Auto` order.
+#[cfg(feature = "std")]
+fn guessed_endianness(data: Source, settings: &HexOutSettings, offset: usize) -> Endianness {
+    if settings.endianness != Endianness::Auto && !settings.group_endianness.contains(&Endianness::Auto) {
+        return Endianness::Little;
+    }
+    // Start with the first group shown
+    let grid_start = if settings.align_address { 0 } else { offset };
+    let start = (offset - (offset - grid_start) % settings.group_size).min(data.len());
+    guess_endianness(&data.bytes_from(start, 0), settings.group_size)
+}
+
+/// Pad the line written to `buffer` from `start` to the width of a full line if `pad_lines` is set.
+#[cfg(feature = "std")]
+fn pad_line(settings: &HexOutSettings, buffer: &mut String, start: usize) {
+    if settings.pad_lines {
+        let width = visible_width(&buffer[start..]);
+        push_spaces(buffer, line_width(settings).saturating_sub(width));
+    }
+}
+
+/// Append the hex (or binary) digits of the group `bytes` to `buffer`, most significant byte first.
+#[cfg(feature = "std")]
+fn push_group_hex(buffer: &mut String, bytes: &[u8], endianness: Endianness, digits: &[u8; 16], binary: bool) {
+    let push_byte = |byte: &u8| push_byte_digits(buffer, *byte, digits, binary);
+    match endianness {
+        Endianness::Little | Endianness::Auto => bytes.iter().rev().for_each(push_byte),
+        Endianness::Big => bytes.iter().for_each(push_byte),
+        Endianness::Pdp => endianness.read(bytes).to_be_bytes()[16 - bytes.len()..].iter().for_each(push_byte),
+    }
+}
+
+/// Append the two hex digits of `byte` to `buffer`, or its eight binary digits if `binary` is set.
+#[cfg(feature = "std")]
+fn push_byte_digits(buffer: &mut String, byte: u8, digits: &[u8; 16], binary: bool) {
+    if binary {
+        buffer.extend((0..8).rev().map(|bit| if byte >> bit & 1 == 1 { '1' } else { '0' }));
+    } else {
+        buffer.push(digits[(byte >> 4) as usize] as char);
+        buffer.push(digits[(byte & 0x0f) as usize] as char);
+    }
+}
+
+/// Number of characters `text` takes up on screen, not counting ANSI escape sequences.
+#[cfg(feature = "std")]
+fn visible_width(text: &str) -> usize {
+    strip_ansi(text).chars().count()
+}
+
+/// Append `count` spaces to `buffer`.
+#[cfg(feature = "std")]
+fn push_spaces(buffer: &mut String, count: usize) {
+    buffer.extend(std::iter::repeat_n(' ', count));
+}
+
+/// Substitute the `{len}`, `{offset}` and `{address}` placeholders of a caption template.
+#[cfg(feature = "std")]
+fn expand_caption(caption: &str, data_len: usize, offset: usize, settings: &HexOutSettings) -> String {
+    expand_offsets(&caption.replace("{len}", &data_len.to_string()), offset, settings)
+}
+
+/// Substitute the `{offset}` and `{address}` placeholders of a template.
+#[cfg(feature = "std")]
+fn expand_offsets(template: &str, offset: usize, settings: &HexOutSettings) -> String {
+    template
+        .replace("{offset}", &format!("{:0width$x}", offset, width = settings.address_width))
+        .replace(
+            "{address}",
+            &format!("{:0width$x}", display_address(settings, offset), width = settings.address_width),
+        )
+}
+
+/// The address displayed for `offset`: mapped by `address_map` if set, otherwise `address_origin + offset`.
+#[cfg(feature = "std")]
+pub(crate) fn display_address(settings: &HexOutSettings, offset: usize) -> u128 {
+    match &settings.address_map {
+        Some(map) => map.map(offset) as u128,
+        None => settings.address_origin.wrapping_add(offset as u128),
     }
-    Ok(result)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests;
@@ -0,0 +1,65 @@
+use std::{borrow::Cow, ops::Range};
+
+use crate::{groups_per_line_for_width, render_dump, HexOutError, HexOutSettings, RenderContext};
+
+/// Per-call options of [`hex_out_with_options`]: the line window and the state that changes with every frame of an
+/// interactive viewer, so long-lived [`HexOutSettings`] need not be cloned or mutated to render it.
+///
+/// The cursor, selection and caption take precedence over those of the settings, and the highlights are added to
+/// theirs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenderOptions<'a> {
+    /// The starting offset, see [`hex_out`](crate::hex_out).
+    pub offset: usize,
+    /// The first line to render (0-based).
+    pub start_line: usize,
+    /// The number of lines to render (0 for all lines from `start_line` on).
+    pub line_count: usize,
+    /// Index of the byte under the cursor, styled with `cursor_style`.
+    pub cursor: Option<usize>,
+    /// Range of selected bytes, styled with `selection_style`.
+    pub selection: Option<Range<usize>>,
+    /// Ranges of highlighted bytes, styled with `highlight_style`.
+    pub highlights: &'a [Range<usize>],
+    /// Caption rendered above the dump, with the placeholders of `HexOutSettings::caption`.
+    pub caption: Option<&'a str>,
+    /// Maximum width of the lines in characters, e.g. the width of the terminal: if lines with all `groups_per_line`
+    /// groups would be wider, fewer groups are shown per line, as given by
+    /// [`groups_per_line_for_width`](crate::groups_per_line_for_width).  Variable width columns such as line
+    /// annotations are not counted.
+    pub max_line_width: Option<usize>,
+}
+
+/// Generate a hex dump of `data` with the layout and styles of `settings` and the line window, cursor, selection,
+/// highlights, caption and maximum line width of `options`.
+///
+/// Lines are numbered with the groups per line actually shown, so the line window of a dump fitted to a maximum line
+/// width should be chosen with [`groups_per_line_for_width`].
+/// ```rust
+/// use hexout::{hex_out_with_options, HexOutSettings, RenderOptions, Style};
+/// let settings = HexOutSettings { groups_per_line: 4, cursor_style: Some(Style::new("[", "]")), ..Default::default() };
+/// let options = RenderOptions { cursor: Some(5), start_line: 1, ..Default::default() };
+/// let dump = hex_out_with_options(b"abcdefgh", &settings, &options).unwrap();
+/// assert_eq!(dump, "00000004: 65 [66]  67 68 |e[f] gh|");
+/// ```
+pub fn hex_out_with_options(
+    data: &[u8],
+    settings: &HexOutSettings,
+    options: &RenderOptions,
+) -> Result<String, HexOutError> {
+    let context = RenderContext {
+        cursor: options.cursor,
+        selection: options.selection.as_ref(),
+        highlights: options.highlights,
+        caption: options.caption,
+        ..Default::default()
+    };
+    let groups_per_line = options.max_line_width.map(|width| groups_per_line_for_width(settings, width));
+    let settings = match groups_per_line {
+        Some(groups_per_line) if groups_per_line < settings.groups_per_line => {
+            Cow::Owned(HexOutSettings { groups_per_line, ..settings.clone() })
+        }
+        _ => Cow::Borrowed(settings),
+    };
+    render_dump(data.into(), context, &settings, options.offset, options.start_line, options.line_count)
+}
@@ -0,0 +1,35 @@
+use std::{num::NonZero, panic, thread};
+
+use crate::{hex_out, HexOutError, HexOutSettings};
+
+/// Generate hex dumps of many buffers at once, e.g. of the packets of a capture, sharing the settings between threads.
+///
+/// The buffers are split among up to [`available_parallelism`](thread::available_parallelism) scoped threads, and
+/// each is dumped in full as by [`hex_out`]; the results are in the order of the buffers.  Settings, themes and the
+/// other configuration types are `Send + Sync`, so services can equally share them with a thread pool of their own.
+/// A panic of a callback of the settings is passed on.
+/// ```rust
+/// use hexout::{dump_many, hex_out, HexOutSettings};
+/// let packets: Vec<Vec<u8>> = (0..100u8).map(|length| (0..length).collect()).collect();
+/// let batches = packets.iter().map(Vec::as_slice).collect::<Vec<_>>();
+/// let settings = HexOutSettings::default();
+/// let dumps = dump_many(&batches, &settings);
+/// assert_eq!(dumps[42].as_ref().unwrap(), &hex_out(&packets[42], &settings, 0, 0, 0).unwrap());
+/// ```
+pub fn dump_many(batches: &[&[u8]], settings: &HexOutSettings) -> Vec<Result<String, HexOutError>> {
+    let dump_all = |batches: &[&[u8]]| batches.iter().map(|data| hex_out(data, settings, 0, 0, 0)).collect::<Vec<_>>();
+    let threads = thread::available_parallelism().map_or(1, NonZero::get).min(batches.len());
+    if threads <= 1 {
+        return dump_all(batches);
+    }
+    thread::scope(|scope| {
+        let workers = batches
+            .chunks(batches.len().div_ceil(threads))
+            .map(|chunk| scope.spawn(move || dump_all(chunk)))
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_else(|payload| panic::resume_unwind(payload)))
+            .collect()
+    })
+}
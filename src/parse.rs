@@ -0,0 +1,45 @@
+use crate::HexOutError;
+
+/// Parse a string of hex digits into bytes, the inverse of a dump's hex area without addresses.
+///
+/// Digits may be upper or lower case, and whitespace, underscores and `0x` prefixes (at the start of the string or
+/// after whitespace) are skipped anywhere, so `"deadBEEF"`, `"de ad be ef"`, `"0xdead_beef"` and `"0xde 0xad 0xbe
+/// 0xef"` all give the same bytes.  Digits pair up across separators, so every byte needs both of its digits.
+///
+/// Other characters are rejected with [`HexOutError::InvalidHexDigit`], and a trailing half byte with
+/// [`HexOutError::OddHexDigitCount`].
+/// ```rust
+/// use hexout::parse_hex_str;
+/// assert_eq!(parse_hex_str("deadBEEF  ff 00").unwrap(), [0xde, 0xad, 0xbe, 0xef, 0xff, 0x00]);
+/// assert_eq!(parse_hex_str("0x1234_5678").unwrap(), [0x12, 0x34, 0x56, 0x78]);
+/// ```
+pub fn parse_hex_str(text: &str) -> Result<Vec<u8>, HexOutError> {
+    let mut bytes = Vec::with_capacity(text.len() / 2);
+    let mut high: Option<u8> = None;
+    let mut token_start = true;
+    let mut chars = text.char_indices().peekable();
+    while let Some((position, character)) = chars.next() {
+        if character.is_whitespace() {
+            token_start = true;
+            continue;
+        }
+        if character == '0' && token_start && chars.peek().is_some_and(|(_, next)| matches!(next, 'x' | 'X')) {
+            chars.next();
+            token_start = false;
+            continue;
+        }
+        token_start = false;
+        if character == '_' {
+            continue;
+        }
+        let digit = character.to_digit(16).ok_or(HexOutError::InvalidHexDigit { position, character })? as u8;
+        match high.take() {
+            Some(high) => bytes.push(high << 4 | digit),
+            None => high = Some(digit),
+        }
+    }
+    match high {
+        Some(_) => Err(HexOutError::OddHexDigitCount),
+        None => Ok(bytes),
+    }
+}
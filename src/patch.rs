@@ -0,0 +1,161 @@
+use std::fmt::Write;
+
+use crate::{parse_hex_str, HexOutError};
+
+/// A run of changed bytes in a [`Patch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchEntry {
+    /// Data index of the first changed byte.
+    pub offset: usize,
+    /// The bytes before the change, empty for bytes appended to the data.
+    pub old: Vec<u8>,
+    /// The bytes after the change, empty for bytes cut off the end of the data.
+    pub new: Vec<u8>,
+}
+
+/// The changes between two versions of some data, as runs of changed bytes ordered by offset.
+///
+/// A patch is created with [`Patch::diff`], written as text (`offset: old -> new` per entry) or JSON, read back from
+/// text with [`Patch::from_text`] and applied with [`apply_patch`].
+/// ```rust
+/// use hexout::{apply_patch, Patch};
+/// let old = b"firmware v1.0".to_vec();
+/// let patch = Patch::diff(&old, b"firmware v1.2!");
+/// assert_eq!(patch.to_text(), "0000000c: 30 -> 32\n0000000d: - -> 21");
+/// let mut data = old.clone();
+/// apply_patch(&mut data, &Patch::from_text(&patch.to_text()).unwrap()).unwrap();
+/// assert_eq!(data, b"firmware v1.2!");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Patch {
+    /// The runs of changed bytes, ordered by offset and not overlapping.
+    pub entries: Vec<PatchEntry>,
+}
+
+impl Patch {
+    /// The changes turning `old` into `new`: one entry per run of changed bytes, and one for the bytes appended to or
+    /// cut off the end of `old` if the lengths differ.
+    pub fn diff(old: &[u8], new: &[u8]) -> Self {
+        let common = old.len().min(new.len());
+        let mut entries = Vec::new();
+        let mut index = 0;
+        while index < common {
+            if old[index] == new[index] {
+                index += 1;
+                continue;
+            }
+            let start = index;
+            while index < common && old[index] != new[index] {
+                index += 1;
+            }
+            let (removed, added) = (old[start..index].to_vec(), new[start..index].to_vec());
+            entries.push(PatchEntry { offset: start, old: removed, new: added });
+        }
+        if old.len() != new.len() {
+            entries.push(PatchEntry { offset: common, old: old[common..].to_vec(), new: new[common..].to_vec() });
+        }
+        Self { entries }
+    }
+
+    /// Whether the patch changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The patch as text, one `offset: old -> new` line per entry with the offset in hex (at least 8 digits) and the
+    /// bytes in hex separated by spaces, `-` standing for no bytes.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            if index > 0 {
+                text.push('\n');
+            }
+            let _ = write!(text, "{:08x}: ", entry.offset);
+            push_bytes(&mut text, &entry.old, " ");
+            text.push_str(" -> ");
+            push_bytes(&mut text, &entry.new, " ");
+        }
+        text
+    }
+
+    /// Read a patch written by [`to_text`](Self::to_text).  Empty lines are skipped, and the bytes may be written in
+    /// any form [`parse_hex_str`] takes.
+    ///
+    /// Lines that are not of the form `offset: old -> new` are rejected with [`HexOutError::InvalidPatchLine`].
+    pub fn from_text(text: &str) -> Result<Self, HexOutError> {
+        let mut entries: Vec<PatchEntry> = Vec::new();
+        for (index, line) in text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let invalid = || HexOutError::InvalidPatchLine { line: index + 1 };
+            let (offset, change) = line.split_once(':').ok_or_else(invalid)?;
+            let (old, new) = change.split_once("->").ok_or_else(invalid)?;
+            let offset = usize::from_str_radix(offset.trim(), 16).map_err(|_| invalid())?;
+            let parse = |bytes: &str| match bytes.trim() {
+                "-" => Ok(Vec::new()),
+                bytes => parse_hex_str(bytes).map_err(|_| invalid()),
+            };
+            let entry = PatchEntry { offset, old: parse(old)?, new: parse(new)? };
+            if entries.last().is_some_and(|last| !follows(last, &entry)) {
+                return Err(invalid());
+            }
+            entries.push(entry);
+        }
+        Ok(Self { entries })
+    }
+
+    /// The patch as a JSON array of objects with the offset as a number and the bytes as hex strings, e.g.
+    /// `[{"offset": 12, "old": "30", "new": "32"}]`.
+    pub fn to_json(&self) -> String {
+        if self.entries.is_empty() {
+            return "[]".to_string();
+        }
+        let mut json = String::from("[\n");
+        for (index, entry) in self.entries.iter().enumerate() {
+            let _ = write!(json, "  {{\"offset\": {}, \"old\": \"", entry.offset);
+            push_bytes(&mut json, &entry.old, "");
+            json.push_str("\", \"new\": \"");
+            push_bytes(&mut json, &entry.new, "");
+            json.push_str(if index + 1 < self.entries.len() { "\"},\n" } else { "\"}\n" });
+        }
+        json.push(']');
+        json
+    }
+}
+
+/// Apply `patch` to `data`, replacing the old bytes of each entry with its new ones.
+///
+/// The old bytes of every entry are checked before changing anything, and a mismatch, an entry reaching past the end
+/// of the data or entries out of order or overlapping are rejected with [`HexOutError::PatchMismatch`], leaving `data`
+/// untouched.
+pub fn apply_patch(data: &mut Vec<u8>, patch: &Patch) -> Result<(), HexOutError> {
+    let mut previous: Option<&PatchEntry> = None;
+    for entry in &patch.entries {
+        let old = entry.offset.checked_add(entry.old.len()).and_then(|end| data.get(entry.offset..end));
+        if old != Some(&entry.old[..]) || previous.is_some_and(|previous| !follows(previous, entry)) {
+            return Err(HexOutError::PatchMismatch { offset: entry.offset });
+        }
+        previous = Some(entry);
+    }
+    // Entries changing the length only shift the ones after them
+    for entry in patch.entries.iter().rev() {
+        data.splice(entry.offset..entry.offset + entry.old.len(), entry.new.iter().copied());
+    }
+    Ok(())
+}
+
+/// Whether `entry` starts after the bytes replaced by `previous` (or after its offset, if it only inserts bytes).
+fn follows(previous: &PatchEntry, entry: &PatchEntry) -> bool {
+    previous.offset.saturating_add(previous.old.len().max(1)) <= entry.offset
+}
+
+/// Append `bytes` in hex to `buffer`, separated by `separator`, or `-` if there are none and `separator` isn't empty.
+fn push_bytes(buffer: &mut String, bytes: &[u8], separator: &str) {
+    if bytes.is_empty() && !separator.is_empty() {
+        buffer.push('-');
+    }
+    for (index, byte) in bytes.iter().enumerate() {
+        if index > 0 {
+            buffer.push_str(separator);
+        }
+        let _ = write!(buffer, "{byte:02x}");
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{Annotation, HexOutError};
+
+/// Read annotations from a CSV table of fields, such as the template results of 010 Editor or the pattern data of
+/// ImHex exported as CSV, or a bookmark list written by [`bookmarks_csv`](crate::bookmarks_csv).
+///
+/// The first row names the columns; the field name is taken from a `name` or `label` column, its offset from an
+/// `offset`, `start` or `address` column and its size from a `size` or `length` column, and a `value` column, if
+/// any, becomes the value of the annotation.  Other columns are ignored, and so is the case of the column names.
+/// Fields may be quoted, but not span lines.
+/// Numbers are decimal, or hex with a `0x` prefix or an `h` suffix (as 010 Editor writes them, e.g. `1Ch`).
+///
+/// Rows that are missing a column or whose offset or size is not a number, and tables without all three columns,
+/// are rejected with [`HexOutError::InvalidPatternLine`].
+/// ```rust
+/// use hexout::{parse_pattern_csv, Annotation};
+/// let csv = "Name,Value,Start,Size,Color,Comment\nmagic,PK,0h,2h,Fg: Bg:,\nversion,20,2h,2h,Fg: Bg:,";
+/// assert_eq!(
+///     parse_pattern_csv(csv).unwrap(),
+///     [Annotation::new(0..2, "magic").with_value("PK"), Annotation::new(2..4, "version").with_value("20")]
+/// );
+/// ```
+pub fn parse_pattern_csv(text: &str) -> Result<Vec<Annotation>, HexOutError> {
+    let mut rows = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = rows.next() else {
+        return Ok(Vec::new());
+    };
+    let header = split_row(header).into_iter().map(|name| name.trim().to_ascii_lowercase()).collect::<Vec<_>>();
+    let column = |names: &[&str]| header.iter().position(|name| names.contains(&name.as_str()));
+    let invalid = |line: usize| HexOutError::InvalidPatternLine { line };
+    let name = column(&["name", "label"]).ok_or_else(|| invalid(1))?;
+    let offset = column(&["offset", "start", "address"]).ok_or_else(|| invalid(1))?;
+    let size = column(&["size", "length"]).ok_or_else(|| invalid(1))?;
+    let value = column(&["value"]);
+    let mut annotations = Vec::new();
+    for (index, row) in rows {
+        let fields = split_row(row);
+        let invalid_row = || invalid(index + 1);
+        let field = |column: usize| fields.get(column).map(|field| field.trim()).ok_or_else(invalid_row);
+        let start = parse_number(field(offset)?).ok_or_else(invalid_row)?;
+        let end = parse_number(field(size)?).and_then(|size| start.checked_add(size)).ok_or_else(invalid_row)?;
+        let mut annotation = Annotation::new(start..end, field(name)?);
+        let value = value.and_then(|value| fields.get(value)).map(|value| value.trim());
+        annotation.value = value.filter(|value| !value.is_empty()).map(str::to_string);
+        annotations.push(annotation);
+    }
+    Ok(annotations)
+}
+
+/// Split a CSV row into its fields, unquoting quoted ones.
+fn split_row(row: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => field.push(c),
+        }
+    }
+    fields
+}
+
+/// Parse a decimal number, or a hex one with a `0x` prefix or an `h` suffix.
+fn parse_number(text: &str) -> Option<usize> {
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        usize::from_str_radix(hex, 16).ok()
+    } else if let Some(hex) = text.strip_suffix('h').or_else(|| text.strip_suffix('H')) {
+        usize::from_str_radix(hex, 16).ok()
+    } else {
+        text.parse().ok()
+    }
+}
@@ -0,0 +1,155 @@
+use std::{borrow::Cow, fmt::Debug, sync::Arc};
+
+use crate::{hex_out_annotated, Annotation, HexOutError, HexOutSettings, LineAnnotator};
+
+type TransformFn = dyn Fn(&mut Vec<u8>) + Send + Sync;
+type AnalyzerFn = dyn Fn(&[u8]) -> Vec<Annotation> + Send + Sync;
+type RendererFn = dyn Fn(&[u8], &[Annotation], &HexOutSettings) -> Result<String, HexOutError> + Send + Sync;
+type EmitterFn = dyn Fn(&str) + Send + Sync;
+
+/// A dump built from pluggable stages, run in a fixed order on each call to [`run`](Self::run):
+///
+/// 1. **transform**: rewrite a copy of the data, e.g. to redact secrets (the data is only copied if there is a
+///    transform);
+/// 2. **analyze**: derive [`Annotation`]s from the transformed data, e.g. with a [`Layout`](crate::Layout) or
+///    [`verify_checksums`](crate::verify_checksums);
+/// 3. **annotate**: add line notes with [`LineAnnotator`]s, e.g. from a
+///    [`GroupClassifier`](crate::GroupClassifier), after that of the settings;
+/// 4. **render**: turn the data, annotations and settings into text, with [`hex_out_annotated`] unless replaced;
+/// 5. **emit**: pass the text on, e.g. to a log.
+///
+/// Stages of the same kind run in the order they were added, each analyzer adding to the annotations of the previous
+/// ones.  A pipeline holds no state between runs, so one can be shared between threads and reused for many dumps.
+/// ```rust
+/// use std::sync::{Arc, Mutex};
+/// use hexout::{HexOutSettings, Layout, LineAnnotator, Pipeline};
+/// let settings = HexOutSettings { groups_per_line: 8, show_ascii: false, ..Default::default() };
+/// let log = Arc::new(Mutex::new(Vec::new()));
+/// let pipeline = Pipeline::new(settings)
+///     .transform(|data| data[2..6].fill(0))
+///     .analyze(|data| Layout::new().u16_be("id").bytes("key", 4).apply(data))
+///     .annotate(LineAnnotator::new(|_, bytes| Some(format!("{} bytes", bytes.len()))))
+///     .emit({
+///         let log = log.clone();
+///         move |dump| log.lock().unwrap().push(dump.to_string())
+///     });
+/// let dump = pipeline.run(b"\x00\x07secret").unwrap();
+/// assert_eq!(dump, "00000000: 00 07 00 00  00 00 65 74 8 bytes, id=7, key=00000000");
+/// assert_eq!(log.lock().unwrap()[..], [dump]);
+/// ```
+#[derive(Clone)]
+pub struct Pipeline {
+    settings: HexOutSettings,
+    transforms: Vec<Arc<TransformFn>>,
+    analyzers: Vec<Arc<AnalyzerFn>>,
+    annotators: Vec<LineAnnotator>,
+    renderer: Option<Arc<RendererFn>>,
+    emitters: Vec<Arc<EmitterFn>>,
+}
+
+impl Pipeline {
+    /// A pipeline rendering with `settings` and no other stages, giving the same dump as [`hex_out`](crate::hex_out).
+    pub fn new(settings: HexOutSettings) -> Self {
+        Self {
+            settings,
+            transforms: Vec::new(),
+            analyzers: Vec::new(),
+            annotators: Vec::new(),
+            renderer: None,
+            emitters: Vec::new(),
+        }
+    }
+
+    /// Add a transform stage rewriting the data.  Transforms may change the length of the data.
+    pub fn transform(mut self, transform: impl Fn(&mut Vec<u8>) + Send + Sync + 'static) -> Self {
+        self.transforms.push(Arc::new(transform));
+        self
+    }
+
+    /// Add an analysis stage deriving annotations from the transformed data.
+    pub fn analyze(mut self, analyzer: impl Fn(&[u8]) -> Vec<Annotation> + Send + Sync + 'static) -> Self {
+        self.analyzers.push(Arc::new(analyzer));
+        self
+    }
+
+    /// Add a line annotator, whose note follows those of the settings and of the annotators added before it.
+    pub fn annotate(mut self, annotator: LineAnnotator) -> Self {
+        self.annotators.push(annotator);
+        self
+    }
+
+    /// Render with `renderer` instead of [`hex_out_annotated`], e.g. to produce HTML.  The settings passed to it
+    /// include the line annotators added with [`annotate`](Self::annotate).
+    pub fn render_with(
+        mut self,
+        renderer: impl Fn(&[u8], &[Annotation], &HexOutSettings) -> Result<String, HexOutError> + Send + Sync + 'static,
+    ) -> Self {
+        self.renderer = Some(Arc::new(renderer));
+        self
+    }
+
+    /// Add an emit stage receiving each rendered dump.
+    pub fn emit(mut self, emitter: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.emitters.push(Arc::new(emitter));
+        self
+    }
+
+    /// The settings the dump is rendered with, before the line annotators are added to them.
+    pub fn settings(&self) -> &HexOutSettings {
+        &self.settings
+    }
+
+    /// Run all stages on `data`, returning the rendered dump after emitting it.
+    ///
+    /// Fails with the error of the renderer, in which case nothing is emitted.
+    pub fn run(&self, data: &[u8]) -> Result<String, HexOutError> {
+        let mut data = Cow::Borrowed(data);
+        for transform in &self.transforms {
+            transform(data.to_mut());
+        }
+        let annotations = self.analyzers.iter().flat_map(|analyzer| analyzer(&data)).collect::<Vec<_>>();
+        let settings = match self.annotators.is_empty() {
+            true => Cow::Borrowed(&self.settings),
+            false => {
+                let mut annotators = self.settings.line_annotator.iter().cloned().collect::<Vec<_>>();
+                annotators.extend(self.annotators.iter().cloned());
+                Cow::Owned(HexOutSettings { line_annotator: Some(joined(annotators)), ..self.settings.clone() })
+            }
+        };
+        let dump = match &self.renderer {
+            Some(renderer) => renderer(&data, &annotations, &settings)?,
+            None => hex_out_annotated(&data, &annotations, &settings, 0, 0, 0)?,
+        };
+        for emitter in &self.emitters {
+            emitter(&dump);
+        }
+        Ok(dump)
+    }
+}
+
+impl Debug for Pipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("settings", &self.settings)
+            .field("transforms", &self.transforms.len())
+            .field("analyzers", &self.analyzers.len())
+            .field("annotators", &self.annotators)
+            .field("renderer", &self.renderer.as_ref().map(|_| ".."))
+            .field("emitters", &self.emitters.len())
+            .finish()
+    }
+}
+
+/// A line annotator joining the notes of `annotators` with `, `, noting nothing if none of them does.
+fn joined(annotators: Vec<LineAnnotator>) -> LineAnnotator {
+    LineAnnotator::new(move |line_offset, line_bytes| {
+        let notes = annotators.iter().filter_map(|annotator| annotator.annotate(line_offset, line_bytes));
+        notes.reduce(|mut note, next| {
+            if !note.is_empty() && !next.is_empty() {
+                note.push_str(", ");
+            }
+            note.push_str(&next);
+            note
+        })
+    })
+}
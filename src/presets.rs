@@ -0,0 +1,58 @@
+use crate::{CompactSettings, Endianness, HexOutSettings};
+
+impl HexOutSettings {
+    /// Settings for the output of plain `xxd`: 16 bytes per line in 2-byte groups in memory order, and the ASCII
+    /// column after two spaces instead of bars.  Lines start at the offset, as with `xxd -s`.
+    ///
+    /// The lines are those of `xxd` byte for byte (`hex_out` leaves out the newline after the last line).  To change the
+    /// grouping or the case, adjust the preset like `xxd -g`, `-c` and `-u` would:
+    /// ```rust
+    /// use hexout::{hex_out, HexOutSettings};
+    /// assert_eq!(
+    ///     hex_out(b"hexample in xxd, ok!", &HexOutSettings::xxd(), 0, 0, 0).unwrap(),
+    ///     "00000000: 6865 7861 6d70 6c65 2069 6e20 7878 642c  hexample in xxd,\n\
+    ///      00000010: 206f 6b21                                 ok!"
+    /// );
+    /// let settings = HexOutSettings { group_size: 4, groups_per_line: 2, uppercase: true, ..HexOutSettings::xxd() };
+    /// assert_eq!(hex_out(b"\xca\xfe\xba\xbe", &settings, 0, 0, 0).unwrap(), "00000000: CAFEBABE           ....");
+    /// ```
+    pub fn xxd() -> Self {
+        Self {
+            align_address: false,
+            endianness: Endianness::Big,
+            invalid_data_placeholder: ' ',
+            ascii_open: " ".to_string(),
+            ascii_close: String::new(),
+            ..CompactSettings::XXD.into()
+        }
+    }
+
+    /// Settings for the output of `hexdump -C`: 16 single bytes per line with two spaces after the address and at the
+    /// centerline of the hex area, the ASCII column in bars closed right after its last byte, runs of repeated lines
+    /// squeezed into a `*` line, and the address of the end of the data on a line of its own.  Lines start at the
+    /// offset, as with `hexdump -s`.
+    ///
+    /// The output is that of `hexdump -C` byte for byte, so golden files made with it compare equal (`hex_out` leaves
+    /// out the newline after the last line).  These are the settings of [`CompactSettings::HEXDUMP_C`] with the two
+    /// spaces after the address and the lead-in of the ASCII column, which the compact preset can't hold as strings.
+    /// ```rust
+    /// use hexout::{hex_out, HexOutSettings};
+    /// let mut data = b"hexdump -C layout".to_vec();
+    /// data.resize(64, 0);
+    /// assert_eq!(
+    ///     hex_out(&data, &HexOutSettings::hexdump_c(), 0, 0, 0).unwrap(),
+    ///     "00000000  68 65 78 64 75 6d 70 20  2d 43 20 6c 61 79 6f 75  |hexdump -C layou|\n\
+    ///      00000010  74 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |t...............|\n\
+    ///      00000020  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|\n\
+    ///      *\n\
+    ///      00000040"
+    /// );
+    /// ```
+    pub fn hexdump_c() -> Self {
+        Self {
+            ascii_open: " |".to_string(),
+            address_separator: "  ".to_string(),
+            ..CompactSettings::HEXDUMP_C.into()
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{push_truncation, ByteHistogram, DumpLines, HexOutError, HexOutSettings, RenderContext};
+
+/// Generate an overview of `data` in at most `max_lines` lines, sampling lines evenly spaced across the whole dump,
+/// e.g. to get a feel for the contents of a huge file at a glance.
+///
+/// Data that fits is dumped in full.  Otherwise the first and the last line are shown with evenly spaced lines in
+/// between, each run of lines left out in between noted as `… 12 lines skipped …`, and the lines and notes together
+/// take at most `max_lines` lines (0 for no limit).  The caption, header and borders come on top of them.  Lines are
+/// sampled by their position only, so `skip_fill`, `squeeze_repeats`, `line_filter` and `line_decoder` are ignored,
+/// and `byte_histogram` too, as only the sampled lines pass.
+/// ```rust
+/// use hexout::{preview, HexOutSettings};
+/// let data = (0u8..=255).collect::<Vec<u8>>();
+/// let settings = HexOutSettings { show_ascii: false, ..Default::default() };
+/// assert_eq!(
+///     preview(&data, &settings, 5).unwrap(),
+///     "00000000: 00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f\n\
+///      … 6 lines skipped …\n\
+///      00000070: 70 71 72 73 74 75 76 77  78 79 7a 7b 7c 7d 7e 7f\n\
+///      … 7 lines skipped …\n\
+///      000000f0: f0 f1 f2 f3 f4 f5 f6 f7  f8 f9 fa fb fc fd fe ff"
+/// );
+/// ```
+pub fn preview(data: &[u8], settings: &HexOutSettings, max_lines: usize) -> Result<String, HexOutError> {
+    let settings = HexOutSettings {
+        skip_fill: None,
+        squeeze_repeats: false,
+        byte_histogram: ByteHistogram::None,
+        line_filter: None,
+        line_decoder: None,
+        ..settings.clone()
+    };
+    let mut lines = DumpLines::new(data.into(), RenderContext::default(), &settings, 0, 0, 0)?;
+    let total_lines = lines.total_lines;
+    // A line and the note after it take two lines, except for the last line
+    let samples = match max_lines {
+        0 => total_lines,
+        _ if total_lines <= max_lines => total_lines,
+        _ => max_lines.div_ceil(2),
+    };
+    let sample = |index: usize| match samples {
+        0 | 1 => 0,
+        _ => index * (total_lines - 1) / (samples - 1),
+    };
+    let total_bytes_per_line = settings.group_size * settings.groups_per_line;
+    let grid_start = lines.line_start;
+    let mut result = Vec::new();
+    let mut next = 1;
+    loop {
+        let mut line = String::new();
+        if !lines.next_line(&mut line) {
+            break;
+        }
+        result.push(line);
+        if lines.current_line.is_none() || samples == total_lines {
+            continue;
+        }
+        // Move on to the next sample, past the end after the last one
+        if next == samples {
+            lines.line_start = lines.renderer.end;
+            continue;
+        }
+        let skipped = sample(next) - sample(next - 1) - 1;
+        if skipped > 0 {
+            let mut note = String::new();
+            push_truncation(&settings, skipped, "skipped", &mut note);
+            result.push(note);
+        }
+        lines.line_start = grid_start + sample(next) * total_bytes_per_line;
+        next += 1;
+    }
+    Ok(result.join("\n"))
+}
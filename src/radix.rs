@@ -0,0 +1,42 @@
+use std::fmt::Write;
+
+/// The radix the groups of the hex area are shown in (see
+/// [`HexOutSettings::group_radix`](crate::HexOutSettings::group_radix)).
+///
+/// Hex digits take two characters per byte whatever the value, so groups stay lined up by themselves.  Octal and
+/// decimal values have no such fixed width per byte, so each group takes the width of the largest value of its size,
+/// e.g. 5 characters for 2-byte groups in decimal (65535): octal values are padded with zeros as by `od -o`, decimal
+/// values are right-aligned with spaces as by `od -d`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GroupRadix {
+    /// Two hex digits per byte.
+    #[default]
+    Hex,
+    /// The value of the group in octal, padded with zeros.
+    Octal,
+    /// The value of the group in decimal, right-aligned.
+    Decimal,
+}
+
+impl GroupRadix {
+    /// Number of characters of a group of `group_size` bytes, those of its largest value.
+    pub(crate) fn group_digits(self, group_size: usize) -> usize {
+        let bits = group_size as u32 * 8;
+        match self {
+            GroupRadix::Hex => group_size * 2,
+            GroupRadix::Octal => bits.div_ceil(3) as usize,
+            GroupRadix::Decimal => (u128::MAX >> (128 - bits)).ilog10() as usize + 1,
+        }
+    }
+
+    /// Append `value`, a group of `group_size` bytes, in octal or decimal (hex digits are appended byte by byte, see
+    /// `push_group_hex`).
+    pub(crate) fn push_value(self, buffer: &mut String, value: u128, group_size: usize) {
+        let width = self.group_digits(group_size);
+        let _ = match self {
+            GroupRadix::Hex => write!(buffer, "{value:0width$x}"),
+            GroupRadix::Octal => write!(buffer, "{value:0width$o}"),
+            GroupRadix::Decimal => write!(buffer, "{value:>width$}"),
+        };
+    }
+}
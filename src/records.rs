@@ -0,0 +1,163 @@
+use crate::{
+    display_address, geometry::column_spans, group_width, validate, Borders, Column, DumpLines, Endianness,
+    HexOutError, HexOutSettings, RenderContext,
+};
+
+/// A line of a dump broken into its parts, see [`hex_dump_records`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HexLineRecord {
+    /// Data index of the start of the line, which is before the offset on a first line aligned to the address.
+    pub offset: usize,
+    /// Displayed address of the line (see `address_origin` and `address_map`).
+    pub address: u128,
+    /// The bytes of the line within the data, from the offset on.
+    pub bytes: Vec<u8>,
+    /// The groups of the line as shown in the hex area, `groups_per_line` of them in display order, `None` for groups
+    /// wholly before the offset or past the end of the data.  Groups cut short show placeholders for the missing
+    /// bytes.
+    pub groups: Vec<Option<String>>,
+    /// The ASCII column as shown, up to the end of the data, without `ascii_open`, `ascii_close` and the centerline.
+    pub ascii: String,
+}
+
+/// Generate the lines of a hex dump as records of their address, bytes, groups and ASCII column, so GUI and TUI
+/// front-ends can lay them out and style them on their own, without parsing a rendered dump.
+///
+/// The groups and the ASCII column are formatted as by [`hex_out`](crate::hex_out) (in byte order, case, radix,
+/// binary digits, group prefix and suffix, inline characters and text mode), but without styles.  Only lines of data
+/// are returned: the caption, header, rules and the notes of lines left out by `skip_fill` are not, and neither are
+/// line annotations or decoded records; `line_filter` and `line_decoder` are ignored.
+///
+/// All other parameters are the same as for [`hex_out`](crate::hex_out).
+/// ```rust
+/// use hexout::{hex_dump_records, HexOutSettings};
+/// let settings = HexOutSettings { group_size: 2, groups_per_line: 2, uppercase: true, ..Default::default() };
+/// let records = hex_dump_records(b"\x01\xabxyz", &settings, 0, 0, 0).unwrap();
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(records[0].groups, [Some("AB01".to_string()), Some("7978".to_string())]);
+/// assert_eq!(records[0].ascii, "..xy");
+/// assert_eq!((records[1].address, &records[1].bytes[..]), (4, &b"z"[..]));
+/// assert_eq!(records[1].groups, [Some("??7A".to_string()), None]);
+/// ```
+pub fn hex_dump_records(
+    data: &[u8],
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<Vec<HexLineRecord>, HexOutError> {
+    validate(settings, offset)?;
+    // Full lines of the hex area followed by the ASCII column put every group and character at a fixed position
+    let plain = HexOutSettings {
+        columns: vec![Column::Hex, Column::Ascii],
+        borders: Borders::None,
+        show_centerline: false,
+        guide_interval: 0,
+        ascii_open: String::new(),
+        ascii_close: "|".to_string(),
+        pad_last_line: true,
+        line_annotator: None,
+        line_filter: None,
+        line_decoder: None,
+        ..settings.without_styles()
+    };
+    let spans = column_spans(&plain).0;
+    let span = |column| spans.iter().find(|(spanned, _)| *spanned == column).map_or(0, |(_, span)| span.start);
+    let (hex_start, ascii_start) = (span(Column::Hex), span(Column::Ascii));
+    let group_size = settings.group_size;
+    let width = group_width(&plain);
+    let cell_width = plain.text_mode.cell_width();
+    let total_bytes_per_line = group_size * settings.groups_per_line;
+    let mut lines = DumpLines::new(data.into(), RenderContext::default(), &plain, offset, start_line, line_count)?;
+    let mut records = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if !lines.next_line(&mut line) {
+            break;
+        }
+        let Some(line_start) = lines.current_line else {
+            continue;
+        };
+        let chars = line.chars().collect::<Vec<_>>();
+        let text = |start: usize, len: usize| {
+            chars[start.min(chars.len())..(start + len).min(chars.len())].iter().collect::<String>()
+        };
+        let groups = (0..settings.groups_per_line)
+            .map(|index| {
+                let group_start = line_start + index * group_size;
+                let shown = group_start + group_size > offset && group_start < data.len();
+                shown.then(|| text(hex_start + index * (width + 1), width))
+            })
+            .collect();
+        let line_end = (line_start + total_bytes_per_line).min(data.len());
+        records.push(HexLineRecord {
+            offset: line_start,
+            address: display_address(settings, line_start),
+            bytes: data[line_start.max(offset).min(line_end)..line_end].to_vec(),
+            groups,
+            ascii: text(ascii_start, line_end.saturating_sub(line_start) * cell_width),
+        });
+    }
+    Ok(records)
+}
+
+/// Generate the numeric values of the groups of the lines of a hex dump, e.g. for statistics or plots of sensor data
+/// that should line up with a dump of it, without parsing the text of the dump.
+///
+/// Each line holds `groups_per_line` values in display order, read in the byte order of the group as by
+/// [`hex_out`](crate::hex_out) (`endianness`, `group_endianness`, and the byte order guessed for `Endianness::Auto`);
+/// with `dual_endianness` the values are those in `endianness`.  Groups not wholly within the data from the offset on
+/// are `None`.  The lines are those of [`hex_dump_records`]: lines of data only, without those left out by `skip_fill`
+/// or `squeeze_repeats`, and with `line_filter` and `line_decoder` ignored.
+///
+/// All other parameters are the same as for [`hex_out`](crate::hex_out).
+/// ```rust
+/// use hexout::{hex_dump_values, Endianness, HexOutSettings};
+/// let settings =
+///     HexOutSettings { group_size: 2, groups_per_line: 2, endianness: Endianness::Big, ..Default::default() };
+/// let values = hex_dump_values(b"\x01\x02\x03\x04\x05", &settings, 0, 0, 0).unwrap();
+/// assert_eq!(values, [vec![Some(0x0102), Some(0x0304)], vec![None, None]]);
+/// ```
+pub fn hex_dump_values(
+    data: &[u8],
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<Vec<Vec<Option<u128>>>, HexOutError> {
+    validate(settings, offset)?;
+    // The text of the lines is not used, so leave out everything that doesn't decide which lines there are
+    let plain = HexOutSettings {
+        columns: vec![Column::Hex],
+        line_annotator: None,
+        line_filter: None,
+        line_decoder: None,
+        ..settings.without_styles()
+    };
+    let group_size = settings.group_size;
+    let mut lines = DumpLines::new(data.into(), RenderContext::default(), &plain, offset, start_line, line_count)?;
+    let guessed = lines.renderer.guessed_endianness;
+    let mut values = Vec::new();
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if !lines.next_line(&mut line) {
+            break;
+        }
+        let Some(line_start) = lines.current_line else {
+            continue;
+        };
+        let groups = (0..settings.groups_per_line).map(|index| {
+            let group_start = line_start + index * group_size;
+            let group = data.get(group_start..group_start + group_size).filter(|_| group_start >= offset)?;
+            let endianness = match settings.group_endianness.get(index).copied().unwrap_or(settings.endianness) {
+                Endianness::Auto => guessed,
+                endianness => endianness,
+            };
+            Some(endianness.read(group))
+        });
+        values.push(groups.collect());
+    }
+    Ok(values)
+}
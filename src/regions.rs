@@ -0,0 +1,61 @@
+use crate::{hex_out, HexOutError, HexOutSettings};
+
+/// A region of a process's memory, as read from `/proc/<pid>/maps` or a core dump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryRegion<'a> {
+    /// Address of the first byte of the region.
+    pub base: u128,
+    /// Permissions in `/proc/<pid>/maps` notation, e.g. `r-xp`.
+    pub permissions: &'a str,
+    /// Optional name of the mapping (file path, `[heap]`, `[stack]`, ...).
+    pub name: Option<&'a str>,
+    /// Contents of the region.
+    pub data: &'a [u8],
+}
+
+impl<'a> MemoryRegion<'a> {
+    /// Create an unnamed region.
+    pub fn new(base: u128, permissions: &'a str, data: &'a [u8]) -> Self {
+        Self {
+            base,
+            permissions,
+            name: None,
+            data,
+        }
+    }
+
+    /// Set the name of the mapping.
+    pub fn with_name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+}
+
+/// Generate a hex dump of several memory regions, each preceded by a header line in `/proc/<pid>/maps` style
+/// (`start-end permissions name`) and addressed from its base.
+///
+/// The `caption` and `address_origin` settings are replaced per region; everything else applies to every region.
+pub fn hex_out_regions(regions: &[MemoryRegion], settings: &HexOutSettings) -> Result<String, HexOutError> {
+    let mut settings = settings.clone();
+    let mut result = String::new();
+    for region in regions {
+        let mut caption = format!(
+            "{:0width$x}-{:0width$x} {}",
+            region.base,
+            region.base.wrapping_add(region.data.len() as u128),
+            region.permissions,
+            width = settings.address_width
+        );
+        if let Some(name) = region.name {
+            caption.push(' ');
+            caption.push_str(name);
+        }
+        settings.caption = Some(caption);
+        settings.address_origin = region.base;
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result.push_str(&hex_out(region.data, &settings, 0, 0, 0)?);
+    }
+    Ok(result)
+}
@@ -0,0 +1,68 @@
+use crate::HexOutSettings;
+
+/// Data indices of all occurrences of `pattern` in `data`, including overlapping ones, in ascending order.  An empty
+/// pattern occurs nowhere.
+/// ```rust
+/// assert_eq!(hexout::find_all(b"abababa", b"aba"), [0, 2, 4]);
+/// ```
+pub fn find_all(data: &[u8], pattern: &[u8]) -> Vec<usize> {
+    let Some((first, _)) = pattern.split_first() else {
+        return Vec::new();
+    };
+    data.windows(pattern.len())
+        .enumerate()
+        .filter(|(_, window)| window[0] == *first && *window == pattern)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// The line windows showing the hits of a search, as `(start_line, line_count)` pairs to pass to
+/// [`hex_out`](crate::hex_out) with `settings` and `offset`, e.g. to print each match of a binary grep in context.
+///
+/// Each hit covers `hit_len` bytes from its data index (at least one), and its window takes `context` lines before and
+/// after the lines of those bytes.  Windows that overlap or touch are merged into one, and the windows are in
+/// ascending order.  Hits before `offset`, which a dump from it doesn't show, are left out.
+/// ```rust
+/// use hexout::{find_all, hex_out, hit_windows, HexOutSettings};
+/// let mut data = vec![0u8; 64];
+/// data[20..24].copy_from_slice(b"\xde\xad\xbe\xef");
+/// data[50..54].copy_from_slice(b"\xde\xad\xbe\xef");
+/// let settings = HexOutSettings { show_ascii: false, ..Default::default() };
+/// let hits = find_all(&data, b"\xde\xad\xbe\xef");
+/// assert_eq!(hits, [20, 50]);
+/// assert_eq!(hit_windows(&hits, 4, &settings, 0, 0), [(1, 1), (3, 1)]);
+/// let (start_line, line_count) = hit_windows(&hits, 4, &settings, 0, 0)[0];
+/// assert_eq!(
+///     hex_out(&data, &settings, 0, start_line, line_count).unwrap(),
+///     "00000010: 00 00 00 00 de ad be ef  00 00 00 00 00 00 00 00"
+/// );
+/// ```
+pub fn hit_windows(
+    hits: &[usize],
+    hit_len: usize,
+    settings: &HexOutSettings,
+    offset: usize,
+    context: usize,
+) -> Vec<(usize, usize)> {
+    let total_bytes_per_line = settings.group_size.saturating_mul(settings.groups_per_line).max(1);
+    // Lines are counted from the start of the grid, as for the line window of a dump
+    let grid_start = if settings.align_address { 0 } else { offset };
+    let mut spans = hits
+        .iter()
+        .filter(|hit| **hit >= offset)
+        .map(|hit| {
+            let first = (hit - grid_start) / total_bytes_per_line;
+            let last = (hit.saturating_add(hit_len.max(1) - 1) - grid_start) / total_bytes_per_line;
+            (first.saturating_sub(context), last.saturating_add(context).saturating_add(1))
+        })
+        .collect::<Vec<_>>();
+    spans.sort_unstable();
+    let mut windows: Vec<(usize, usize)> = Vec::new();
+    for (first, end) in spans {
+        match windows.last_mut() {
+            Some((start, count)) if first <= *start + *count => *count = (*count).max(end - *start),
+            _ => windows.push((first, end - first)),
+        }
+    }
+    windows
+}
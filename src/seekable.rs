@@ -0,0 +1,119 @@
+use std::{
+    borrow::Cow,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+use crate::{concat::Source, render_dump, HexOutError, HexOutSettings, RenderContext};
+
+/// Data read on demand from a [`Read`] + [`Seek`] source such as a file, for scrollable viewers over files too big to
+/// hold in memory.
+///
+/// Each call to [`hex_out`](Self::hex_out) seeks to the lines asked for and reads only their bytes, unless they were
+/// read before: the bytes read last are kept, with those of the read-ahead set by
+/// [`with_read_ahead`](Self::with_read_ahead) around them.  The lines are
+/// the same as those of a dump of all of the data, including the `{len}` of the caption and the lines left out noted
+/// by `show_truncation`, except that `line_decoder` is ignored: records can only be found by decoding the data from
+/// its start.
+/// ```rust
+/// use std::io::Cursor;
+/// use hexout::{HexOutSettings, SeekableHexSource};
+/// let mut source = SeekableHexSource::new(Cursor::new(vec![0xab; 1 << 20])).unwrap();
+/// let settings = HexOutSettings { groups_per_line: 4, show_ascii: false, ..Default::default() };
+/// assert_eq!(source.line_count(&settings), 1 << 18);
+/// assert_eq!(source.hex_out(&settings, 0x1000, 2).unwrap(), "00004000: ab ab  ab ab\n00004004: ab ab  ab ab");
+/// ```
+#[derive(Debug)]
+pub struct SeekableHexSource<R> {
+    reader: R,
+    /// Length of the data in bytes.
+    len: u64,
+    /// Bytes read last, from data index `buffer_start` on.
+    buffer: Vec<u8>,
+    buffer_start: usize,
+    /// Number of bytes read before and after the lines asked for.
+    read_ahead: usize,
+}
+
+impl<R: Read + Seek> SeekableHexSource<R> {
+    /// Wrap `reader`, seeking to its end to find the length of the data.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let len = reader.seek(SeekFrom::End(0))?;
+        Ok(Self { reader, len, buffer: Vec::new(), buffer_start: 0, read_ahead: 0 })
+    }
+
+    /// Read `read_ahead` bytes before and after the lines asked for whenever they have to be read, so scrolling
+    /// through them in a viewer renders from memory instead of stalling on a read per screen.
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use hexout::{HexOutSettings, SeekableHexSource};
+    /// let mut source = SeekableHexSource::new(Cursor::new(vec![0u8; 1 << 20])).unwrap().with_read_ahead(64 * 1024);
+    /// let settings = HexOutSettings::default();
+    /// // Reads 1 KiB of lines and 64 KiB before and after them, so scrolling up or down renders from memory
+    /// source.hex_out(&settings, 0x1000, 64).unwrap();
+    /// source.hex_out(&settings, 0x1040, 64).unwrap();
+    /// ```
+    pub fn with_read_ahead(mut self, read_ahead: usize) -> Self {
+        self.read_ahead = read_ahead;
+        self
+    }
+
+    /// Length of the data in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether the data is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of lines of a dump of all of the data with `settings`, e.g. for the extent of a scroll bar.
+    pub fn line_count(&self, settings: &HexOutSettings) -> u64 {
+        self.len.div_ceil(settings.group_size.saturating_mul(settings.groups_per_line).max(1) as u64)
+    }
+
+    /// Generate a hex dump of `line_count` lines from `start_line` on (all lines to the end of the data if 0), reading
+    /// only their bytes.
+    ///
+    /// Fails with the I/O error of the reader, or with a [`HexOutError`] wrapped as an I/O error (see
+    /// [`HexOutError`]'s conversion) for invalid settings or data too long to index in memory.
+    pub fn hex_out(&mut self, settings: &HexOutSettings, start_line: usize, line_count: usize) -> io::Result<String> {
+        let len = usize::try_from(self.len).map_err(|_| HexOutError::RangeOverflow)?;
+        let total_bytes_per_line = settings.group_size.saturating_mul(settings.groups_per_line);
+        let start = start_line.checked_mul(total_bytes_per_line).ok_or(HexOutError::RangeOverflow)?.min(len);
+        let end = match line_count {
+            0 => len,
+            _ => start_line
+                .checked_add(line_count)
+                .and_then(|end_line| end_line.checked_mul(total_bytes_per_line))
+                .ok_or(HexOutError::RangeOverflow)?
+                .min(len),
+        };
+        if start < self.buffer_start || end > self.buffer_start + self.buffer.len() {
+            self.buffer_start = start.saturating_sub(self.read_ahead);
+            self.buffer.clear();
+            self.buffer.resize(end.saturating_add(self.read_ahead).min(len) - self.buffer_start, 0);
+            self.reader.seek(SeekFrom::Start(self.buffer_start as u64))?;
+            if let Err(error) = self.reader.read_exact(&mut self.buffer) {
+                self.buffer.clear();
+                return Err(error);
+            }
+        }
+        let settings = match settings.line_decoder {
+            Some(_) => Cow::Owned(HexOutSettings { line_decoder: None, ..settings.clone() }),
+            None => Cow::Borrowed(settings),
+        };
+        let data = Source::Partial { start: self.buffer_start, bytes: &self.buffer, len };
+        Ok(render_dump(data, RenderContext::default(), &settings, 0, start_line, line_count)?)
+    }
+
+    /// A reference to the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// The wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
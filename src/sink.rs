@@ -0,0 +1,88 @@
+use std::io;
+
+/// A writer passing each completed line written to it to a callback, without its line break, so each line of a dump
+/// becomes one log record, e.g. with `|line| log::debug!("{line}")`.  Records of one line each interleave cleanly
+/// with those of other threads, where a multi-line record written by pieces would not.
+///
+/// Implements both [`io::Write`] and [`std::fmt::Write`].  Lines end at `\n` (with an optional `\r` before it), and
+/// invalid UTF-8 is replaced with `U+FFFD`.  A last line without a line break is passed on by
+/// [`flush`](io::Write::flush), or when the sink is dropped.
+/// ```rust
+/// use std::fmt::Write;
+/// use hexout::{hex_out, HexOutSettings, LineSink};
+/// let mut records = Vec::new();
+/// let mut sink = LineSink::new(|line: &str| records.push(line.to_string()));
+/// let settings = HexOutSettings { groups_per_line: 4, ..Default::default() };
+/// writeln!(sink, "{}", hex_out(b"line sink", &settings, 0, 0, 0).unwrap()).unwrap();
+/// drop(sink);
+/// assert_eq!(
+///     records,
+///     ["00000000: 6c 69  6e 65 |li ne|", "00000004: 20 73  69 6e | s in|", "00000008: 6b           |k    |"]
+/// );
+/// ```
+pub struct LineSink<F: FnMut(&str)> {
+    emit: F,
+    /// Bytes of the line written so far.
+    line: Vec<u8>,
+}
+
+impl<F: FnMut(&str)> LineSink<F> {
+    /// A sink passing each line to `emit`.
+    pub fn new(emit: F) -> Self {
+        Self { emit, line: Vec::new() }
+    }
+
+    /// Append `bytes` to the line, passing on every line they complete.
+    fn push(&mut self, bytes: &[u8]) {
+        let mut rest = bytes;
+        while let Some(end) = rest.iter().position(|byte| *byte == b'\n') {
+            self.line.extend_from_slice(&rest[..end]);
+            self.emit_line();
+            rest = &rest[end + 1..];
+        }
+        self.line.extend_from_slice(rest);
+    }
+
+    /// Pass on the line written so far.
+    fn emit_line(&mut self) {
+        if self.line.last() == Some(&b'\r') {
+            self.line.pop();
+        }
+        (self.emit)(&String::from_utf8_lossy(&self.line));
+        self.line.clear();
+    }
+}
+
+impl<F: FnMut(&str)> io::Write for LineSink<F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push(buf);
+        Ok(buf.len())
+    }
+
+    /// Pass on the last line if it lacks a line break.
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.line.is_empty() {
+            self.emit_line();
+        }
+        Ok(())
+    }
+}
+
+impl<F: FnMut(&str)> std::fmt::Write for LineSink<F> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.push(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl<F: FnMut(&str)> Drop for LineSink<F> {
+    fn drop(&mut self) {
+        let _ = io::Write::flush(self);
+    }
+}
+
+impl<F: FnMut(&str)> std::fmt::Debug for LineSink<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "LineSink(..)")
+    }
+}
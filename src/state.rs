@@ -0,0 +1,107 @@
+use crate::{DumpLines, DumpStage, HexOutError, HexOutLines, HexOutSettings};
+
+/// The position of a paused render of [`HexOutLines`], to resume it later, e.g. to render a long dump a few hundred
+/// lines per frame in a single-threaded UI.
+///
+/// A state holds no borrows of the data or settings, so they may be used (but not changed) in between.
+/// ```rust
+/// use hexout::{hex_out, HexOutLines, HexOutSettings};
+/// let data = (0u8..=255).collect::<Vec<u8>>();
+/// let settings = HexOutSettings::default();
+/// let mut lines = HexOutLines::new(&data, &settings, 0, 0, 0).unwrap();
+/// let mut dump = Vec::new();
+/// loop {
+///     // Render up to 5 lines per slice of time
+///     for _ in 0..5 {
+///         match lines.next_line() {
+///             Some(line) => dump.push(line.to_string()),
+///             None => break,
+///         }
+///     }
+///     let state = lines.pause();
+///     if state.is_done() {
+///         break;
+///     }
+///     lines = HexOutLines::resume(&data, &settings, &state).unwrap();
+/// }
+/// assert_eq!(dump.join("\n"), hex_out(&data, &settings, 0, 0, 0).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderState {
+    /// Parameters of the dump.
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+    stage: DumpStage,
+    line_start: usize,
+    /// Marker row of the last line rendered, if still to be emitted.
+    marker_row: Option<String>,
+    lines_rendered: usize,
+    lines_dropped: usize,
+    pending_gap: usize,
+    pending_rule: bool,
+    pending_header: bool,
+    pending_page: bool,
+    previous_line: Vec<u8>,
+    squeezing: bool,
+    byte_counts: [usize; 256],
+    record: usize,
+}
+
+impl RenderState {
+    /// Whether all lines of the dump have been produced.
+    pub fn is_done(&self) -> bool {
+        self.stage == DumpStage::Done
+    }
+
+    /// Number of lines of data produced so far (not counting the caption, header or marker rows).
+    pub fn lines_rendered(&self) -> usize {
+        self.lines_rendered
+    }
+}
+
+impl<'a> HexOutLines<'a> {
+    /// Stop producing lines, returning the position to [`resume`](Self::resume) from.
+    pub fn pause(self) -> RenderState {
+        let lines = self.lines;
+        RenderState {
+            offset: lines.renderer.offset,
+            start_line: lines.start_line,
+            line_count: lines.line_count,
+            stage: lines.stage,
+            line_start: lines.line_start,
+            marker_row: lines.pending_marker_row.then_some(lines.renderer.marker_row),
+            lines_rendered: lines.lines_rendered,
+            lines_dropped: lines.lines_dropped,
+            pending_gap: lines.pending_gap,
+            pending_rule: lines.pending_rule,
+            pending_header: lines.pending_header,
+            pending_page: lines.pending_page,
+            previous_line: lines.previous_line,
+            squeezing: lines.squeezing,
+            byte_counts: lines.byte_counts,
+            record: lines.record,
+        }
+    }
+
+    /// Continue the lines of a dump paused at `state`, with the data and settings it was started with.
+    pub fn resume(data: &'a [u8], settings: &'a HexOutSettings, state: &RenderState) -> Result<Self, HexOutError> {
+        let mut lines = Self::new(data, settings, state.offset, state.start_line, state.line_count)?;
+        let dump: &mut DumpLines = &mut lines.lines;
+        dump.stage = state.stage;
+        dump.line_start = state.line_start;
+        dump.pending_marker_row = state.marker_row.is_some();
+        dump.renderer.marker_row = state.marker_row.clone().unwrap_or_default();
+        dump.lines_rendered = state.lines_rendered;
+        dump.lines_dropped = state.lines_dropped;
+        dump.pending_gap = state.pending_gap;
+        dump.pending_rule = state.pending_rule;
+        dump.pending_header = state.pending_header;
+        dump.pending_page = state.pending_page;
+        dump.previous_line.clone_from(&state.previous_line);
+        dump.squeezing = state.squeezing;
+        dump.byte_counts = state.byte_counts;
+        dump.record = state.record;
+        Ok(lines)
+    }
+}
@@ -0,0 +1,152 @@
+//! [proptest] strategies for settings and data shapes, for property-testing code built on hexout (enabled by the
+//! `proptest` feature).
+//!
+//! The strategies favour the edge cases of the renderer: lines ending in partial groups, offsets inside groups and
+//! line windows reaching past the end of the data.
+//! ```rust
+//! use hexout::strategies::dump_case;
+//! use proptest::prelude::*;
+//!
+//! fn my_log_format(dump: &str) -> String {
+//!     dump.lines().map(|line| format!("> {line}\n")).collect()
+//! }
+//!
+//! proptest!(|(case in dump_case())| {
+//!     let dump = case.render().unwrap();
+//!     prop_assert_eq!(my_log_format(&dump).lines().count(), dump.lines().count());
+//! });
+//! ```
+
+use proptest::prelude::*;
+
+use crate::{hex_out, Borders, Endianness, HexOutError, HexOutSettings, TextMode};
+
+/// Valid settings covering the layout options (group size and count, byte order, columns, text mode, ASCII column
+/// bars, borders, padding, header and rules).  Styles and callbacks are left unset, and addresses of [`data`] fit in `address_width`.
+pub fn settings() -> impl Strategy<Value = HexOutSettings> {
+    let dimensions = (1..=16usize, 1..=8usize, 5..=16usize, 0..=0xffffu16);
+    let order = (
+        prop_oneof![Just(Endianness::Little), Just(Endianness::Big), Just(Endianness::Pdp), Just(Endianness::Auto)],
+        any::<bool>(),
+        prop_oneof![Just(""), Just("0x")],
+        prop_oneof![Just(""), Just(",")],
+        prop_oneof![
+            Just(TextMode::Ascii),
+            Just(TextMode::Escaped),
+            any::<bool>().prop_map(|uppercase| TextMode::Hexified { uppercase }),
+        ],
+        prop_oneof![Just(("|", "|")), Just((" ", ""))],
+        any::<bool>(),
+    );
+    let columns = (
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+    );
+    let frame = (
+        prop_oneof![Just(Borders::None), Just(Borders::Ascii), Just(Borders::Unicode)],
+        0..=3usize,
+        0..=3usize,
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+    );
+    (dimensions, order, columns, frame).prop_map(
+        |(
+            (group_size, groups_per_line, address_width, address_origin),
+            (endianness, dual_endianness, group_prefix, group_suffix, text_mode, (ascii_open, ascii_close), binary),
+            (show_offset, show_ascii, show_line_number, show_byte_count, inline_chars, show_centerline, show_base64),
+            (borders, rule_interval, header_interval, show_header, align_address, strict, pad_lines, pad_last_line, uppercase),
+        )| HexOutSettings {
+            address_origin: address_origin.into(),
+            address_width,
+            align_address,
+            endianness,
+            group_size,
+            groups_per_line,
+            show_ascii,
+            show_centerline,
+            show_header,
+            show_offset,
+            strict,
+            uppercase,
+            borders,
+            rule_interval,
+            pad_lines,
+            pad_last_line,
+            show_byte_count,
+            show_line_number,
+            header_interval,
+            inline_chars,
+            group_prefix: group_prefix.to_string(),
+            group_suffix: group_suffix.to_string(),
+            dual_endianness,
+            text_mode,
+            ascii_open: ascii_open.to_string(),
+            ascii_close: ascii_close.to_string(),
+            show_base64,
+            binary,
+            ..Default::default()
+        },
+    )
+}
+
+/// Data lengths of up to four full lines for `settings`, followed by any number of groups and bytes, so lines and
+/// groups are often left incomplete.
+pub fn data_len(settings: &HexOutSettings) -> impl Strategy<Value = usize> + use<> {
+    let (group_size, groups_per_line) = (settings.group_size, settings.groups_per_line);
+    (0..=4usize, 0..groups_per_line, 0..group_size)
+        .prop_map(move |(lines, groups, bytes)| (lines * groups_per_line + groups) * group_size + bytes)
+}
+
+/// Data of a length chosen by [`data_len`].
+pub fn data(settings: &HexOutSettings) -> impl Strategy<Value = Vec<u8>> + use<> {
+    data_len(settings).prop_flat_map(|len| proptest::collection::vec(any::<u8>(), len))
+}
+
+/// Offsets into data of `data_len` bytes that `settings` accept: aligned to the group size in strict mode, anywhere
+/// otherwise (including inside groups).
+pub fn offset(settings: &HexOutSettings, data_len: usize) -> impl Strategy<Value = usize> + use<> {
+    let group_size = settings.group_size;
+    let strict = settings.strict;
+    (0..=data_len).prop_map(move |offset| if strict { offset - offset % group_size } else { offset })
+}
+
+/// The arguments of a [`hex_out`] call.
+#[derive(Debug, Clone)]
+pub struct DumpCase {
+    pub settings: HexOutSettings,
+    pub data: Vec<u8>,
+    pub offset: usize,
+    pub start_line: usize,
+    pub line_count: usize,
+}
+
+impl DumpCase {
+    /// Render the dump.
+    pub fn render(&self) -> Result<String, HexOutError> {
+        hex_out(&self.data, &self.settings, self.offset, self.start_line, self.line_count)
+    }
+}
+
+/// Dumps with [`settings`], [`data`] and an [`offset`] accepted by the settings, and line windows that may start or
+/// end past the data (a `line_count` of 0 dumps to the end).
+pub fn dump_case() -> impl Strategy<Value = DumpCase> {
+    settings()
+        .prop_flat_map(|settings| (data(&settings), Just(settings)))
+        .prop_flat_map(|(data, settings)| (offset(&settings, data.len()), 0..=5usize, 0..=5usize, Just(data), Just(settings)))
+        .prop_map(|(offset, start_line, line_count, data, settings)| DumpCase {
+            settings,
+            data,
+            offset,
+            start_line,
+            line_count,
+        })
+}
@@ -0,0 +1,129 @@
+use std::{
+    borrow::Cow,
+    io::{self, ErrorKind, Read, Write},
+};
+
+use crate::{concat::Source, validate, DumpLines, DumpStage, Endianness, HexOutError, HexOutSettings, RenderContext};
+
+/// Number of bytes read at a time, rounded down to whole lines.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Write a hex dump of all the data of `reader` to `writer`, reading it a chunk of whole lines at a time, so files of
+/// any size can be dumped without holding them in memory.
+///
+/// The lines are the same as those of [`hex_out`](crate::hex_out) of all of the data, each ended with `\n`, however
+/// short the reads of `reader` are: bytes are collected until a chunk ends at a line boundary, so no group is split
+/// between two chunks.  Rules, headers and page separators due after a number of lines, and runs of lines left out by
+/// `skip_fill` and `squeeze_repeats`, carry on from one chunk to the next.  As the length of the data is only known at
+/// the end, the `{len}` of the caption is shown as `?`; `line_decoder` is ignored, as for
+/// [`SeekableHexSource`](crate::SeekableHexSource), and with `Endianness::Auto` the byte order is guessed from the
+/// first chunk.
+///
+/// Fails with the I/O error of the reader or the writer, or with a [`HexOutError`] wrapped as an I/O error (see
+/// [`HexOutError`]'s conversion) for invalid settings or data too long to index.  The lines of the chunks before the
+/// error have been written.
+/// ```rust
+/// use hexout::{hex_dump_reader, HexOutSettings};
+/// let settings = HexOutSettings { groups_per_line: 4, ..Default::default() };
+/// let mut dump = Vec::new();
+/// hex_dump_reader(&b"streamed"[..], &mut dump, &settings).unwrap();
+/// assert_eq!(String::from_utf8(dump).unwrap(), "00000000: 73 74  72 65 |st re|\n00000004: 61 6d  65 64 |am ed|\n");
+/// ```
+pub fn hex_dump_reader<R: Read, W: Write>(mut reader: R, mut writer: W, settings: &HexOutSettings) -> io::Result<()> {
+    validate(settings, 0)?;
+    let settings = match settings.line_decoder {
+        Some(_) => Cow::Owned(HexOutSettings { line_decoder: None, ..settings.clone() }),
+        None => Cow::Borrowed(settings),
+    };
+    let total_bytes_per_line = settings.group_size * settings.groups_per_line;
+    let lines_per_chunk = (CHUNK_SIZE / total_bytes_per_line).max(1);
+    let caption = settings.caption.as_ref().map(|caption| caption.replace("{len}", "?"));
+    let context = RenderContext { caption: caption.as_deref(), ..Default::default() };
+    let mut buffer = vec![0; lines_per_chunk * total_bytes_per_line];
+    let mut out = String::new();
+    let mut start = 0usize;
+    let mut progress: Option<Progress> = None;
+    loop {
+        let (len, at_end) = fill(&mut reader, &mut buffer)?;
+        let end = start.checked_add(len).ok_or(HexOutError::RangeOverflow)?;
+        let data = Source::Partial { start, bytes: &buffer[..len], len: end };
+        let line_count = if at_end { 0 } else { lines_per_chunk };
+        let mut lines = DumpLines::new(data, context, &settings, 0, start / total_bytes_per_line, line_count)?;
+        if let Some(progress) = progress.take() {
+            progress.restore(&mut lines);
+        }
+        // Stop at the end of a chunk before anything that depends on the lines to come
+        lines.open_ended = !at_end;
+        while lines.next_line(&mut out) {
+            out.push('\n');
+        }
+        writer.write_all(out.as_bytes())?;
+        out.clear();
+        if at_end {
+            return Ok(());
+        }
+        progress = Some(Progress::save(&mut lines));
+        start = end;
+    }
+}
+
+/// Read into `buffer` until it is full or the reader is exhausted, returning the number of bytes read and whether the
+/// reader is exhausted.
+fn fill(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<(usize, bool)> {
+    let mut len = 0;
+    while len < buffer.len() {
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => return Ok((len, true)),
+            Ok(read) => len += read,
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok((len, false))
+}
+
+/// The state of the lines of a dump carried from one chunk to the next.
+struct Progress {
+    lines_rendered: usize,
+    lines_dropped: usize,
+    pending_gap: usize,
+    pending_rule: bool,
+    pending_header: bool,
+    pending_page: bool,
+    previous_line: Vec<u8>,
+    squeezing: bool,
+    byte_counts: [usize; 256],
+    guessed_endianness: Endianness,
+}
+
+impl Progress {
+    fn save(lines: &mut DumpLines) -> Self {
+        Self {
+            lines_rendered: lines.lines_rendered,
+            lines_dropped: lines.lines_dropped,
+            pending_gap: lines.pending_gap,
+            pending_rule: lines.pending_rule,
+            pending_header: lines.pending_header,
+            pending_page: lines.pending_page,
+            previous_line: std::mem::take(&mut lines.previous_line),
+            squeezing: lines.squeezing,
+            byte_counts: lines.byte_counts,
+            guessed_endianness: lines.renderer.guessed_endianness,
+        }
+    }
+
+    /// Continue `lines` of the next chunk where those of the previous one stopped, past the caption and header.
+    fn restore(self, lines: &mut DumpLines) {
+        lines.stage = DumpStage::Lines;
+        lines.lines_rendered = self.lines_rendered;
+        lines.lines_dropped = self.lines_dropped;
+        lines.pending_gap = self.pending_gap;
+        lines.pending_rule = self.pending_rule;
+        lines.pending_header = self.pending_header;
+        lines.pending_page = self.pending_page;
+        lines.previous_line = self.previous_line;
+        lines.squeezing = self.squeezing;
+        lines.byte_counts = self.byte_counts;
+        lines.renderer.guessed_endianness = self.guessed_endianness;
+    }
+}
@@ -0,0 +1,173 @@
+use crate::HexOutSettings;
+
+/// Text inserted around a styled part of the output, e.g. ANSI escape sequences or markup.
+///
+/// Styles can be combined with [`Style::and`], so emphasis such as bold or reverse video can be
+/// layered on top of a color:
+/// ```rust
+/// use hexout::Style;
+/// let style = Style::ansi("31").and(&Style::bold());
+/// assert_eq!(style.apply("42"), "\x1b[31m\x1b[1m42\x1b[0m\x1b[0m");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct Style {
+    /// Text inserted before the styled part.
+    pub prefix: String,
+    /// Text inserted after the styled part.
+    pub suffix: String,
+}
+
+/// Areas of a dump the styles of bytes apply to, see [`HexOutSettings::styled_areas`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum StyledAreas {
+    /// All styles in the hex area; the cursor, selection, highlight, overlay and stripe styles in the ASCII column.
+    #[default]
+    Standard,
+    /// All styles in both areas, so missing and flagged bytes stand out in the ASCII column too.
+    Both,
+    /// All styles in the hex area only.
+    HexOnly,
+    /// All styles in the ASCII column only.
+    AsciiOnly,
+}
+
+impl Style {
+    /// Create a style from a prefix and a suffix.
+    pub fn new(prefix: impl Into<String>, suffix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            suffix: suffix.into(),
+        }
+    }
+
+    /// Create a style from ANSI SGR parameters (e.g. `"1;31"`), reset at the end.
+    pub fn ansi(sgr: &str) -> Self {
+        Self::new(format!("\x1b[{sgr}m"), "\x1b[0m")
+    }
+
+    /// Bold text.
+    pub fn bold() -> Self {
+        Self::ansi("1")
+    }
+
+    /// Dimmed (faint) text.
+    pub fn dim() -> Self {
+        Self::ansi("2")
+    }
+
+    /// Underlined text.
+    pub fn underline() -> Self {
+        Self::ansi("4")
+    }
+
+    /// Blinking text.
+    pub fn blink() -> Self {
+        Self::ansi("5")
+    }
+
+    /// Reverse video (swapped foreground and background colors).
+    pub fn reverse() -> Self {
+        Self::ansi("7")
+    }
+
+    /// Combine this style with another one, nesting `other` inside `self`.
+    pub fn and(&self, other: &Style) -> Style {
+        Style {
+            prefix: format!("{}{}", self.prefix, other.prefix),
+            suffix: format!("{}{}", other.suffix, self.suffix),
+        }
+    }
+
+    /// Wrap `text` in this style.
+    pub fn apply(&self, text: &str) -> String {
+        format!("{}{}{}", self.prefix, text, self.suffix)
+    }
+}
+
+/// Remove ANSI escape sequences (e.g. SGR colors and OSC 8 hyperlinks) from `text`.
+pub fn strip_ansi(text: &str) -> String {
+    plain_with(text, &[])
+}
+
+/// Remove all styling from output rendered with `settings`, so a colored dump can also be logged as plain text
+/// without rendering it twice.
+///
+/// ANSI escape sequences are removed, as are the prefix and suffix strings of every style configured in `settings`
+/// (including `hex_out_error_prefix`/`hex_out_error_postfix`).  Styles made of other text (e.g. markup) are removed
+/// wherever they occur, so they should not also appear in the dumped data.
+/// ```rust
+/// use hexout::{hex_out, plain, HexOutSettings, Style};
+/// let settings = HexOutSettings { cursor: Some(0), cursor_style: Some(Style::new("<u>", "</u>")), ..Default::default() };
+/// let colored = hex_out(b"abc", &settings, 0, 0, 0).unwrap();
+/// assert_eq!(plain(&colored, &settings), hex_out(b"abc", &settings.without_styles(), 0, 0, 0).unwrap());
+/// ```
+pub fn plain(output: &str, settings: &HexOutSettings) -> String {
+    let styles = [
+        &settings.cursor_style,
+        &settings.selection_style,
+        &settings.highlight_style,
+        &settings.flag_style,
+        &settings.address_style,
+        &settings.error_style,
+        &settings.stripe_style,
+        &settings.guide_style,
+    ];
+    let mut markers = styles
+        .into_iter()
+        .flatten()
+        .flat_map(|style| [style.prefix.as_str(), style.suffix.as_str()])
+        .chain(settings.hex_out_error_prefix.as_deref())
+        .chain(settings.hex_out_error_postfix.as_deref())
+        // Escape sequences are stripped anyway, only the literal text of a marker is left to remove
+        .map(strip_ansi)
+        .filter(|marker| !marker.is_empty())
+        .collect::<Vec<_>>();
+    // Prefer the longest marker where several match
+    markers.sort_by_key(|marker| std::cmp::Reverse(marker.len()));
+    plain_with(output, &markers.iter().map(String::as_str).collect::<Vec<_>>())
+}
+
+/// Copy `text`, skipping ANSI escape sequences and any of the literal `markers`.
+fn plain_with(text: &str, markers: &[&str]) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '\x1b' {
+            rest = skip_escape(rest);
+        } else if let Some(marker) = markers.iter().find(|marker| rest.starts_with(**marker)) {
+            rest = &rest[marker.len()..];
+        } else {
+            result.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    result
+}
+
+/// Skip the escape sequence at the start of `text` (which starts with ESC).
+fn skip_escape(text: &str) -> &str {
+    let bytes = text.as_bytes();
+    match bytes.get(1) {
+        // CSI: parameter and intermediate bytes, then a final byte
+        Some(b'[') => {
+            let end = bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b)).map_or(bytes.len(), |end| end + 3);
+            &text[end..]
+        }
+        // OSC: terminated by BEL or ST (ESC \)
+        Some(b']') => {
+            let mut index = 2;
+            while index < bytes.len() {
+                match bytes[index] {
+                    0x07 => return &text[index + 1..],
+                    0x1b if bytes.get(index + 1) == Some(&b'\\') => return &text[index + 2..],
+                    _ => index += 1,
+                }
+            }
+            ""
+        }
+        // Two-character sequences
+        Some(next) if next.is_ascii() => &text[2..],
+        _ => &text[1..],
+    }
+}
@@ -0,0 +1,109 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{guessed_endianness, hex_out, validate, Endianness, HexOutError, HexOutSettings, LineAnnotator};
+
+/// Callback reading the memory a pointer points to, see [`hex_out_telescope`].
+///
+/// The callback receives the value of a group and returns the bytes at that address, or `None` if the value is not a
+/// valid pointer, e.g. because it is outside the memory regions of a crash dump.
+/// ```rust
+/// use hexout::PointerResolver;
+/// let heap = vec![0xaau8; 0x100];
+/// let resolver = PointerResolver::new(move |address| {
+///     let start = usize::try_from(address.checked_sub(0x1000)?).ok()?;
+///     Some(heap.get(start..)?.iter().take(4).copied().collect())
+/// });
+/// assert_eq!(resolver.resolve(0x1010), Some(vec![0xaa; 4]));
+/// assert_eq!(resolver.resolve(0x10), None);
+/// ```
+#[derive(Clone)]
+pub struct PointerResolver(Arc<PointerResolverFn>);
+
+type PointerResolverFn = dyn Fn(u128) -> Option<Vec<u8>> + Send + Sync;
+
+impl PointerResolver {
+    /// Wrap a closure as a pointer resolver.
+    pub fn new(resolver: impl Fn(u128) -> Option<Vec<u8>> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(resolver))
+    }
+
+    /// Invoke the resolver for `address`.
+    pub fn resolve(&self, address: u128) -> Option<Vec<u8>> {
+        (self.0)(address)
+    }
+}
+
+impl Debug for PointerResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PointerResolver(..)")
+    }
+}
+
+/// Generate a "telescope" hex dump, dereferencing one level of pointers, e.g. to triage the stack of a crash dump.
+///
+/// Each group is read as a pointer in its byte order (see `endianness` and `group_endianness`) and passed to
+/// `resolver`.  Each line lists the groups it resolves as `address -> bytes` after the ASCII column, following the
+/// note of the `line_annotator` if one is set; the number of bytes shown is up to the resolver.  Groups cut short by
+/// the offset or the end of the data are not resolved.
+///
+/// All other parameters are the same as for [`hex_out`].
+/// ```rust
+/// use hexout::{hex_out_telescope, HexOutSettings, PointerResolver};
+/// let resolver = PointerResolver::new(|address| (address == 0x40_1000).then(|| vec![0x55, 0x48, 0x89, 0xe5]));
+/// let settings = HexOutSettings { group_size: 4, groups_per_line: 2, show_ascii: false, ..Default::default() };
+/// let data = [0x0040_1000u32.to_le_bytes(), 7u32.to_le_bytes()].concat();
+/// let dump = hex_out_telescope(&data, &resolver, &settings, 0, 0, 0).unwrap();
+/// assert_eq!(dump, "00000000: 00401000  00000007 0x401000 -> 55 48 89 e5");
+/// ```
+pub fn hex_out_telescope(
+    data: &[u8],
+    resolver: &PointerResolver,
+    settings: &HexOutSettings,
+    offset: usize,
+    start_line: usize,
+    line_count: usize,
+) -> Result<String, HexOutError> {
+    validate(settings, offset)?;
+    let mut settings = settings.clone();
+    let group_size = settings.group_size;
+    let total_bytes_per_line = group_size * settings.groups_per_line;
+    let grid_start = if settings.align_address { 0 } else { offset };
+    let guessed = guessed_endianness(data.into(), &settings, offset);
+    let (endianness, group_endianness) = (settings.endianness, settings.group_endianness.clone());
+    let uppercase = settings.uppercase;
+    let resolver = resolver.clone();
+    let line_annotator = settings.line_annotator.take();
+    settings.line_annotator = Some(LineAnnotator::new(move |line_offset, line_bytes| {
+        let mut note = line_annotator.as_ref().and_then(|annotator| annotator.annotate(line_offset, line_bytes));
+        // Groups start on the grid, possibly after a group cut short by the offset
+        let lead = (group_size - (line_offset - grid_start) % group_size) % group_size;
+        let first_group = (line_offset + lead - grid_start) % total_bytes_per_line / group_size;
+        let groups = line_bytes[lead.min(line_bytes.len())..].chunks_exact(group_size);
+        for (index, group) in groups.enumerate() {
+            let endianness = match group_endianness.get(first_group + index).copied().unwrap_or(endianness) {
+                Endianness::Auto => guessed,
+                endianness => endianness,
+            };
+            let address = endianness.read(group);
+            let Some(bytes) = resolver.resolve(address) else {
+                continue;
+            };
+            let note = match &mut note {
+                Some(note) => {
+                    note.push_str(if note.is_empty() { "" } else { ", " });
+                    note
+                }
+                None => note.insert(String::new()),
+            };
+            note.push_str(&format!("{address:#x} ->"));
+            for byte in bytes {
+                match uppercase {
+                    true => note.push_str(&format!(" {byte:02X}")),
+                    false => note.push_str(&format!(" {byte:02x}")),
+                }
+            }
+        }
+        note
+    }));
+    hex_out(data, &settings, offset, start_line, line_count)
+}
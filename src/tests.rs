@@ -131,7 +131,7 @@ fn big_endian_16bit() {
     let settings = HexOutSettings {
         group_size: 2,
         groups_per_line: 8,
-        big_endian: true,
+        endianness: Endianness::Big,
         ..Default::default()
     };
     let result = hex_out(&data, &settings, 0, 0, 1).unwrap();
@@ -144,7 +144,7 @@ fn big_endian_16bit_incomplete() {
     let settings = HexOutSettings {
         group_size: 2,
         groups_per_line: 8,
-        big_endian: true,
+        endianness: Endianness::Big,
         ..Default::default()
     };
     let result = hex_out(&data, &settings, 0, 0, 1).unwrap();
@@ -286,7 +286,7 @@ fn offset_address_aligned_32bit_big_endian() {
         align_address: true,
         group_size: 4,
         groups_per_line: 4,
-        big_endian: true, 
+        endianness: Endianness::Big, 
         uppercase: true,
         ..Default::default()
     };
@@ -444,7 +444,7 @@ fn invalid_address_width() {
     };
     let result = hex_out(&data, &settings, 0, 0, 1);
     let error = result.err().unwrap();
-    assert_eq!(error.to_string(), "Invalid address width (must be 2-16)");
+    assert_eq!(error.to_string(), "Invalid address width (must be 2-32)");
     assert_eq!(format!("{error:?}"), "HexOutError::InvalidAddressWidth");
 }
 
@@ -547,4 +547,3393 @@ fn extremely_large_offset() {
     let result = hex_out(&data, &settings, 0xFFFF_FFFF_0000_0000, 0, 1);
     // Should handle gracefully, not panic
     assert!(result.is_ok());
-}
\ No newline at end of file
+}
+#[test]
+fn caption_with_placeholders() {
+    let data = (0u8..=15u8).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        caption: Some("RX buffer ({len} bytes) @ 0x{address}, offset {offset}".to_string()),
+        address_origin: 0x2000_0100,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 1).unwrap();
+    assert_eq!(result, "RX buffer (16 bytes) @ 0x20000100, offset 00000000\n20000100: 00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f |........ ........|");
+}
+
+#[test]
+fn caption_above_header() {
+    let data = (0u8..=15u8).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        caption: Some("Dump".to_string()),
+        show_header: true,
+        show_ascii: false,
+        address_width: 4,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 1).unwrap();
+    assert_eq!(result, "Dump\nAddr: 00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f\n0000: 00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f");
+}
+
+#[test]
+fn line_annotations() {
+    let data = (0u8..24).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 4,
+        line_annotator: Some(LineAnnotator::new(|offset, bytes| {
+            let sum = bytes.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+            Some(format!("@{offset} sum={sum:02x}"))
+        })),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 03020100 07060504  0b0a0908 0f0e0d0c |........ ........| @0 sum=78\n00000010: 13121110 17161514                    |........         | @16 sum=9c");
+}
+
+#[test]
+fn line_annotations_skip_lines_and_pad_without_ascii() {
+    let data = (0u8..24).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 4,
+        show_ascii: false,
+        line_annotator: Some(LineAnnotator::new(|offset, bytes| (offset >= 16).then(|| format!("{} bytes", bytes.len())))),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 3, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 03?????? 07060504  0b0a0908 0f0e0d0c\n00000010: 13121110 17161514                    8 bytes");
+}
+
+#[test]
+fn flagged_bytes_with_marker_row() {
+    let data = (0x41u8..0x51).collect::<Vec<u8>>();
+    let mut flags = vec![0u8; 16];
+    flags[1] = 1;
+    flags[9] = 1;
+    let settings = HexOutSettings {
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(&data, &flags, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50 |ABCDEFGH IJKLMNOP|\n             ^^                       ^^                     ^        ^");
+}
+
+#[test]
+fn flagged_bytes_styled_within_groups() {
+    let data = (0u8..8).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 2,
+        show_ascii: false,
+        flag_style: Some(Style::new("[", "]")),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(&data, &[0, 1, 1, 0, 0, 0, 0, 1], &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 03[0201]00  [07]060504");
+    // Flags only add styling, the plain dump is unchanged without them
+    assert_eq!(hex_out_flagged(&data, &[], &settings, 0, 0, 0).unwrap(), hex_out(&data, &settings, 0, 0, 0).unwrap());
+}
+
+#[test]
+fn cursor_and_selection() {
+    let data = (0x41u8..0x49).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        groups_per_line: 8,
+        cursor: Some(5),
+        cursor_style: Some(Style::new("<", ">")),
+        selection: Some(1..4),
+        selection_style: Some(Style::new("[", "]")),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 41 [42] [43] [44]  45 <46> 47 48 |A[BCD] E<F>GH|");
+}
+
+#[test]
+fn cursor_inside_group_and_padding() {
+    let data = (0x41u8..0x47).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 2,
+        cursor: Some(4),
+        selection: Some(0..2),
+        cursor_style: Some(Style::reverse()),
+        selection_style: Some(Style::underline()),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 4443\x1b[4m4241\x1b[0m  ????46\x1b[7m45\x1b[0m |\x1b[4mAB\x1b[0mCD \x1b[7mE\x1b[0mF  |");
+}
+
+#[test]
+fn combined_styles_for_addresses_highlights_and_errors() {
+    let data = (0x41u8..0x46).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 4,
+        address_width: 4,
+        address_style: Some(Style::bold()),
+        highlights: vec![0..1, 3..4],
+        highlight_style: Some(Style::new("{", "}")),
+        error_style: Some(Style::ansi("31").and(&Style::blink())),
+        hex_out_error_prefix: Some("ignored".to_string()),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "\x1b[1m0000\x1b[0m: 42{41} {44}43  \x1b[31m\x1b[5m??\x1b[0m\x1b[0m45      |{A}BC{D} E   |");
+}
+
+#[test]
+fn style_combination() {
+    let style = Style::new("<b>", "</b>").and(&Style::new("<i>", "</i>"));
+    assert_eq!(style.apply("x"), "<b><i>x</i></b>");
+    assert_eq!(Style::default().apply("x"), "x");
+}
+
+#[test]
+fn lines_as_vec() {
+    let data = (0u8..40).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        caption: Some("{len} bytes".to_string()),
+        show_header: true,
+        ..Default::default()
+    };
+    let lines = hex_out_vec(&data, &settings, 0, 1, 2).unwrap();
+    assert_eq!(lines, vec![
+        "40 bytes",
+        "Address: 00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f",
+        "00000010: 10 11 12 13 14 15 16 17  18 19 1a 1b 1c 1d 1e 1f |........ ........|",
+        "00000020: 20 21 22 23 24 25 26 27                          | !\"#$%&'         |",
+    ]);
+    assert_eq!(lines.join("\n"), hex_out(&data, &settings, 0, 1, 2).unwrap());
+}
+
+#[test]
+fn lines_as_vec_empty() {
+    let lines = hex_out_vec(&[], &HexOutSettings::default(), 0, 0, 0).unwrap();
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn dump_to_writers_matches_hex_out() {
+    let data = (0u8..40).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        caption: Some("{len} bytes".to_string()),
+        show_header: true,
+        ..Default::default()
+    };
+    let expected = hex_out(&data, &settings, 3, 0, 0).unwrap();
+    let mut text = String::new();
+    hex_dump_to(&data, &settings, &mut text, 3, 0, 0).unwrap();
+    assert_eq!(text, expected);
+    let mut bytes = Vec::new();
+    hex_dump_to_writer(&data, &settings, &mut bytes, 3, 0, 0).unwrap();
+    assert_eq!(String::from_utf8(bytes).unwrap(), expected);
+}
+
+#[test]
+fn dump_to_writers_report_errors() {
+    /// A writer accepting `capacity` bytes.
+    struct Limited {
+        written: String,
+        capacity: usize,
+    }
+    impl std::fmt::Write for Limited {
+        fn write_str(&mut self, text: &str) -> std::fmt::Result {
+            if self.written.len() + text.len() > self.capacity {
+                return Err(std::fmt::Error);
+            }
+            self.written.push_str(text);
+            Ok(())
+        }
+    }
+    let data = [0u8; 40];
+    let mut out = Limited { written: String::new(), capacity: 100 };
+    let error = hex_dump_to(&data, &HexOutSettings::default(), &mut out, 0, 0, 0).unwrap_err();
+    assert!(matches!(error, HexOutError::WriteFailed));
+    assert_eq!(out.written, hex_out(&data, &HexOutSettings::default(), 0, 0, 1).unwrap() + "\n");
+    let invalid = HexOutSettings { group_size: 17, ..Default::default() };
+    let error = hex_dump_to(&data, &invalid, &mut String::new(), 0, 0, 0).unwrap_err();
+    assert!(matches!(error, HexOutError::InvalidGroupSize));
+    let error = hex_dump_to_writer(&data, &invalid, Vec::new(), 0, 0, 0).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    let error = hex_dump_to_writer(&data, &HexOutSettings::default(), &mut [0u8; 10][..], 0, 0, 0).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn lending_line_iterator() {
+    let data = (0u8..40).collect::<Vec<u8>>();
+    let settings = HexOutSettings::default();
+    let mut lines = HexOutLines::new(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(lines.get(), None);
+    let mut collected = Vec::new();
+    while lines.advance() {
+        collected.push(lines.get().unwrap().to_string());
+    }
+    assert_eq!(lines.get(), None);
+    assert!(!lines.advance());
+    assert_eq!(collected, hex_out_vec(&data, &settings, 0, 0, 0).unwrap());
+}
+
+#[test]
+fn lending_line_iterator_validates_settings() {
+    let settings = HexOutSettings {
+        group_size: 0,
+        ..Default::default()
+    };
+    assert!(matches!(HexOutLines::new(&[], &settings, 0, 0, 0), Err(HexOutError::InvalidGroupSize)));
+}
+
+#[test]
+fn layout_fields_arrays_and_conditions() {
+    let layout = Layout::new()
+        .u32_be("magic")
+        .signed("delta", 2, Endianness::Little)
+        .u8("count")
+        .array("items", "count", Layout::new().u8("len").text("name", "len"))
+        .when("count", |count| count == 0, Layout::new().u8("never"));
+    let data = b"\x7fELF\xfe\xff\x02\x02hi\x03abc";
+    let annotations = layout.apply(data);
+    let decoded = annotations
+        .iter()
+        .map(|a| format!("{:?} {}={}", a.range, a.label, a.value.as_deref().unwrap_or_default()))
+        .collect::<Vec<_>>();
+    assert_eq!(decoded, vec![
+        "0..4 magic=2135247942",
+        "4..6 delta=-2",
+        "6..7 count=2",
+        "7..8 items[0].len=2",
+        "8..10 items[0].name=\"hi\"",
+        "10..11 items[1].len=3",
+        "11..14 items[1].name=\"abc\"",
+    ]);
+}
+
+#[test]
+fn layout_truncated_data() {
+    let layout = Layout::new().u16_le("a").u32_le("b").u8("c");
+    let annotations = layout.apply(&[1, 0, 2, 3]);
+    assert_eq!(annotations, vec![
+        Annotation::new(0..2, "a").with_value("1"),
+        Annotation::new(2..4, "b").with_value("<truncated>"),
+    ]);
+}
+
+#[test]
+fn annotated_dump() {
+    let data = (0x41u8..0x59).collect::<Vec<u8>>();
+    let annotations = Layout::new().u32_le("id").bytes("body", 16).u16_be("crc").apply(&data);
+    let settings = HexOutSettings {
+        highlight_style: Some(Style::new("[", "]")),
+        ..Default::default()
+    };
+    let result = hex_out_annotated(&data, &annotations, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: [41] [42] [43] [44] 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50 |[ABCD]EFGH IJKLMNOP| id=1145258561, body=45464748494a4b4c…\n00000010: 51 52 53 54 [55] [56] 57 58                          |QRST[UV]WX         | crc=21846");
+}
+
+#[test]
+fn annotation_index_finds_overlapping_annotations() {
+    let index = AnnotationIndex::new([
+        Annotation::new(10..12, "tail"),
+        Annotation::new(0..16, "packet"),
+        Annotation::new(0..4, "header"),
+        Annotation::new(2..4, "header.len"),
+        Annotation::new(5..5, "empty"),
+        Annotation::new(0..4, "magic"),
+    ]);
+    let labels = |offset| index.annotations_at(offset).map(|annotation| annotation.label.as_str()).collect::<Vec<_>>();
+    assert_eq!(labels(3), ["packet", "header", "magic", "header.len"]);
+    assert_eq!(labels(5), ["packet"]);
+    assert_eq!(labels(11), ["packet", "tail"]);
+    assert!(labels(16).is_empty());
+    assert_eq!(index.annotations()[0].label, "packet");
+}
+
+#[test]
+fn annotation_index_of_layout_fields() {
+    let data = [2, 0xaa, 0xbb, 0x01, 0x02, 0x03, 0x04];
+    let inner = Layout::new().u8("tag").u16_be("value");
+    let annotations = Layout::new().u8("count").array("items", "count", inner).apply(&data);
+    let index = AnnotationIndex::new(annotations);
+    let field = |offset| index.annotations_at(offset).last().map(|annotation| annotation.label.clone());
+    assert_eq!(field(0).as_deref(), Some("count"));
+    assert_eq!(field(2).as_deref(), Some("items[0].value"));
+    assert_eq!(field(6).as_deref(), Some("items[1].value"));
+    assert_eq!(field(7), None);
+    assert!(AnnotationIndex::default().annotations_at(0).next().is_none());
+}
+
+#[test]
+fn memory_regions() {
+    let text = (0x41u8..0x45).collect::<Vec<u8>>();
+    let heap = vec![0u8; 2];
+    let regions = [
+        MemoryRegion::new(0x400000, "r-xp", &text).with_name("/usr/bin/cat"),
+        MemoryRegion::new(0x7f0010, "rw-p", &heap),
+    ];
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        ..Default::default()
+    };
+    let result = hex_out_regions(&regions, &settings).unwrap();
+    assert_eq!(result, "00400000-00400004 r-xp /usr/bin/cat\n00400000: 41 42  43 44 |AB CD|\n007f0010-007f0012 rw-p\n007f0010: 00 00        |..   |");
+}
+
+#[test]
+fn snapshot_differ_renders_changed_lines() {
+    let mut differ = SnapshotDiffer::new(HexOutSettings {
+        groups_per_line: 4,
+        flag_style: Some(Style::new("*", "*")),
+        caption: Some("{len} bytes".to_string()),
+        ..Default::default()
+    });
+    let mut data = vec![0u8; 12];
+    assert_eq!(differ.diff(&data).unwrap().lines().count(), 4);
+    data[1] = 1;
+    data[9] = 2;
+    assert_eq!(differ.diff(&data).unwrap(), "12 bytes\n00000000: 00 *01*  00 00 |.. ..|\n00000008: 00 *02*  00 00 |.. ..|");
+    assert_eq!(differ.diff(&data).unwrap(), "");
+    data.push(0x41);
+    assert_eq!(differ.diff(&data).unwrap(), "13 bytes\n0000000c: *41*           |A    |");
+    differ.reset();
+    assert_eq!(differ.diff(&data).unwrap().lines().count(), 5);
+}
+
+#[test]
+fn diffs_show_no_end_address_or_histogram() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        show_end_address: true,
+        byte_histogram: ByteHistogram::Buckets16,
+        ..Default::default()
+    };
+    let old = [0u8; 48];
+    let mut new = old;
+    new[5] = 1;
+    new[41] = 2;
+    let changes = "00000004: 00 01  00 00 |.. ..|\n00000028: 00 02  00 00 |.. ..|";
+    assert_eq!(hex_out_changes(&old, &new, &settings, 0).unwrap(), changes);
+    let mut differ = SnapshotDiffer::new(settings);
+    differ.diff(&old).unwrap();
+    assert_eq!(differ.diff(&new).unwrap(), changes);
+}
+
+#[test]
+fn html_report_sections_anchors_and_annotations() {
+    let data = b"<tag>&\x00\x01 more data here!".to_vec();
+    let annotations = [Annotation::new(0..5, "tag").with_value("<tag>"), Annotation::new(5..7, "flags")];
+    let settings = HexOutSettings {
+        groups_per_line: 8,
+        caption: Some("{len} bytes".to_string()),
+        ..Default::default()
+    };
+    let mut report = HtmlReport::new("Report & Co");
+    report.add_section("First", &data, &annotations, &settings, true).unwrap();
+    report.add_section("Second", &data[..4], &[], &settings, false).unwrap();
+    let html = report.finish();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>Report &amp; Co</title>"));
+    assert!(html.contains("<details class=\"section\" open>\n<summary>First</summary>\n<pre class=\"dump\">24 bytes\n"));
+    let tag = "<a class=\"ann ann0\" href=\"#ann-0\" title=\"tag = &lt;tag&gt;\" data-label=\"tag\" data-value=\"&lt;tag&gt;\">";
+    let flags = "<a class=\"ann ann1\" href=\"#ann-1\" title=\"flags\" data-label=\"flags\">";
+    assert!(html.contains(&format!(
+        "<span class=\"line\" id=\"off-000000\"><a class=\"addr\" href=\"#off-000000\">00000000</a>: {tag}3c</a> {tag}74</a>"
+    )));
+    assert!(html.contains(&format!(" |{tag}&lt;tag</a> {tag}&gt;</a>{flags}&amp;.</a>.|</span>")));
+    assert!(html.contains("<li id=\"ann-0\"><a href=\"#off-000000\">tag</a> = <span class=\"value\">&lt;tag&gt;</span> <span class=\"range\">[0x0..0x5)</span></li>"));
+    assert!(html.contains("<details class=\"section\">\n<summary>Second</summary>"));
+    assert!(html.contains("id=\"s1-off-000000\""));
+    assert!(html.ends_with("</body>\n</html>\n"));
+}
+
+#[test]
+fn html_single_section() {
+    let data = (0u8..40).collect::<Vec<u8>>();
+    let html = hex_out_html(&data, &[], &HexOutSettings::default()).unwrap();
+    assert!(html.contains("<a class=\"addr\" href=\"#off-000020\">00000020</a>: 20 21 22 23 24 25 26 27"));
+    assert!(!html.contains("<ol class=\"annotations\">"));
+}
+
+#[test]
+fn html_with_rewritten_lines() {
+    let data = (0u8..40).collect::<Vec<u8>>();
+    let filter = |rewrite: fn(&str) -> String| LineFilter::new(move |_, _, line| Some(rewrite(line)));
+    // Shortened, shifted by a multi-byte character, or replaced lines keep no address link
+    for rewrite in [|_: &str| "x".to_string(), |line: &str| format!("é{line}"), |line: &str| line.replace('0', "o")] {
+        let settings = HexOutSettings { line_filter: Some(filter(rewrite)), ..Default::default() };
+        let html = hex_out_html(&data, &[], &settings).unwrap();
+        assert!(!html.contains("class=\"addr\""));
+        let mut report = HtmlReport::new("Rewritten");
+        report.add_section("Lines", &data, &[], &settings, true).unwrap();
+        assert!(!report.finish().contains("class=\"addr\""));
+    }
+    // Lines with the address in place keep it
+    let settings = HexOutSettings { line_filter: Some(filter(|line| format!("{line} <"))), ..Default::default() };
+    let html = hex_out_html(&data, &[], &settings).unwrap();
+    assert!(html.contains("<a class=\"addr\" href=\"#off-000020\">00000020</a>: 20 21 22 23 24 25 26 27"));
+    assert!(html.contains(" <</span>\n"));
+}
+
+#[test]
+fn address_hyperlinks() {
+    let data = (0u8..20).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        address_width: 4,
+        address_origin: 0x100,
+        show_ascii: false,
+        address_link: Some("https://viewer.example/dump#{offset}-{address}".to_string()),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 1, 1).unwrap();
+    assert_eq!(result, "\x1b]8;;https://viewer.example/dump#0010-0110\x1b\\0110\x1b]8;;\x1b\\: 10 11 12 13");
+}
+
+#[test]
+fn color_decision() {
+    use std::ffi::OsString;
+    let set = |value: &str| Some(OsString::from(value));
+    assert!(color::decide(true, None, None, None));
+    assert!(!color::decide(false, None, None, None));
+    assert!(!color::decide(true, set("1"), None, None));
+    assert!(color::decide(true, set(""), None, None));
+    assert!(color::decide(false, set("1"), set("1"), None));
+    assert!(!color::decide(false, None, set("0"), None));
+    assert!(!color::decide(true, None, None, set("0")));
+}
+
+#[test]
+fn settings_without_styles() {
+    let data = (0u8..5).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_size: 2,
+        address_style: Some(Style::bold()),
+        hex_out_error_prefix: Some("\x1b[31m".to_string()),
+        hex_out_error_postfix: Some("\x1b[0m".to_string()),
+        cursor: Some(1),
+        cursor_style: Some(Style::reverse()),
+        address_link: Some("file://{offset}".to_string()),
+        ..Default::default()
+    };
+    let plain = settings.without_styles();
+    assert_eq!(plain.cursor, Some(1));
+    assert_eq!(hex_out(&data, &plain, 0, 0, 0).unwrap(), hex_out(&data, &HexOutSettings { group_size: 2, ..Default::default() }, 0, 0, 0).unwrap());
+}
+
+#[test]
+fn strip_ansi_sequences() {
+    assert_eq!(strip_ansi("\x1b[1;31mred\x1b[0m \x1b]8;;https://x\x1b\\link\x1b]8;;\x1b\\ \x1b]0;title\x07done\x1b"), "red link done");
+    assert_eq!(strip_ansi("no escapes: ü"), "no escapes: ü");
+}
+
+#[test]
+fn plain_output_matches_unstyled_render() {
+    let data = (0x41u8..0x5b).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 4,
+        cursor: Some(2),
+        cursor_style: Some(Style::reverse().and(&Style::new("<c>", "</c>"))),
+        highlights: vec![5..9, 20..22],
+        highlight_style: Some(Style::new("{{", "}}")),
+        hex_out_error_prefix: Some("\x1b[31m".to_string()),
+        hex_out_error_postfix: Some("\x1b[0m".to_string()),
+        address_link: Some("https://example/{offset}".to_string()),
+        ..Default::default()
+    };
+    let styled = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_ne!(styled, hex_out(&data, &settings.without_styles(), 0, 0, 0).unwrap());
+    assert_eq!(plain(&styled, &settings), hex_out(&data, &settings.without_styles(), 0, 0, 0).unwrap());
+}
+
+#[test]
+fn framed_dump_with_header() {
+    let data = b"Hello, World! frame".to_vec();
+    let settings = HexOutSettings {
+        borders: Borders::Unicode,
+        show_header: true,
+        caption: Some("{len} bytes".to_string()),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "19 bytes\n\
+         ┌──────────┬──────────────────────────────────────────────────┬───────────────────┐\n\
+         │ Address  │ 00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f │                   │\n\
+         ├──────────┼──────────────────────────────────────────────────┼───────────────────┤\n\
+         │ 00000000 │ 48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 20 66 72 │ Hello, W orld! fr │\n\
+         │ 00000010 │ 61 6d 65                                         │ ame               │\n\
+         └──────────┴──────────────────────────────────────────────────┴───────────────────┘"
+    );
+}
+
+#[test]
+fn framed_hex_only_with_marker_row() {
+    let data = b"Hello, World! frame".to_vec();
+    let settings = HexOutSettings {
+        borders: Borders::Unicode,
+        show_ascii: false,
+        show_offset: false,
+        group_size: 2,
+        groups_per_line: 4,
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(&data, &[0, 0, 1], &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "┌──────────────────────┐\n\
+         │ 6548 6c6c  2c6f 5720 │\n\
+         │        ^^            │\n\
+         │ 726f 646c  2021 7266 │\n\
+         │ 6d61 ??65            │\n\
+         └──────────────────────┘"
+    );
+}
+
+#[test]
+fn ascii_borders_with_rules() {
+    let data = (0x41u8..0x61).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        borders: Borders::Ascii,
+        rule_interval: 2,
+        groups_per_line: 8,
+        show_header: true,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "+----------+--------------------------+-----------+\n\
+         | Address  | 00 01 02 03  04 05 06 07 |           |\n\
+         +----------+--------------------------+-----------+\n\
+         | 00000000 | 41 42 43 44  45 46 47 48 | ABCD EFGH |\n\
+         | 00000008 | 49 4a 4b 4c  4d 4e 4f 50 | IJKL MNOP |\n\
+         +----------+--------------------------+-----------+\n\
+         | 00000010 | 51 52 53 54  55 56 57 58 | QRST UVWX |\n\
+         | 00000018 | 59 5a 5b 5c  5d 5e 5f 60 | YZ[\\ ]^_` |\n\
+         +----------+--------------------------+-----------+"
+    );
+}
+
+#[test]
+fn rules_without_borders() {
+    let data = (0x41u8..0x55).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        rule_interval: 1,
+        groups_per_line: 8,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "00000000: 41 42 43 44  45 46 47 48 |ABCD EFGH|\n\
+         ----------------------------------------------\n\
+         00000008: 49 4a 4b 4c  4d 4e 4f 50 |IJKL MNOP|\n\
+         ----------------------------------------------\n\
+         00000010: 51 52 53 54              |QRST     |"
+    );
+}
+
+#[test]
+fn pad_every_line_without_ascii() {
+    let data = (0x41u8..0x55).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        pad_lines: true,
+        show_ascii: false,
+        groups_per_line: 8,
+        show_header: true,
+        caption: Some("Dump".to_string()),
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(&data, &[1], &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "Dump                              \n\
+         Address: 00 01 02 03  04 05 06 07 \n\
+         00000000: 41 42 43 44  45 46 47 48\n          \
+         ^^                      \n\
+         00000008: 49 4a 4b 4c  4d 4e 4f 50\n\
+         00000010: 51 52 53 54             "
+    );
+}
+
+#[test]
+fn padded_lines_have_equal_width() {
+    let data = (0u8..37).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        pad_lines: true,
+        caption: Some("{len} bytes at {address}".to_string()),
+        show_header: true,
+        ..Default::default()
+    };
+    let lines = hex_out_vec(&data, &settings, 3, 0, 0).unwrap();
+    assert_eq!(lines.len(), 5);
+    assert!(lines.iter().all(|line| line.chars().count() == 78), "{lines:#?}");
+}
+
+#[test]
+fn unpadded_last_line() {
+    let data = b"Hello, World!".to_vec();
+    let settings = HexOutSettings {
+        pad_last_line: false,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 48 65 6c 6c 6f 2c 20 57  6f 72 6c 64 21 |Hello, W orld!|");
+}
+
+#[test]
+fn unpadded_last_line_ending_at_centerline() {
+    let data = (0x41u8..0x59).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        pad_last_line: false,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50 |ABCDEFGH IJKLMNOP|\n00000010: 51 52 53 54 55 56 57 58 |QRSTUVWX|");
+    let settings = HexOutSettings { show_ascii: false, ..settings };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50\n00000010: 51 52 53 54 55 56 57 58");
+}
+
+#[test]
+fn byte_count_suffix() {
+    let data = (0x41u8..0x5b).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        show_byte_count: true,
+        groups_per_line: 8,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 7, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "00000000:                       48 (1 byte)\n\
+         00000008: 49 4a 4b 4c  4d 4e 4f 50 (8 bytes)\n\
+         00000010: 51 52 53 54  55 56 57 58 (8 bytes)\n\
+         00000018: 59 5a                    (2 bytes)"
+    );
+}
+
+#[test]
+fn byte_count_before_annotation() {
+    let data = (0u8..10).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        show_byte_count: true,
+        groups_per_line: 8,
+        group_size: 4,
+        line_annotator: Some(LineAnnotator::new(|offset, _| Some(format!("@{offset}")))),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "00000000: 03020100 07060504 ????0908                                               |..........                       | (10 bytes) @0"
+    );
+}
+
+#[test]
+fn line_numbers_with_addresses() {
+    let data = (0x41u8..0x61).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        show_line_number: true,
+        groups_per_line: 8,
+        show_header: true,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 1, 2).unwrap();
+    assert_eq!(
+        result,
+        "Line     Address: 00 01 02 03  04 05 06 07\n       \
+         1 00000008: 49 4a 4b 4c  4d 4e 4f 50 |IJKL MNOP|\n       \
+         2 00000010: 51 52 53 54  55 56 57 58 |QRST UVWX|"
+    );
+}
+
+#[test]
+fn line_numbers_instead_of_addresses() {
+    let data = (0x41u8..0x61).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        show_line_number: true,
+        show_offset: false,
+        address_width: 4,
+        groups_per_line: 8,
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(&data, &[1], &settings, 0, 0, 2).unwrap();
+    assert_eq!(
+        result,
+        "   0: 41 42 43 44  45 46 47 48 |ABCD EFGH|\n      \
+         ^^                        ^\n   \
+         1: 49 4a 4b 4c  4d 4e 4f 50 |IJKL MNOP|"
+    );
+}
+
+#[test]
+fn truncation_markers_around_window() {
+    let data = (0x41u8..0x61).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        show_truncation: true,
+        groups_per_line: 8,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 1, 1).unwrap();
+    assert_eq!(result, "… 1 line above …\n00000008: 49 4a 4b 4c  4d 4e 4f 50 |IJKL MNOP|\n… 2 lines below …");
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert!(!result.contains('…'));
+}
+
+#[test]
+fn truncation_markers_inside_borders() {
+    let data = (0x41u8..0x61).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        show_truncation: true,
+        borders: Borders::Unicode,
+        groups_per_line: 8,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 2, 1).unwrap();
+    assert_eq!(
+        result,
+        "┌──────────┬──────────────────────────┬───────────┐\n\
+         │ … 2 lines above …                               │\n\
+         │ 00000010 │ 51 52 53 54  55 56 57 58 │ QRST UVWX │\n\
+         │ … 1 line below …                                │\n\
+         └──────────┴──────────────────────────┴───────────┘"
+    );
+}
+
+#[test]
+fn repeated_header() {
+    let data = (0x41u8..0x5b).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        show_header: true,
+        header_interval: 2,
+        groups_per_line: 8,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "Address: 00 01 02 03  04 05 06 07\n\
+         00000000: 41 42 43 44  45 46 47 48\n\
+         00000008: 49 4a 4b 4c  4d 4e 4f 50\n\
+         Address: 00 01 02 03  04 05 06 07\n\
+         00000010: 51 52 53 54  55 56 57 58\n\
+         00000018: 59 5a"
+    );
+}
+
+#[test]
+fn repeated_header_inside_borders() {
+    let data = (0x41u8..0x51).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        show_header: true,
+        header_interval: 1,
+        borders: Borders::Ascii,
+        groups_per_line: 8,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "+----------+--------------------------+\n\
+         | Address  | 00 01 02 03  04 05 06 07 |\n\
+         +----------+--------------------------+\n\
+         | 00000000 | 41 42 43 44  45 46 47 48 |\n\
+         +----------+--------------------------+\n\
+         | Address  | 00 01 02 03  04 05 06 07 |\n\
+         +----------+--------------------------+\n\
+         | 00000008 | 49 4a 4b 4c  4d 4e 4f 50 |\n\
+         +----------+--------------------------+"
+    );
+}
+
+#[test]
+fn form_feed_between_pages() {
+    let data = (0x41u8..0x5b).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        page_interval: 2,
+        groups_per_line: 8,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "00000000: 41 42 43 44  45 46 47 48\n\
+         00000008: 49 4a 4b 4c  4d 4e 4f 50\n\
+         \x0c\n\
+         00000010: 51 52 53 54  55 56 57 58\n\
+         00000018: 59 5a"
+    );
+}
+
+#[test]
+fn custom_page_separator_before_repeated_header() {
+    let data = (0x41u8..0x51).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        page_interval: 1,
+        page_separator: "---- page ----".to_string(),
+        show_header: true,
+        header_interval: 1,
+        groups_per_line: 8,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "Address: 00 01 02 03  04 05 06 07\n\
+         00000000: 41 42 43 44  45 46 47 48\n\
+         ---- page ----\n\
+         Address: 00 01 02 03  04 05 06 07\n\
+         00000008: 49 4a 4b 4c  4d 4e 4f 50"
+    );
+}
+
+#[test]
+fn ascii_on_the_left_address_on_the_right() {
+    let data = (0x41u8..0x5b).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        columns: vec![Column::Ascii, Column::Hex, Column::Address],
+        groups_per_line: 8,
+        show_header: true,
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(&data, &[0, 1], &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "            00 01 02 03  04 05 06 07\n\
+         |ABCD EFGH| 41 42 43 44  45 46 47 48 00000000\n  \
+         ^            ^^\n\
+         |IJKL MNOP| 49 4a 4b 4c  4d 4e 4f 50 00000008\n\
+         |QRST UVWX| 51 52 53 54  55 56 57 58 00000010\n\
+         |YZ       | 59 5a                    00000018"
+    );
+}
+
+#[test]
+fn checksum_and_byte_count_columns_with_borders() {
+    let data = (0x41u8..0x5b).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        columns: vec![Column::Address, Column::Hex, Column::ByteCount, Column::Checksum],
+        borders: Borders::Unicode,
+        groups_per_line: 8,
+        uppercase: true,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 2, 0).unwrap();
+    assert_eq!(
+        result,
+        "┌──────────┬──────────────────────────┬────┐\n\
+         │ 00000010 │ 51 52 53 54  55 56 57 58 │ A4 │ (8 bytes)\n\
+         │ 00000018 │ 59 5A                    │ B3 │ (2 bytes)\n\
+         └──────────┴──────────────────────────┴────┘"
+    );
+    assert_eq!(
+        HexOutSettings { show_line_number: true, show_ascii: false, ..Default::default() }.effective_columns(),
+        vec![Column::LineNumber, Column::Address, Column::Hex]
+    );
+}
+
+#[test]
+fn inline_chars_per_byte() {
+    let data = b"Hi!\x00\x7fok".to_vec();
+    let settings = HexOutSettings {
+        inline_chars: true,
+        show_ascii: false,
+        groups_per_line: 4,
+        show_header: true,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "Address: 00    01     02    03   \n\
+         00000000: 48(H) 69(i)  21(!) 00(.)\n\
+         00000004: 7f(.) 6f(o)  6b(k)"
+    );
+}
+
+#[test]
+fn inline_chars_per_group_with_padding() {
+    let data = b"ABCDEF".to_vec();
+    let settings = HexOutSettings {
+        inline_chars: true,
+        group_size: 2,
+        groups_per_line: 4,
+        endianness: Endianness::Big,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 1, 0, 0).unwrap();
+    assert_eq!(result, "00000000: ??42( B) 4344(CD)  4546(EF)          | BCD EF  |");
+}
+
+#[test]
+fn initializer_list_groups() {
+    let data = (0u8..10).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_prefix: "0x".to_string(),
+        group_suffix: ",".to_string(),
+        groups_per_line: 8,
+        show_centerline: false,
+        show_offset: false,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,\n0x08, 0x09,");
+}
+
+#[test]
+fn group_prefix_with_header_and_markers() {
+    let data = (0u8..6).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_prefix: "0x".to_string(),
+        group_size: 2,
+        groups_per_line: 4,
+        show_header: true,
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(&data, &[0, 0, 0, 1], &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "Address:     00     02      04     06\n\
+         00000000: 0x0100 0x0302  0x0504        |.... ..  |\n                   \
+         ^^                      ^"
+    );
+}
+
+#[test]
+fn pdp_endian_32bit() {
+    let data = vec![0x0b, 0x0a, 0x0d, 0x0c, 0x02, 0x01, 0x04, 0x03, 0xff];
+    let settings = HexOutSettings {
+        endianness: Endianness::Pdp,
+        group_size: 4,
+        groups_per_line: 4,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 0a0b0c0d 01020304  ??ff????");
+}
+
+#[test]
+fn pdp_byte_offsets() {
+    let order = |endianness: Endianness, group_size| {
+        (0..group_size).map(|position| endianness.byte_offset(position, group_size)).collect::<Vec<_>>()
+    };
+    assert_eq!(order(Endianness::Pdp, 4), vec![1, 0, 3, 2]);
+    assert_eq!(order(Endianness::Pdp, 3), vec![1, 0, 2]);
+    assert_eq!(order(Endianness::Little, 3), vec![2, 1, 0]);
+    assert_eq!(order(Endianness::Big, 3), vec![0, 1, 2]);
+}
+
+#[test]
+fn group_values_follow_byte_offsets() {
+    let data = (0xf0u8..=0xff).collect::<Vec<u8>>();
+    for endianness in [Endianness::Little, Endianness::Big, Endianness::Pdp] {
+        for size in 1..=16 {
+            let bytes = &data[..size];
+            let expected = (0..size)
+                .map(|position| bytes[endianness.byte_offset(position, size)])
+                .fold(0u128, |value, byte| (value << 8) | byte as u128);
+            assert_eq!(endianness.read(bytes), expected, "{endianness:?} {size}");
+        }
+    }
+}
+
+#[test]
+fn pdp_endian_odd_group_size_with_selection() {
+    let data = vec![0x0b, 0x0a, 0x0c, 0x02, 0x01, 0x03];
+    let settings = HexOutSettings {
+        endianness: Endianness::Pdp,
+        group_size: 3,
+        groups_per_line: 2,
+        show_ascii: false,
+        selection: Some(4..5),
+        selection_style: Some(Style::new("[", "]")),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 0a0b0c  [01]0203");
+}
+
+#[test]
+fn per_group_endianness() {
+    let data = vec![0x12, 0x34, 0x56, 0x78, 0x12, 0x34, 0x56, 0x78, 0x12, 0x34, 0x56, 0x78];
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 3,
+        show_centerline: false,
+        show_ascii: false,
+        group_endianness: vec![Endianness::Big, Endianness::Pdp],
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 12345678 34127856 78563412");
+}
+
+#[test]
+fn layout_fields_with_mixed_endianness() {
+    let layout = Layout::new()
+        .unsigned("be", 4, Endianness::Big)
+        .unsigned("pdp", 4, Endianness::Pdp)
+        .signed("le", 2, Endianness::Little);
+    let data = [0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0xfe, 0xff];
+    let values = layout.apply(&data).into_iter().map(|annotation| annotation.value.unwrap()).collect::<Vec<_>>();
+    assert_eq!(values, vec!["256", "65536", "-2"]);
+}
+
+#[test]
+fn dual_endianness_groups() {
+    let data = vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+    let settings = HexOutSettings {
+        dual_endianness: true,
+        group_size: 2,
+        groups_per_line: 4,
+        show_header: true,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "Address:   00        02         04        06     \n\
+         00000000: 0201/0102 0403/0304  0605/0506           |.... ..  |"
+    );
+}
+
+#[test]
+fn dual_endianness_with_flags() {
+    let data = vec![0x01, 0x02, 0x03, 0x04];
+    let settings = HexOutSettings {
+        dual_endianness: true,
+        group_size: 4,
+        groups_per_line: 1,
+        show_ascii: false,
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(&data, &[1], &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 04030201/01020304\n                ^^ ^^");
+}
+
+#[test]
+fn register_legends() {
+    let data = vec![0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x80, 0x00, 0x00];
+    let registers = [
+        Register::new(0, "CTRL").flag("EN", 0).flag("IRQ", 1).field("MODE", 4..6),
+        Register::new(4, "STATUS").flag("BUSY", 0),
+        Register::new(8, "CFG").field("DIV", 0..4).flag("LOCK", 15),
+    ];
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 2,
+        show_ascii: false,
+        show_centerline: false,
+        ..Default::default()
+    };
+    let result = hex_out_registers(&data, &registers, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "00000000: 00000021 00000000 CTRL: EN MODE=2, STATUS: -\n\
+         00000008: 00008003          CFG: DIV=3 LOCK"
+    );
+}
+
+#[test]
+fn register_legends_per_group_endianness() {
+    let data = vec![0x00, 0x01, 0x00, 0x01, 0x00, 0x01];
+    let registers = [
+        Register::new(0, "A").flag("LO", 0).flag("HI", 8),
+        Register::new(2, "B").flag("LO", 0).flag("HI", 8),
+        Register::new(4, "C").flag("LO", 0),
+    ];
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 2,
+        group_endianness: vec![Endianness::Big],
+        show_ascii: false,
+        show_centerline: false,
+        ..Default::default()
+    };
+    let result = hex_out_registers(&data, &registers, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 0001 0100 A: LO, B: HI\n00000004: 0001      C: LO");
+}
+
+#[test]
+fn striped_lines() {
+    let data = (0x41..0x4b).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        stripe_style: Some(Style::new("<", ">")),
+        cursor: Some(5),
+        cursor_style: Some(Style::new("[", "]")),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "00000000: 41 42  43 44 |AB CD|\n\
+         00000004: <45> [46]  <47> <48> |<E>[F] <GH>|\n\
+         00000008: 49 4a        |IJ   |"
+    );
+}
+
+#[test]
+fn striped_groups() {
+    let data = (0x41..0x47).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 3,
+        show_centerline: false,
+        stripe_style: Some(Style::new("<", ">")),
+        stripe_groups: true,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 4241 <4443> 4645 |AB<CD>EF|");
+}
+
+#[test]
+fn line_geometry() {
+    let data = (0x41..0x51).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        groups_per_line: 8,
+        show_line_number: true,
+        address_width: 4,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 1).unwrap();
+    assert_eq!(result, "   0 0000: 41 42 43 44  45 46 47 48 |ABCD EFGH|");
+    assert_eq!(line_width(&settings), result.chars().count());
+    assert_eq!(&result[hex_area_span(&settings).unwrap()], "41 42 43 44  45 46 47 48");
+    assert_eq!(&result[ascii_area_span(&settings).unwrap()], "ABCD EFGH");
+}
+
+#[test]
+fn line_geometry_with_borders() {
+    let data = (0x41..0x45).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        borders: Borders::Ascii,
+        groups_per_line: 4,
+        columns: vec![Column::Ascii, Column::Hex],
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    let line = result.lines().nth(1).unwrap();
+    assert_eq!(line, "| AB CD | 41 42  43 44 |");
+    assert_eq!(line_width(&settings), line.chars().count());
+    assert_eq!(&line[hex_area_span(&settings).unwrap()], "41 42  43 44");
+    assert_eq!(&line[ascii_area_span(&settings).unwrap()], "AB CD");
+    assert_eq!(ascii_area_span(&HexOutSettings { show_ascii: false, ..Default::default() }), None);
+}
+
+#[test]
+fn invalid_groups_per_line() {
+    let settings = HexOutSettings {
+        groups_per_line: 0,
+        ..Default::default()
+    };
+    let error = hex_out(&[0u8; 4], &settings, 0, 0, 0).err().unwrap();
+    assert!(matches!(error, HexOutError::InvalidGroupsPerLine));
+    assert_eq!(error.to_string(), "Invalid number of groups per line (must be at least 1)");
+}
+
+#[test]
+fn invalid_bit_field() {
+    let settings = HexOutSettings {
+        group_size: 2,
+        ..Default::default()
+    };
+    let registers = [Register::new(0, "CTRL").flag("EN", 0).field("MODE", 12..17)];
+    let error = hex_out_registers(&[0u8; 4], &registers, &settings, 0, 0, 0).err().unwrap();
+    assert_eq!(format!("{error:?}"), "HexOutError::InvalidBitField { register: \"CTRL\", field: \"MODE\" }");
+    assert_eq!(error.to_string(), "Bit field MODE of register CTRL is empty or does not fit in a group");
+}
+
+#[test]
+fn error_into_io_error() {
+    fn dump(data: &[u8], settings: &HexOutSettings) -> std::io::Result<String> {
+        Ok(hex_out(data, settings, 0, 0, 0)?)
+    }
+    let settings = HexOutSettings {
+        group_size: 0,
+        ..Default::default()
+    };
+    let error = dump(&[0u8; 4], &settings).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(error.to_string(), "Invalid group size (must be 1-16)");
+}
+
+#[test]
+fn line_window_overflow() {
+    let data = (0u8..32).collect::<Vec<u8>>();
+    let settings = HexOutSettings::default();
+    let result = hex_out(&data, &settings, 0, usize::MAX / 2, 2);
+    assert!(matches!(result, Err(HexOutError::RangeOverflow)));
+    let result = hex_out(&data, &settings, 0, 1, usize::MAX);
+    assert!(matches!(result, Err(HexOutError::RangeOverflow)));
+    let result = hex_out(&data, &settings, 0, usize::MAX / 16, 0);
+    assert_eq!(result.unwrap(), "");
+}
+
+#[test]
+fn address_overflow() {
+    let data = (0u8..32).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        address_origin: u128::MAX - 16,
+        ..Default::default()
+    };
+    let error = hex_out(&data, &settings, 0, 0, 0).err().unwrap();
+    assert_eq!(error.to_string(), "Line window or addresses out of range");
+    let settings = HexOutSettings {
+        address_origin: u128::MAX - 32,
+        address_width: 32,
+        groups_per_line: 8,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 0, 3, 0).unwrap();
+    assert_eq!(result, "fffffffffffffffffffffffffffffff7: 18 19 1a 1b  1c 1d 1e 1f");
+}
+
+#[test]
+fn wide_addresses() {
+    let data = (0x41..0x51).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        address_origin: 0x1234_5678_9abc_def0_0000_0000_0000_0000,
+        address_width: 32,
+        align_address: false,
+        groups_per_line: 8,
+        caption: Some("{address}".to_string()),
+        ..Default::default()
+    };
+    let result = hex_out(&data, &settings, 8, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "123456789abcdef00000000000000008\n\
+         123456789abcdef00000000000000008: 49 4a 4b 4c  4d 4e 4f 50 |IJKL MNOP|"
+    );
+    let settings = HexOutSettings {
+        address_width: 33,
+        ..Default::default()
+    };
+    assert!(matches!(hex_out(&data, &settings, 0, 0, 0), Err(HexOutError::InvalidAddressWidth)));
+}
+
+#[test]
+fn const_settings_match_runtime_settings() {
+    fn check<const GROUP: usize, const GROUPS: usize, const ASCII: bool>(settings: ConstHexOutSettings<GROUP, GROUPS, ASCII>) {
+        let data = (0u8..=0xff).rev().collect::<Vec<u8>>();
+        for len in 0..=3 * GROUP * GROUPS + 1 {
+            let expected = hex_out(&data[..len], &settings.to_settings(), 0, 0, 0).unwrap();
+            assert_eq!(settings.hex_out(&data[..len]), expected, "{GROUP}x{GROUPS}, ascii {ASCII}, {len} bytes");
+        }
+    }
+    check(ConstHexOutSettings::<1, 16>::new());
+    check(ConstHexOutSettings::<4, 3>::new());
+    check(ConstHexOutSettings::<2, 5, false>::new());
+    check(ConstHexOutSettings::<3, 1>::new());
+    check(ConstHexOutSettings::<16, 2, false> {
+        address_origin: 0x1_0000_0000_0000_0000,
+        address_width: 20,
+        uppercase: true,
+    });
+    // Addresses leaving the range of `usize` after the first line
+    check(ConstHexOutSettings::<4, 2> { address_origin: usize::MAX as u128 - 7, ..ConstHexOutSettings::new() });
+}
+
+#[test]
+fn const_settings_dump() {
+    const SETTINGS: ConstHexOutSettings<2, 4> = ConstHexOutSettings::new();
+    let result = SETTINGS.hex_out(b"const dump");
+    assert_eq!(
+        result,
+        "00000000: 6f63 736e  2074 7564 |cons t du|\n\
+         00000008: 706d                 |mp       |"
+    );
+}
+
+#[test]
+fn const_settings_write_to_fixed_buffer() {
+    // A writer backed by a fixed array, as used without an allocator
+    struct ArrayWriter<const N: usize> {
+        buffer: [u8; N],
+        len: usize,
+    }
+    impl<const N: usize> std::fmt::Write for ArrayWriter<N> {
+        fn write_str(&mut self, text: &str) -> std::fmt::Result {
+            let end = self.len + text.len();
+            self.buffer.get_mut(self.len..end).ok_or(std::fmt::Error)?.copy_from_slice(text.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+    let settings: ConstHexOutSettings<2, 2> = ConstHexOutSettings { address_width: 4, ..ConstHexOutSettings::new() };
+    let mut writer = ArrayWriter { buffer: [0; 64], len: 0 };
+    settings.write_to(b"AVR!?", &mut writer).unwrap();
+    assert_eq!(
+        std::str::from_utf8(&writer.buffer[..writer.len]).unwrap(),
+        "0000: 5641  2152 |AV R!|\n0004: ??3f       |?    |"
+    );
+    let mut writer = ArrayWriter { buffer: [0; 16], len: 0 };
+    assert!(settings.write_to(b"AVR!?", &mut writer).is_err());
+}
+
+#[test]
+fn const_settings_render_array() {
+    const DATA: &[u8] = b"Dumped at compile time\x00\x01";
+    const WIDE: ConstHexOutSettings<4, 3> = ConstHexOutSettings::new();
+    const NARROW: ConstHexOutSettings<2, 5, false> = ConstHexOutSettings {
+        address_origin: 0xfff0,
+        address_width: 2,
+        uppercase: true,
+    };
+    const WIDE_DUMP: [u8; WIDE.dump_len(DATA.len())] = WIDE.render_array(DATA);
+    const NARROW_DUMP: [u8; NARROW.dump_len(DATA.len())] = NARROW.render_array(DATA);
+    assert_eq!(std::str::from_utf8(&WIDE_DUMP).unwrap(), WIDE.hex_out(DATA));
+    assert_eq!(std::str::from_utf8(&NARROW_DUMP).unwrap(), NARROW.hex_out(DATA));
+    assert_eq!(
+        std::str::from_utf8(&NARROW_DUMP).unwrap(),
+        "fff0: 7544 706D  6465 6120 2074\n\
+         fffa: 6F63 706D  6C69 2065 6974\n\
+         10004: 656D 0100 "
+    );
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_settings_are_valid() {
+    use arbitrary::{Arbitrary, Unstructured};
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    let data = (0u8..=0xff).cycle().take(300).collect::<Vec<u8>>();
+    for round in 0..500 {
+        let bytes = (0..512)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as u8
+            })
+            .collect::<Vec<u8>>();
+        let settings = HexOutSettings::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        let offset = round % 7;
+        let result = hex_out(&data[..round % data.len()], &settings, offset, round % 3, round % 5);
+        match result {
+            Err(HexOutError::UnalignedOffset { .. }) => assert!(settings.strict),
+            result => assert!(result.is_ok(), "{settings:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod properties {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::strategies::dump_case;
+
+    proptest! {
+        #[test]
+        fn generated_cases_render(case in dump_case()) {
+            prop_assert!(case.render().is_ok());
+        }
+
+        #[test]
+        fn padded_lines_match_line_width(mut case in dump_case()) {
+            case.settings.pad_lines = true;
+            case.settings.show_byte_count = false;
+            let width = line_width(&case.settings);
+            for line in case.render().unwrap().lines() {
+                prop_assert_eq!(line.chars().count(), width, "{:?}", line);
+            }
+        }
+    }
+}
+
+#[test]
+fn header_of_single_group_line() {
+    let settings = HexOutSettings {
+        groups_per_line: 1,
+        group_size: 2,
+        show_ascii: false,
+        show_header: true,
+        borders: Borders::Ascii,
+        ..Default::default()
+    };
+    let result = hex_out(&[1, 2], &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "+----------+------+\n\
+         | Address  |   00 |\n\
+         +----------+------+\n\
+         | 00000000 | 0201 |\n\
+         +----------+------+"
+    );
+}
+
+#[test]
+fn compact_settings_round_trip() {
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 2,
+        borders: Borders::Unicode,
+        show_line_number: true,
+        caption: Some("ignored".to_string()),
+        ..Default::default()
+    };
+    let compact = CompactSettings::from(&settings);
+    assert_eq!(CompactSettings::from(&HexOutSettings::from(compact)), compact);
+    assert_eq!(CompactSettings::from(&HexOutSettings::default()), CompactSettings::default());
+    let data = (0x30u8..0x3b).collect::<Vec<u8>>();
+    let expected = hex_out(&data, &HexOutSettings { caption: None, ..settings }, 0, 0, 0).unwrap();
+    assert_eq!(compact.hex_out(&data, 0, 0, 0).unwrap(), expected);
+}
+
+#[test]
+fn shared_preset_with_style_table() {
+    let styles = StyleTable {
+        address: Some(Style::new("<", ">")),
+        error: Some(Style::new("[", "]")),
+        ..Default::default()
+    };
+    let preset = CompactSettings { group_size: 2, groups_per_line: 2, show_ascii: false, ..CompactSettings::new() }.share(&styles);
+    assert_eq!(StyleTable::from(&*preset), styles);
+    let handle = std::thread::spawn({
+        let preset = Arc::clone(&preset);
+        move || hex_out(&[1, 2, 3], &preset, 0, 0, 0).unwrap()
+    });
+    assert_eq!(handle.join().unwrap(), "<00000000>: 0201  [??]03");
+}
+
+#[test]
+fn xxd_and_hexdump_c_presets() {
+    let xxd = CompactSettings::XXD.hex_out(b"hexample in xxd ", 0, 0, 0).unwrap();
+    assert_eq!(xxd, "00000000: 6865 7861 6d70 6c65 2069 6e20 7878 6420 |hexample in xxd |");
+    let hexdump = CompactSettings::HEXDUMP_C.hex_out(b"hexdump -C layou", 0, 0, 0).unwrap();
+    assert_eq!(hexdump, "00000000: 68 65 78 64 75 6d 70 20  2d 43 20 6c 61 79 6f 75 |hexdump -C layou|\n00000010");
+    assert_eq!(HexOutSettings::from(CompactSettings::HEXDUMP_C).groups_per_line, 16);
+    // Only the strings of `hexdump -C` are missing from the compact preset
+    let mut data = b"hexdump -C layout".to_vec();
+    data.resize(70, 0);
+    let settings = HexOutSettings {
+        address_separator: "  ".to_string(),
+        ascii_open: " |".to_string(),
+        ..CompactSettings::HEXDUMP_C.into()
+    };
+    let hexdump_c = HexOutSettings::hexdump_c();
+    assert_eq!(hex_out(&data, &settings, 3, 0, 0).unwrap(), hex_out(&data, &hexdump_c, 3, 0, 0).unwrap());
+}
+
+#[test]
+fn compact_and_wide_presets() {
+    let compact = CompactSettings::COMPACT.hex_out(b"compact log line", 0, 0, 0).unwrap();
+    assert_eq!(compact, "00000000: 636f6d70 61637420 6c6f6720 6c696e65");
+    let data = (0x40u8..0x60).collect::<Vec<u8>>();
+    let wide = CompactSettings::WIDE32.hex_out(&data, 0, 0, 0).unwrap();
+    assert_eq!(
+        wide,
+        "00000000: 40 41 42 43 44 45 46 47 48 49 4a 4b 4c 4d 4e 4f  50 51 52 53 54 55 56 57 58 59 5a 5b 5c 5d 5e 5f \
+         |@ABCDEFGHIJKLMNO PQRSTUVWXYZ[\\]^_|"
+    );
+}
+
+#[test]
+fn ascii_placeholder_before_offset_and_past_end() {
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 2,
+        align_address: true,
+        ascii_placeholder: '·',
+        ..Default::default()
+    };
+    let result = hex_out(b"ab cd", &settings, 2, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 6320????  ??????64 |·· c d···|");
+}
+
+#[test]
+fn ascii_placeholder_with_inline_chars_in_html() {
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 2,
+        show_ascii: false,
+        inline_chars: true,
+        ascii_placeholder: '<',
+        ..Default::default()
+    };
+    let result = hex_out(b"abc", &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 6261(ab)  ??63(c<)");
+    assert!(hex_out_html(b"abc", &[], &settings).unwrap().contains("(c&lt;)"));
+}
+
+#[test]
+fn escaped_text_mode() {
+    let settings = HexOutSettings {
+        groups_per_line: 8,
+        text_mode: TextMode::Escaped,
+        ..Default::default()
+    };
+    let result = hex_out(b"GET /\r\n\0\tx", &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "00000000: 47 45 54 20  2f 0d 0a 00 |G E T    / \\r\\n\\0|\n\
+         00000008: 09 78                    |\\tx              |"
+    );
+}
+
+#[test]
+fn escaped_text_mode_with_inline_chars_and_markers() {
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 2,
+        show_ascii: false,
+        inline_chars: true,
+        text_mode: TextMode::Escaped,
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(b"a\nb", &[0, 1], &settings, 1, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 0a??(  \\n)  ??62(b   )\n          ^^");
+    assert_eq!(hex_area_span(&settings), Some(10..32));
+}
+
+#[test]
+fn hexified_text_mode() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        text_mode: TextMode::Hexified { uppercase: false },
+        ..Default::default()
+    };
+    let result = hex_out(b"ok\r\n\xff", &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "00000000: 6f 6b  0d 0a |o   k    ⟨0d⟩⟨0a⟩|\n\
+         00000004: ff           |⟨ff⟩             |"
+    );
+    assert_eq!(ascii_area_span(&settings), Some(24..41));
+}
+
+#[test]
+fn hexified_text_mode_uppercase_with_inline_chars() {
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 2,
+        show_ascii: false,
+        inline_chars: true,
+        text_mode: TextMode::Hexified { uppercase: true },
+        ..Default::default()
+    };
+    let result = hex_out(b"\x00\xabz", &settings, 1, 0, 0).unwrap();
+    assert_eq!(result, "00000000: ab??(    ⟨AB⟩)  ??7a(z       )");
+}
+
+#[test]
+fn xxd_style_ascii_lead_in() {
+    let settings = HexOutSettings {
+        ascii_open: " ".to_string(),
+        ascii_close: String::new(),
+        ..HexOutSettings::from(CompactSettings::XXD)
+    };
+    let result = hex_out(b"hello\n", &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 6865 6c6c 6f0a                           hello.");
+    assert_eq!(ascii_area_span(&settings), Some(51..67));
+    assert_eq!(line_width(&settings), 67);
+}
+
+#[test]
+fn styled_ascii_bars() {
+    let bar = Style::dim().apply("|");
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        ascii_open: bar.clone(),
+        ascii_close: bar.clone(),
+        pad_lines: true,
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(b"abc", &[0, 0, 1], &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, format!("00000000: 61 62  63    {bar}ab c {bar}\n                 ^^        ^  "));
+    assert_eq!(line_width(&settings), 30);
+    assert_eq!(plain(&result, &settings), hex_out_flagged(b"abc", &[0, 0, 1], &settings.without_styles(), 0, 0, 0).unwrap());
+}
+
+#[test]
+fn bit_dump_lines_and_window() {
+    let settings = HexOutSettings {
+        groups_per_line: 2,
+        address_width: 4,
+        address_origin: 0x100,
+        ..Default::default()
+    };
+    let data = [0xf0, 0x0f, 0xa5];
+    let result = bit_dump(&data, &settings, 2, 6, 0, 0).unwrap();
+    assert_eq!(result, "0100.2: 110000  000011\n0101.6: 111010  0101??");
+    assert_eq!(bit_dump(&data, &settings, 2, 6, 1, 1).unwrap(), "0101.6: 111010  0101??");
+    assert_eq!(bit_dump(&data, &settings, 24, 6, 0, 0).unwrap(), "");
+}
+
+#[test]
+fn bit_dump_rejects_invalid_group_sizes() {
+    let settings = HexOutSettings::default();
+    assert!(matches!(bit_dump(&[1], &settings, 0, 0, 0, 0), Err(HexOutError::InvalidBitGroupSize)));
+    assert!(matches!(bit_dump(&[1], &settings, 0, 129, 0, 0), Err(HexOutError::InvalidBitGroupSize)));
+    assert_eq!(bit_dump(&[0x81], &HexOutSettings { show_offset: false, ..settings }, 0, 128, 0, 0).unwrap().len(), 128);
+}
+
+fn opcode_decoder() -> LineDecoder {
+    LineDecoder::new(|bytes, address| match bytes[0] {
+        0x90 => Some((1, "nop".to_string())),
+        0xe8 => Some((5, format!("call {:x}", address + 5))),
+        _ => None,
+    })
+}
+
+#[test]
+fn line_decoder_records() {
+    let settings = HexOutSettings {
+        groups_per_line: 8,
+        show_centerline: false,
+        address_width: 4,
+        address_origin: 0x1000,
+        line_decoder: Some(opcode_decoder()),
+        ..Default::default()
+    };
+    let data = [0x90, 0xe8, 0x10, 0x00, 0x00, 0x00, 0x90, 0xcc, 0xcc];
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "1000: 90                      |.       | nop\n\
+         1001: e8 10 00 00 00          |.....   | call 1006\n\
+         1006: 90                      |.       | nop\n\
+         1007: cc cc                   |..      |"
+    );
+    // Line numbers and windows count records
+    let settings = HexOutSettings { show_ascii: false, show_line_number: true, show_truncation: true, ..settings };
+    assert_eq!(
+        hex_out(&data, &settings, 1, 1, 1).unwrap(),
+        "… 1 line above …\n   1 1006: 90                      nop\n… 1 line below …"
+    );
+}
+
+#[test]
+fn line_decoder_partial_groups_and_lengths() {
+    // Records are at least one byte and at most a line long
+    let decoder = LineDecoder::new(|bytes, _| Some((bytes[0] as usize, format!("len {}", bytes[0]))));
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 2,
+        endianness: Endianness::Big,
+        show_offset: false,
+        line_decoder: Some(decoder),
+        ..Default::default()
+    };
+    let data = [0x03, 0xaa, 0xbb, 0x00, 0x09, 0x01, 0x02, 0x03, 0x01];
+    assert_eq!(
+        hex_out(&data, &settings, 0, 0, 0).unwrap(),
+        "03aa  bb?? |.. . | len 3\n00??       |.    | len 0\n0901  0203 |.. ..| len 9\n01??       |.    | len 1"
+    );
+}
+
+/// Parses entries with a one-byte tag and a one-byte length, tags with bit 5 set being constructed.
+fn parse_ber(bytes: &[u8]) -> Option<Tlv> {
+    let tag = match bytes[0] {
+        0x30 => "SEQUENCE".to_string(),
+        0x04 => "OCTET STRING".to_string(),
+        tag => format!("{tag:02x}"),
+    };
+    let tlv = Tlv::new(tag, 2, *bytes.get(1)? as usize);
+    Some(if bytes[0] & 0x20 != 0 { tlv.nested() } else { tlv })
+}
+
+#[test]
+fn tlv_nested_entries() {
+    let settings = HexOutSettings { groups_per_line: 4, address_width: 4, show_centerline: false, ..Default::default() };
+    let data = [0x30, 0x09, 0x04, 0x05, b'h', b'e', b'l', b'l', b'o', 0x30, 0x00, 0x02, 0x01, 0x2a];
+    assert_eq!(
+        hex_out_tlv(&data, parse_ber, &settings, 0).unwrap(),
+        "SEQUENCE (length 9)\n\
+         \x20 OCTET STRING (length 5)\n\
+         \x20   0004: 68 65 6c 6c |hell|\n\
+         \x20   0008: 6f          |o   |\n\
+         \x20 SEQUENCE (length 0)\n\
+         02 (length 1)\n\
+         \x20 000d: 2a          |*   |"
+    );
+}
+
+#[test]
+fn tlv_truncated_and_unparsed_entries() {
+    let settings = HexOutSettings { show_ascii: false, address_width: 4, address_origin: 0x100, ..Default::default() };
+    // The inner value claims more bytes than its sequence holds, and the last byte has no length
+    let data = [0x30, 0x03, 0x04, 0x08, 0xaa, 0x04];
+    assert_eq!(
+        hex_out_tlv(&data, parse_ber, &settings, 0).unwrap(),
+        "SEQUENCE (length 3)\n  OCTET STRING (length 8)\n    0104: aa\nunparsed (1 byte)\n  0105: 04"
+    );
+    assert_eq!(hex_out_tlv(&data, parse_ber, &settings, 6).unwrap(), "");
+}
+
+#[test]
+fn base64_column() {
+    let settings = HexOutSettings { groups_per_line: 8, show_base64: true, ..Default::default() };
+    let data = b"Hello, base64 world! \x00\xff\xfe";
+    assert_eq!(
+        hex_out(data, &settings, 0, 0, 0).unwrap(),
+        "00000000: 48 65 6c 6c  6f 2c 20 62 |Hell o, b| SGVsbG8sIGI=\n\
+         00000008: 61 73 65 36  34 20 77 6f |ase6 4 wo| YXNlNjQgd28=\n\
+         00000010: 72 6c 64 21  20 00 ff fe |rld!  ...| cmxkISAA//4="
+    );
+    assert_eq!(
+        hex_out(&data[..10], &settings, 0, 1, 0).unwrap(),
+        "00000008: 61 73                    |as       | YXM="
+    );
+}
+
+#[test]
+fn base64_column_padded_before_offset() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        show_centerline: false,
+        show_base64: true,
+        show_byte_count: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        hex_out(b"abcdefg", &settings, 2, 0, 0).unwrap(),
+        "00000000:       63 64 |  cd| Y2Q=     (2 bytes)\n00000004: 65 66 67    |efg | ZWZn     (3 bytes)"
+    );
+    assert_eq!(line_width(&settings), 37);
+}
+
+#[test]
+fn xxd_bits_preset_matches_xxd() {
+    let settings =
+        HexOutSettings { ascii_open: " ".to_string(), ascii_close: String::new(), ..CompactSettings::XXD_BITS.into() };
+    assert_eq!(
+        hex_out(b"Hello, world!\x00\x01", &settings, 0, 0, 0).unwrap(),
+        "00000000: 01001000 01100101 01101100 01101100 01101111 00101100  Hello,\n\
+         00000006: 00100000 01110111 01101111 01110010 01101100 01100100   world\n\
+         0000000c: 00100001 00000000 00000001                             !.."
+    );
+    assert_eq!(
+        CompactSettings::XXD_BITS.hex_out(b"xxd -b", 0, 0, 0).unwrap(),
+        "00000000: 01111000 01111000 01100100 00100000 00101101 01100010 |xxd -b|"
+    );
+}
+
+#[test]
+fn binary_groups_with_header_and_markers() {
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 2,
+        binary: true,
+        show_header: true,
+        show_ascii: false,
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    assert_eq!(
+        hex_out_flagged(&[0x01, 0x80, 0xff], &[0, 1, 0], &settings, 0, 0, 0).unwrap(),
+        "Address:               00                02\n\
+         00000000: 1000000000000001  ????????11111111\n\
+         \x20         ^^^^^^^^"
+    );
+}
+
+#[test]
+fn decimal_and_octal_groups_stay_aligned() {
+    let data = (0..22u8).map(|byte| byte.wrapping_mul(37)).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 4,
+        group_radix: GroupRadix::Decimal,
+        show_header: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        hex_out(&data, &settings, 1, 0, 0).unwrap(),
+        "Address:    00    02     04    06\n\
+         00000000: ????? 28490  47508   990 | %Jo ....|\n\
+         00000008: 19752 38770  57788 11014 |(Mr. ...+|\n\
+         00000010: 30032 49050   2532       |Pu.. ..  |"
+    );
+    let settings = HexOutSettings { group_radix: GroupRadix::Octal, ..settings };
+    assert_eq!(
+        hex_out(&data, &settings, 1, 0, 0).unwrap(),
+        "Address:     00     02      04     06\n\
+         00000000: ?????? 067512  134624 001736 | %Jo ....|\n\
+         00000008: 046450 113562  160674 025406 |(Mr. ...+|\n\
+         00000010: 072520 137632  004744        |Pu.. ..  |"
+    );
+    // The widest values of 16-byte groups, in both byte orders
+    let settings = HexOutSettings {
+        group_size: 16,
+        groups_per_line: 1,
+        group_radix: GroupRadix::Decimal,
+        dual_endianness: true,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let max = u128::MAX.to_string();
+    assert_eq!(hex_out(&[0xff; 16], &settings, 0, 0, 0).unwrap(), format!("00000000: {max}/{max}"));
+}
+
+#[test]
+fn decimal_groups_are_marked_and_styled_as_a_whole() {
+    let data = (0..8u8).map(|byte| byte.wrapping_mul(37)).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        groups_per_line: 8,
+        group_radix: GroupRadix::Decimal,
+        flag_marker: Some('^'),
+        flag_style: Some(Style::new("[", "]")),
+        ..Default::default()
+    };
+    assert_eq!(
+        hex_out_flagged(&data, &[0, 1, 0, 0, 1], &settings, 0, 0, 0).unwrap(),
+        "00000000:   0 [ 37]  74 111  [148] 185 222   3 |.%Jo ....|\n\
+         \x20             ^^^          ^^^               ^   ^"
+    );
+    let settings = HexOutSettings { group_size: 4, groups_per_line: 2, ..settings };
+    assert_eq!(
+        hex_out_flagged(&data, &[0, 1, 0, 0, 1], &settings, 0, 0, 0).unwrap(),
+        "00000000: [1867130112]  [  64928148] |.%Jo ....|\n\
+         \x20         ^^^^^^^^^^  ^^^^^^^^^^   ^   ^"
+    );
+    // Binary digits take precedence
+    let settings = HexOutSettings { binary: true, groups_per_line: 1, ..settings };
+    assert_eq!(hex_out(b"\x05", &settings, 0, 0, 0).unwrap(), "00000000: ????????????????????????00000101 |.   |");
+}
+
+#[test]
+fn parse_hex_str_separators_and_prefixes() {
+    let expected = [0xde, 0xad, 0xbe, 0xef, 0xff, 0x00];
+    assert_eq!(parse_hex_str("deadBEEF  ff 00").unwrap(), expected);
+    assert_eq!(parse_hex_str("0xDE 0xad\t0xbe_ef\nff_00").unwrap(), expected);
+    assert_eq!(parse_hex_str("d e a d b e e f f f 0 0").unwrap(), expected);
+    assert_eq!(parse_hex_str(" 0x ").unwrap(), Vec::<u8>::new());
+    // Dumps parse back to their data
+    let data = (0u8..=255).collect::<Vec<u8>>();
+    let settings = HexOutSettings { show_offset: false, show_ascii: false, ..Default::default() };
+    assert_eq!(parse_hex_str(&hex_out(&data, &settings, 0, 0, 0).unwrap()).unwrap(), data);
+}
+
+#[test]
+fn parse_hex_str_rejects_invalid_input() {
+    assert!(matches!(parse_hex_str("12 3g"), Err(HexOutError::InvalidHexDigit { position: 4, character: 'g' })));
+    assert!(matches!(parse_hex_str("ff_0x00"), Err(HexOutError::InvalidHexDigit { position: 4, character: 'x' })));
+    assert!(matches!(parse_hex_str("abc"), Err(HexOutError::OddHexDigitCount)));
+    assert_eq!(parse_hex_str("é").unwrap_err().to_string(), "Invalid hex digit 'é' at position 0");
+}
+
+#[test]
+fn patch_diff_text_and_json() {
+    let old = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+    let new = [0x00, 0xaa, 0xbb, 0x33, 0x44, 0xcc, 0xdd];
+    let patch = Patch::diff(&old, &new);
+    assert_eq!(patch.to_text(), "00000001: 11 22 -> aa bb\n00000005: 55 -> cc\n00000006: - -> dd");
+    assert_eq!(
+        patch.to_json(),
+        "[\n  {\"offset\": 1, \"old\": \"1122\", \"new\": \"aabb\"},\n  {\"offset\": 5, \"old\": \"55\", \"new\": \"cc\"},\n  \
+         {\"offset\": 6, \"old\": \"\", \"new\": \"dd\"}\n]"
+    );
+    assert_eq!(Patch::from_text(&patch.to_text()).unwrap(), patch);
+    let mut data = old.to_vec();
+    apply_patch(&mut data, &patch).unwrap();
+    assert_eq!(data, new);
+    // Patching the other way cuts the data back
+    apply_patch(&mut data, &Patch::diff(&new, &old)).unwrap();
+    assert_eq!(data, old);
+    assert!(Patch::diff(&old, &old).is_empty());
+    assert_eq!(Patch::default().to_json(), "[]");
+}
+
+#[test]
+fn patch_rejects_mismatches_and_invalid_lines() {
+    let patch = Patch::from_text("\n10: 0x00ff -> 1234\n12: 00 -> -\n").unwrap();
+    assert_eq!(patch.entries[1], PatchEntry { offset: 0x12, old: vec![0], new: Vec::new() });
+    let mut data = vec![0u8; 0x12];
+    assert!(matches!(apply_patch(&mut data, &patch), Err(HexOutError::PatchMismatch { offset: 0x10 })));
+    assert_eq!(data, [0; 0x12]);
+    data[0x11] = 0xff;
+    data.push(0);
+    apply_patch(&mut data, &patch).unwrap();
+    assert_eq!(data[0x10..], [0x12, 0x34]);
+    assert!(matches!(Patch::from_text("10: 00 11"), Err(HexOutError::InvalidPatchLine { line: 1 })));
+    assert!(matches!(Patch::from_text("10: 00 -> 11\nzz: 00 -> 11"), Err(HexOutError::InvalidPatchLine { line: 2 })));
+    assert!(matches!(Patch::from_text("10: 00 01 -> -\n11: 01 -> -"), Err(HexOutError::InvalidPatchLine { line: 2 })));
+}
+
+#[test]
+fn changed_lines_with_context() {
+    let settings = HexOutSettings {
+        groups_per_line: 2,
+        show_ascii: false,
+        address_width: 4,
+        show_truncation: true,
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let old = (0u8..20).collect::<Vec<u8>>();
+    let mut new = old.clone();
+    new[2] = 0xff;
+    new[7] = 0xff;
+    new.push(0x14);
+    // Overlapping context merges the hunks around lines 1 and 3
+    assert_eq!(
+        hex_out_changes(&old, &new, &settings, 1).unwrap(),
+        "0000: 00  01\n0002: ff  03\n      ^^\n0004: 04  05\n0006: 06  ff\n          ^^\n0008: 08  09\n\
+         … 4 lines unchanged …\n0012: 12  13\n0014: 14 \n      ^^"
+    );
+    assert_eq!(
+        hex_out_changes(&old, &new, &HexOutSettings { flag_marker: None, ..settings.clone() }, 0).unwrap(),
+        "… 1 line unchanged …\n0002: ff  03\n… 1 line unchanged …\n0006: 06  ff\n… 6 lines unchanged …\n0014: 14 "
+    );
+    assert_eq!(hex_out_changes(&old, &old, &settings, 3).unwrap(), "");
+}
+
+#[test]
+fn snapshot_differ_with_context() {
+    let settings = HexOutSettings { groups_per_line: 4, show_ascii: false, ..Default::default() };
+    let mut differ = SnapshotDiffer::new(settings).with_context(1);
+    let mut data = vec![0u8; 24];
+    differ.diff(&data).unwrap();
+    data[13] = 1;
+    assert_eq!(differ.diff(&data).unwrap(), "00000008: 00 00  00 00\n0000000c: 00 01  00 00\n00000010: 00 00  00 00");
+}
+
+#[test]
+fn render_options_override_settings() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        cursor: Some(0),
+        cursor_style: Some(Style::new("[", "]")),
+        selection_style: Some(Style::new("<", ">")),
+        highlights: vec![1..2, 9..10],
+        highlight_style: Some(Style::new("{", "}")),
+        caption: Some("settings".to_string()),
+        ..Default::default()
+    };
+    let highlights = [6..7, 10..12];
+    let options = RenderOptions {
+        cursor: Some(2),
+        selection: Some(4..6),
+        highlights: &highlights,
+        caption: Some("{len} bytes from {offset}"),
+        ..Default::default()
+    };
+    assert_eq!(
+        hex_out_with_options(b"abcdefgh", &settings, &options).unwrap(),
+        "8 bytes from 00000000\n\
+         00000000: 61 {62}  [63] 64 |a{b} [c]d|\n\
+         00000004: <65> <66>  {67} 68 |<ef> {g}h|"
+    );
+    // Without options the settings apply as for hex_out
+    assert_eq!(
+        hex_out_with_options(b"abcdefgh", &settings, &RenderOptions::default()).unwrap(),
+        hex_out(b"abcdefgh", &settings, 0, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn render_options_max_line_width() {
+    let data = (0x41u8..0x4b).collect::<Vec<u8>>();
+    let settings = HexOutSettings { groups_per_line: 8, ..Default::default() };
+    let options = RenderOptions { max_line_width: Some(30), ..Default::default() };
+    let dump = hex_out_with_options(&data, &settings, &options).unwrap();
+    assert_eq!(dump, "00000000: 41 42  43 44 |AB CD|\n00000004: 45 46  47 48 |EF GH|\n00000008: 49 4a        |IJ   |");
+    assert!(dump.lines().all(|line| line.chars().count() <= 30));
+    // Wide enough lines keep their groups
+    let options = RenderOptions { max_line_width: Some(line_width(&settings)), ..Default::default() };
+    assert_eq!(hex_out_with_options(&data, &settings, &options).unwrap(), hex_out(&data, &settings, 0, 0, 0).unwrap());
+}
+
+#[test]
+fn groups_per_line_for_narrow_widths() {
+    let settings = HexOutSettings { group_size: 4, groups_per_line: 8, borders: Borders::Unicode, ..Default::default() };
+    let fitted = groups_per_line_for_width(&settings, 60);
+    assert!(line_width(&HexOutSettings { groups_per_line: fitted, ..settings.clone() }) <= 60);
+    assert!(line_width(&HexOutSettings { groups_per_line: fitted + 1, ..settings.clone() }) > 60);
+    assert_eq!(fitted, 3);
+    assert_eq!(groups_per_line_for_width(&settings, 10), 1);
+    assert_eq!(groups_per_line_for_width(&settings, usize::MAX), 8);
+}
+
+#[test]
+fn render_options_line_window() {
+    let settings = HexOutSettings { groups_per_line: 2, show_ascii: false, show_truncation: true, ..Default::default() };
+    let data = (0u8..10).collect::<Vec<u8>>();
+    let options = RenderOptions { offset: 1, start_line: 1, line_count: 2, ..Default::default() };
+    assert_eq!(hex_out_with_options(&data, &settings, &options).unwrap(), hex_out(&data, &settings, 1, 1, 2).unwrap());
+    let options = RenderOptions { offset: 0, ..options };
+    assert_eq!(
+        hex_out_with_options(&data, &settings, &options).unwrap(),
+        "… 1 line above …\n00000002: 02  03\n00000004: 04  05\n… 2 lines below …"
+    );
+}
+
+#[test]
+fn line_filter_drops_lines() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        show_ascii: false,
+        rule_interval: 2,
+        line_filter: Some(LineFilter::new(|_, bytes, line| {
+            bytes.iter().any(|byte| *byte != 0).then(|| line.to_string())
+        })),
+        ..Default::default()
+    };
+    let mut data = vec![0u8; 24];
+    data[5] = 1;
+    data[13] = 2;
+    data[22] = 3;
+    assert_eq!(
+        hex_out(&data, &settings, 0, 0, 0).unwrap(),
+        "00000004: 00 01  00 00\n0000000c: 00 02  00 00\n----------------------\n00000014: 00 00  03 00"
+    );
+    // Dropped lines are not left out by the window
+    let settings = HexOutSettings { rule_interval: 0, show_truncation: true, ..settings };
+    assert_eq!(
+        hex_out(&data, &settings, 0, 1, 3).unwrap(),
+        "… 1 line above …\n00000004: 00 01  00 00\n0000000c: 00 02  00 00\n… 2 lines below …"
+    );
+}
+
+#[test]
+fn line_filter_rewrites_lines() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        line_filter: Some(LineFilter::new(|offset, bytes, line| Some(format!("{line} <- {offset}+{}", bytes.len())))),
+        ..Default::default()
+    };
+    assert_eq!(
+        hex_out(b"abcdefghij", &settings, 2, 0, 0).unwrap(),
+        "00000000:        63 64 |   cd| <- 2+2\n\
+         00000004: 65 66  67 68 |ef gh| <- 4+4\n\
+         00000008: 69 6a        |ij   | <- 8+2"
+    );
+}
+
+#[test]
+fn skip_fill_notes_gaps() {
+    let settings = HexOutSettings { groups_per_line: 4, show_ascii: false, skip_fill: Some(0xff), ..Default::default() };
+    let mut data = vec![0xffu8; 32];
+    data[1] = 0;
+    data[20] = 0x10;
+    assert_eq!(
+        hex_out(&data, &settings, 0, 0, 0).unwrap(),
+        "00000000: ff 00  ff ff\n… 4 lines of ff …\n00000014: 10 ff  ff ff\n… 2 lines of ff …"
+    );
+    // Only the valid bytes of a line are compared with the fill
+    let settings = HexOutSettings { uppercase: true, ..settings };
+    assert_eq!(hex_out(&data[..22], &settings, 2, 0, 0).unwrap(), "… 5 lines of FF …\n00000014: 10 FF ");
+}
+
+#[test]
+fn skip_fill_with_window_and_rules() {
+    let settings = HexOutSettings {
+        groups_per_line: 2,
+        show_ascii: false,
+        show_line_number: true,
+        show_truncation: true,
+        rule_interval: 1,
+        skip_fill: Some(0),
+        ..Default::default()
+    };
+    let data = [1, 0, 0, 0, 0, 0, 2, 0, 0, 0, 3, 0];
+    assert_eq!(
+        hex_out(&data, &settings, 0, 1, 4).unwrap(),
+        "… 1 line above …\n\
+         … 2 lines of 00 …\n       3 00000006: 02  00\n-------------------------\n… 1 line of 00 …\n\
+         … 1 line below …"
+    );
+}
+
+#[test]
+fn cells_classify_characters() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        address_width: 2,
+        show_header: true,
+        cursor: Some(3),
+        selection: Some(4..6),
+        ..Default::default()
+    };
+    let rows = hex_out_cells(b"abcdef", &settings, 1, 0, 0).unwrap();
+    let text = |row: &[HexCell]| row.iter().map(|cell| cell.ch).collect::<String>();
+    assert_eq!(rows.iter().map(|row| text(row)).collect::<Vec<_>>(), hex_out_vec(b"abcdef", &settings, 1, 0, 0).unwrap());
+    let runs = |row: &[HexCell], class| text(&row.iter().copied().filter(|cell| cell.class == class).collect::<Vec<_>>());
+    assert_eq!(runs(&rows[1], HexCellClass::Address), "00");
+    assert_eq!(runs(&rows[1], HexCellClass::Cursor), "64d");
+    assert_eq!(runs(&rows[2], HexCellClass::Selected), "6566ef");
+    assert!(rows[0].iter().all(|cell| cell.class == HexCellClass::Plain && cell.column.is_none()));
+    let columns = rows[1].iter().map(|cell| cell.column).collect::<Vec<_>>();
+    assert_eq!(columns[..3], [Some(Column::Address), Some(Column::Address), None]);
+    assert_eq!(columns[4], Some(Column::Hex));
+    assert_eq!(columns[columns.len() - 2], Some(Column::Ascii));
+}
+
+#[test]
+fn cells_ignore_styles() {
+    let plain = HexOutSettings { group_size: 2, groups_per_line: 2, highlights: vec![0..2, 4..5], ..Default::default() };
+    let styled = HexOutSettings {
+        highlight_style: Some(Style::reverse()),
+        address_style: Some(Style::bold()),
+        error_style: Some(Style::new("<", ">")),
+        ascii_open: "\x1b[2m|\x1b[0m".to_string(),
+        ..plain.clone()
+    };
+    assert_eq!(hex_out_cells(b"abcde", &plain, 0, 0, 0).unwrap(), hex_out_cells(b"abcde", &styled, 0, 0, 0).unwrap());
+    let rows = hex_out_cells(b"abcde", &styled, 0, 0, 0).unwrap();
+    let class = |class| rows.iter().flatten().filter(|cell| cell.class == class).map(|cell| cell.ch).collect::<String>();
+    assert_eq!(class(HexCellClass::Highlighted), "6261ab65e");
+    assert_eq!(class(HexCellClass::Missing), "??");
+}
+
+#[test]
+fn builtin_themes() {
+    let themes = Themes::builtin();
+    assert_eq!(themes.names().collect::<Vec<_>>(), ["dark", "light", "plain"]);
+    let settings = HexOutSettings { groups_per_line: 2, cursor: Some(0), ..Default::default() };
+    assert_eq!(
+        hex_out(b"ab", &settings.with_theme(themes.get("dark").unwrap()), 0, 0, 0).unwrap(),
+        "\x1b[36m00000000\x1b[0m: \x1b[7m61\x1b[0m  62 |\x1b[7ma\x1b[0m b|"
+    );
+    let styled = HexOutSettings { address_style: Some(Style::bold()), ..settings.clone() };
+    assert_eq!(
+        hex_out(b"ab", &styled.with_theme(themes.get("plain").unwrap()), 0, 0, 0).unwrap(),
+        hex_out(b"ab", &settings, 0, 0, 0).unwrap()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn user_theme_file() {
+    let file = r#"{"dark": {"cursor": {"prefix": "[", "suffix": "]"}}, "mine": {"address": {"prefix": "<", "suffix": ">"}}}"#;
+    let mut themes = Themes::builtin();
+    themes.extend(serde_json::from_str(file).unwrap());
+    assert_eq!(themes.names().collect::<Vec<_>>(), ["dark", "light", "mine", "plain"]);
+    // Classes left out of a theme are unstyled
+    assert_eq!(themes.get("dark"), Some(&StyleTable { cursor: Some(Style::new("[", "]")), ..Default::default() }));
+    assert_eq!(themes.get("mine").unwrap().address, Some(Style::new("<", ">")));
+    let saved = serde_json::to_string(&themes).unwrap();
+    assert_eq!(serde_json::from_str::<Themes>(&saved).unwrap(), themes);
+}
+
+#[test]
+fn bookmarks_escape_labels() {
+    let annotations = [Annotation::new(2..3, "say \"hi\"\t\\"), Annotation::new(8..8, "line\nbreak, \x01")];
+    assert_eq!(
+        bookmarks_json(&annotations),
+        "[\n  {\"offset\": 2, \"length\": 1, \"label\": \"say \\\"hi\\\"\\t\\\\\"},\n  \
+         {\"offset\": 8, \"length\": 0, \"label\": \"line\\nbreak, \\u0001\"}\n]"
+    );
+    assert_eq!(bookmarks_csv(&annotations), "offset,length,label\n2,1,\"say \"\"hi\"\"\t\\\"\n8,0,\"line\nbreak, \x01\"");
+}
+
+#[test]
+fn bookmarks_without_annotations() {
+    assert_eq!(bookmarks_json(&[]), "[]");
+    assert_eq!(bookmarks_csv(&[]), "offset,length,label");
+}
+
+#[test]
+fn pattern_csv_round_trips_bookmarks() {
+    let annotations = [Annotation::new(0..4, "magic"), Annotation::new(16..48, "name, \"quoted\"")];
+    assert_eq!(parse_pattern_csv(&bookmarks_csv(&annotations)).unwrap(), annotations);
+    let csv = "Address,SIZE,Type,Label,Value\n0x10,0x2,u16,count,3\n18,1,u8,flags,";
+    assert_eq!(
+        parse_pattern_csv(csv).unwrap(),
+        [Annotation::new(16..18, "count").with_value("3"), Annotation::new(18..19, "flags")]
+    );
+}
+
+#[test]
+fn pattern_csv_rejects_invalid_rows() {
+    assert!(matches!(parse_pattern_csv("name,offset\nmagic,0"), Err(HexOutError::InvalidPatternLine { line: 1 })));
+    assert!(matches!(
+        parse_pattern_csv("name,start,size\nmagic,0,4\n\nversion,4h"),
+        Err(HexOutError::InvalidPatternLine { line: 4 })
+    ));
+    assert!(matches!(parse_pattern_csv("name,start,size\nmagic,zero,4"), Err(HexOutError::InvalidPatternLine { line: 2 })));
+    assert!(parse_pattern_csv("").unwrap().is_empty());
+}
+
+#[test]
+fn guess_endianness_from_zero_bytes() {
+    let big = [0x00, 0x10, 0x00, 0x20, 0x01, 0x30, 0x7f];
+    assert_eq!(guess_endianness(&big, 2), Endianness::Big);
+    assert_eq!(guess_endianness(&big[1..], 2), Endianness::Little);
+    assert_eq!(guess_endianness(&big, 1), Endianness::Little);
+    assert_eq!(guess_endianness(&[], 4), Endianness::Little);
+}
+
+#[test]
+fn auto_endianness_follows_the_data() {
+    let settings = HexOutSettings { group_size: 2, groups_per_line: 4, endianness: Endianness::Auto, ..Default::default() };
+    let data = [0x00, 0x10, 0x00, 0x20, 0x01, 0x30, 0x00, 0x40];
+    assert_eq!(hex_out(&data, &settings, 0, 0, 0).unwrap(), "00000000: 0010 0020  0130 0040 |...  .0.@|");
+    // Groups in Auto order follow the data as a whole
+    let group_endianness = vec![Endianness::Big, Endianness::Auto];
+    let settings = HexOutSettings { endianness: Endianness::Little, group_endianness, ..settings };
+    let data = [0x10, 0x00, 0x20, 0x00, 0x30, 0x01, 0x40, 0x00];
+    assert_eq!(hex_out(&data, &settings, 0, 0, 0).unwrap(), "00000000: 1000 0020  0130 0040 |.. . 0.@.|");
+}
+
+#[test]
+fn high_entropy_regions_merge_windows() {
+    // A linear congruential generator stands in for compressed data
+    let mut state = 1u32;
+    let mut data = vec![0u8; 1024];
+    for byte in &mut data[256..768] {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        *byte = (state >> 16) as u8;
+    }
+    let regions = high_entropy_regions(&data, 256, 7.0);
+    assert_eq!(regions.len(), 1);
+    assert_eq!(regions[0].range, 256..768);
+    assert_eq!(regions[0].label, "likely compressed/encrypted");
+    assert!(regions[0].value.as_ref().unwrap().ends_with(" bits/byte"));
+    assert!(high_entropy_regions(&data, 256, 7.9).is_empty());
+    assert!(high_entropy_regions(&[], 0, 0.0).is_empty());
+}
+
+#[test]
+fn entropy_dump_annotates_regions() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        show_ascii: false,
+        highlight_style: Some(Style::new("[", "]")),
+        ..Default::default()
+    };
+    let data = [0, 0, 0, 0, 1, 2, 3, 4, 0, 0];
+    assert_eq!(
+        hex_out_entropy(&data, 4, 2.0, &settings, 0, 0, 0).unwrap(),
+        "00000000: 00 00  00 00\n\
+         00000004: [01] [02]  [03] [04] likely compressed/encrypted=2.00 bits/byte\n\
+         00000008: 00 00 "
+    );
+}
+
+#[test]
+fn digest_column_matches_shifted_lines() {
+    let mut state = 7u32;
+    let data = (0..80)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as u8
+        })
+        .collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        columns: vec![Column::Address, Column::Digest, Column::Hex],
+        show_header: true,
+        ..Default::default()
+    };
+    let digests = |data: &[u8]| {
+        let lines = hex_out_vec(data, &settings, 0, 0, 0).unwrap();
+        lines.iter().skip(1).map(|line| line[10..18].to_string()).collect::<Vec<_>>()
+    };
+    // Lines keep their digest when the data shifts by a byte, but don't match other lines
+    let (original, shifted) = (digests(&data[..64]), digests(&data[1..65]));
+    assert_eq!(original.len(), 4);
+    assert_eq!(original.iter().zip(&shifted).filter(|(original, shifted)| original == shifted).count(), 4);
+    assert!(original.iter().enumerate().all(|(index, digest)| !original[index + 1..].contains(digest)));
+    let settings = HexOutSettings { groups_per_line: 4, ..settings };
+    assert_eq!(
+        hex_out(b"ab", &settings, 0, 0, 0).unwrap(),
+        format!("Address:          00 01  02 03\n00000000: {:08x} 61 62 ", similarity_digest(b"ab"))
+    );
+}
+
+#[test]
+fn digest_column_with_borders() {
+    let settings = HexOutSettings {
+        columns: vec![Column::Address, Column::Hex, Column::Digest],
+        borders: Borders::Ascii,
+        groups_per_line: 2,
+        address_width: 4,
+        ..Default::default()
+    };
+    assert_eq!(
+        hex_out(&[0, 0, 0, 0, 1], &settings, 0, 0, 0).unwrap(),
+        format!(
+            "+------+--------+----------+\n| 0000 | 00  00 | {:08x} |\n| 0002 | 00  00 | {:08x} |\n\
+             | 0004 | 01     | {:08x} |\n+------+--------+----------+",
+            similarity_digest(&[0, 0]),
+            similarity_digest(&[0, 0]),
+            similarity_digest(&[1])
+        )
+    );
+}
+
+#[test]
+fn address_map_replaces_origin() {
+    let map = AddressMap::new(|offset| if offset >= 8 { offset as u64 - 8 + 0x40_1000 } else { offset as u64 });
+    let settings = HexOutSettings {
+        address_origin: 0x100,
+        groups_per_line: 4,
+        show_ascii: false,
+        caption: Some("{address}".to_string()),
+        address_map: Some(map),
+        ..Default::default()
+    };
+    assert_eq!(
+        hex_out(&[0; 12], &settings, 8, 0, 0).unwrap(),
+        "00401000\n00000000:             \n00000004:             \n00401000: 00 00  00 00"
+    );
+}
+
+#[test]
+fn address_map_passes_addresses_to_decoder() {
+    let settings = HexOutSettings {
+        groups_per_line: 2,
+        show_ascii: false,
+        line_decoder: Some(LineDecoder::new(|_, address| Some((2, format!("at {address:x}"))))),
+        address_map: Some(AddressMap::new(|offset| 0xffff_0000 + 2 * offset as u64)),
+        ..Default::default()
+    };
+    assert_eq!(hex_out(&[1, 2, 3, 4], &settings, 0, 0, 0).unwrap(), "ffff0000: 01  02 at ffff0000\nffff0004: 03  04 at ffff0004");
+}
+
+#[test]
+fn concat_matches_joined_buffers() {
+    let data = (0u8..100).collect::<Vec<u8>>();
+    let parts: [&[u8]; 5] = [&data[..7], &data[7..7], &data[7..8], &data[8..61], &data[61..]];
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 3,
+        show_header: true,
+        borders: Borders::Ascii,
+        columns: vec![Column::Address, Column::Hex, Column::Ascii, Column::Checksum],
+        ..Default::default()
+    };
+    assert_eq!(
+        hex_dump_concat(&parts, &settings, 0x1000).unwrap(),
+        hex_out(&data, &HexOutSettings { address_origin: 0x1000, ..settings.clone() }, 0, 0, 0).unwrap()
+    );
+    let settings = HexOutSettings { skip_fill: Some(0), line_decoder: Some(LineDecoder::new(|_, _| None)), ..settings };
+    let data = [[0u8; 30].as_slice(), b"concatenated", &[0; 40]].concat();
+    assert_eq!(
+        hex_dump_concat(&[&data[..25], &data[25..50], &data[50..]], &settings, 0).unwrap(),
+        hex_out(&data, &settings, 0, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn concat_continues_addresses_across_parts() {
+    let settings = HexOutSettings { groups_per_line: 4, ..Default::default() };
+    assert_eq!(
+        hex_dump_concat(&[b"abc", b"defgh", b"i"], &settings, 0xfffe).unwrap(),
+        "0000fffe: 61 62  63 64 |ab cd|\n00010002: 65 66  67 68 |ef gh|\n00010006: 69           |i    |"
+    );
+    assert_eq!(hex_dump_concat(&[], &settings, 0).unwrap(), "");
+}
+
+/// A reader counting the bytes read from it.
+struct CountingReader {
+    inner: std::io::Cursor<Vec<u8>>,
+    bytes_read: usize,
+}
+
+impl std::io::Read for CountingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let count = self.inner.read(buf)?;
+        self.bytes_read += count;
+        Ok(count)
+    }
+}
+
+impl std::io::Seek for CountingReader {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn seekable_source_reads_only_window() {
+    let data = (0..1000u32).map(|index| (index * 7 % 251) as u8).collect::<Vec<u8>>();
+    let reader = CountingReader { inner: std::io::Cursor::new(data.clone()), bytes_read: 0 };
+    let mut source = SeekableHexSource::new(reader).unwrap();
+    let settings = HexOutSettings {
+        caption: Some("{len} bytes".to_string()),
+        show_header: true,
+        show_truncation: true,
+        ..Default::default()
+    };
+    assert_eq!((source.len(), source.line_count(&settings)), (1000, 63));
+    assert_eq!(source.hex_out(&settings, 10, 3).unwrap(), hex_out(&data, &settings, 0, 10, 3).unwrap());
+    assert_eq!(source.hex_out(&settings, 62, 5).unwrap(), hex_out(&data, &settings, 0, 62, 5).unwrap());
+    assert_eq!(source.hex_out(&settings, 70, 1).unwrap(), hex_out(&data, &settings, 0, 70, 1).unwrap());
+    assert_eq!(source.into_inner().bytes_read, 48 + 8);
+}
+
+#[test]
+fn seekable_source_reports_errors() {
+    let mut source = SeekableHexSource::new(std::io::Cursor::new(vec![0u8; 64])).unwrap();
+    let error = source.hex_out(&HexOutSettings { group_size: 0, ..Default::default() }, 0, 1).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        line_decoder: Some(LineDecoder::new(|_, _| Some((1, "ignored".to_string())))),
+        ..Default::default()
+    };
+    assert_eq!(source.hex_out(&settings, 15, 0).unwrap(), "0000003c: 00 00  00 00 |.. ..|");
+}
+
+#[test]
+fn seekable_source_reads_ahead() {
+    let data = (0..4096u32).map(|index| (index % 253) as u8).collect::<Vec<u8>>();
+    let reader = CountingReader { inner: std::io::Cursor::new(data.clone()), bytes_read: 0 };
+    let mut source = SeekableHexSource::new(reader).unwrap().with_read_ahead(256);
+    let settings = HexOutSettings::default();
+    // Lines 20 to 24 are read with 256 bytes on either side, lines 8 to 39 come from memory
+    for start_line in [20, 8, 35, 0, 255] {
+        let expected = hex_out(&data, &settings, 0, start_line, 5).unwrap();
+        assert_eq!(source.hex_out(&settings, start_line, 5).unwrap(), expected);
+        let bytes_read = match start_line {
+            20 | 8 | 35 => 592,
+            0 => 592 + 336,
+            _ => 592 + 336 + 272,
+        };
+        assert_eq!(source.get_ref().bytes_read, bytes_read, "line {start_line}");
+    }
+}
+
+/// A reader returning at most `step` bytes per read, and an interruption before each of them.
+struct TrickleReader {
+    inner: std::io::Cursor<Vec<u8>>,
+    step: usize,
+    interrupted: bool,
+}
+
+impl std::io::Read for TrickleReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.interrupted = !self.interrupted;
+        if self.interrupted {
+            return Err(std::io::ErrorKind::Interrupted.into());
+        }
+        let len = buf.len().min(self.step);
+        self.inner.read(&mut buf[..len])
+    }
+}
+
+#[test]
+fn hex_dump_reader_matches_hex_out_across_chunks() {
+    // Zero runs span chunk boundaries, and 12-byte lines don't divide the chunk size of 64 KiB
+    let data = (0..200_000u32)
+        .map(|index| if index % 65_000 < 300 { 0 } else { (index % 251) as u8 })
+        .collect::<Vec<_>>();
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 3,
+        borders: Borders::Ascii,
+        show_header: true,
+        header_interval: 1000,
+        rule_interval: 7,
+        skip_fill: Some(0),
+        ..Default::default()
+    };
+    let reader = TrickleReader { inner: std::io::Cursor::new(data.clone()), step: 4099, interrupted: false };
+    let mut dump = Vec::new();
+    hex_dump_reader(reader, &mut dump, &settings).unwrap();
+    assert_eq!(String::from_utf8(dump).unwrap(), hex_out(&data, &settings, 0, 0, 0).unwrap() + "\n");
+}
+
+#[test]
+fn hex_dump_reader_caption_and_errors() {
+    let settings = HexOutSettings { groups_per_line: 4, caption: Some("{len} bytes".to_string()), ..Default::default() };
+    let mut dump = Vec::new();
+    hex_dump_reader(&[0xffu8; 6][..], &mut dump, &settings).unwrap();
+    assert_eq!(
+        String::from_utf8(dump).unwrap(),
+        "? bytes\n00000000: ff ff  ff ff |.. ..|\n00000004: ff ff        |..   |\n"
+    );
+    let invalid = HexOutSettings { group_size: 0, ..Default::default() };
+    let error = hex_dump_reader(&b""[..], Vec::new(), &invalid).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    let mut failing = [0u8; 0];
+    let error = hex_dump_reader(&b"data"[..], &mut failing[..], &settings).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn paused_lines_resume_where_they_stopped() {
+    let mut data = vec![0xffu8; 200];
+    data[..40].iter_mut().enumerate().for_each(|(index, byte)| *byte = index as u8);
+    data[150] = 0;
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        caption: Some("{len} bytes".to_string()),
+        show_header: true,
+        header_interval: 2,
+        borders: Borders::Ascii,
+        show_truncation: true,
+        skip_fill: Some(0xff),
+        line_decoder: Some(LineDecoder::new(|bytes, _| (bytes[0] < 8).then_some((2, "pair".to_string())))),
+        ..Default::default()
+    };
+    let expected = hex_out_vec(&data, &settings, 0, 1, 40).unwrap();
+    for pause_after in 0..=expected.len() {
+        let mut lines = HexOutLines::new(&data, &settings, 0, 1, 40).unwrap();
+        let mut dump = (0..pause_after).map(|_| lines.next_line().unwrap().to_string()).collect::<Vec<_>>();
+        let state = lines.pause();
+        let mut lines = HexOutLines::resume(&data, &settings, &state).unwrap();
+        while let Some(line) = lines.next_line() {
+            dump.push(line.to_string());
+        }
+        assert_eq!(dump, expected, "paused after {pause_after} lines");
+    }
+}
+
+#[test]
+fn render_state_reports_progress() {
+    let settings = HexOutSettings { show_header: true, ..Default::default() };
+    let mut lines = HexOutLines::new(&[0; 40], &settings, 0, 0, 0).unwrap();
+    lines.next_line();
+    lines.next_line();
+    let state = lines.pause();
+    assert_eq!((state.lines_rendered(), state.is_done()), (1, false));
+    let mut lines = HexOutLines::resume(&[0; 40], &settings, &state).unwrap();
+    assert_eq!(lines.next_line(), Some("00000010: 00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00 |........ ........|"));
+    assert_eq!(lines.next_line(), Some("00000020: 00 00 00 00 00 00 00 00                          |........         |"));
+    assert_eq!(lines.next_line(), None);
+    let state = lines.pause();
+    assert_eq!((state.lines_rendered(), state.is_done()), (3, true));
+}
+
+/// Dumps pinned by the output stability guarantee; a change to any of them is a breaking change.
+#[test]
+fn stable_format_golden_dumps() {
+    let data = (0u8..=255).step_by(7).take(37).collect::<Vec<u8>>();
+    let cases = [
+        (
+            HexOutSettings::default(),
+            "00000000:          15 1c 23 2a 31  38 3f 46 4d 54 5b 62 69 |   ..#*1 8?FMT[bi|\n\
+             00000010: 70 77 7e 85 8c 93 9a a1  a8 af b6 bd c4 cb d2 d9 |pw~..... ........|\n\
+             00000020: e0 e7 ee f5 fc                                   |.....            |",
+        ),
+        (
+            HexOutSettings {
+                group_size: 4,
+                groups_per_line: 4,
+                endianness: Endianness::Big,
+                show_header: true,
+                uppercase: true,
+                ..Default::default()
+            },
+            "Address:       00       04        08       0C\n\
+             00000000: ??????15 1C232A31  383F464D 545B6269 |   ..#*1 8?FMT[bi|\n\
+             00000010: 70777E85 8C939AA1  A8AFB6BD C4CBD2D9 |pw~..... ........|\n\
+             00000020: E0E7EEF5 FC??????                    |.....            |",
+        ),
+        (
+            HexOutSettings {
+                borders: Borders::Unicode,
+                groups_per_line: 8,
+                columns: vec![Column::LineNumber, Column::Address, Column::Hex, Column::Ascii, Column::Checksum],
+                ..Default::default()
+            },
+            "┌──────────┬──────────┬──────────────────────────┬───────────┬────┐\n\
+             │        0 │ 00000000 │          15  1c 23 2a 31 │    . .#*1 │ af │\n\
+             │        1 │ 00000008 │ 38 3f 46 4d  54 5b 62 69 │ 8?FM T[bi │ 84 │\n\
+             │        2 │ 00000010 │ 70 77 7e 85  8c 93 9a a1 │ pw~. .... │ 44 │\n\
+             │        3 │ 00000018 │ a8 af b6 bd  c4 cb d2 d9 │ .... .... │ 04 │\n\
+             │        4 │ 00000020 │ e0 e7 ee f5  fc          │ .... .    │ a6 │\n\
+             └──────────┴──────────┴──────────────────────────┴───────────┴────┘",
+        ),
+        (
+            HexOutSettings { binary: true, groups_per_line: 4, show_base64: true, address_origin: 0xfff0, ..Default::default() },
+            "0000fff0:                             00010101 |    .| FQ==\n\
+             0000fff4: 00011100 00100011  00101010 00110001 |.# *1| HCMqMQ==\n\
+             0000fff8: 00111000 00111111  01000110 01001101 |8? FM| OD9GTQ==\n\
+             0000fffc: 01010100 01011011  01100010 01101001 |T[ bi| VFtiaQ==\n\
+             00010000: 01110000 01110111  01111110 10000101 |pw ~.| cHd+hQ==\n\
+             00010004: 10001100 10010011  10011010 10100001 |.. ..| jJOaoQ==\n\
+             00010008: 10101000 10101111  10110110 10111101 |.. ..| qK+2vQ==\n\
+             0001000c: 11000100 11001011  11010010 11011001 |.. ..| xMvS2Q==\n\
+             00010010: 11100000 11100111  11101110 11110101 |.. ..| 4Ofu9Q==\n\
+             00010014: 11111100                             |.    | /A==",
+        ),
+    ];
+    for (settings, expected) in cases {
+        assert_eq!(hex_out(&data, &settings, 3, 0, 0).unwrap(), expected);
+    }
+}
+
+/// Entropy values computed in integer arithmetic with `stable-format`, the same on every platform.
+#[cfg(feature = "stable-format")]
+#[test]
+fn stable_format_entropy_values() {
+    let mut state = 1u32;
+    let mut data = vec![0u8; 1024];
+    for byte in &mut data[256..768] {
+        state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        *byte = (state >> 16) as u8;
+    }
+    let values = |data: &[u8], window| {
+        high_entropy_regions(data, window, 0.0).into_iter().map(|region| region.value.unwrap()).collect::<Vec<_>>()
+    };
+    assert_eq!(values(&data[256..768], 512), ["7.62 bits/byte"]);
+    assert_eq!(values(b"abcabcabc", 9), ["1.58 bits/byte"]);
+    assert_eq!(values(b"a", 1), ["0.00 bits/byte"]);
+    assert_eq!(values(&(0..=255).collect::<Vec<u8>>(), 256), ["8.00 bits/byte"]);
+}
+
+#[test]
+fn tagged_bookmarks_carry_format_version() {
+    let annotations = [Annotation::new(0..4, "magic"), Annotation::new(4..6, "ver\"sion")];
+    let json: serde_json::Value = serde_json::from_str(&bookmarks_json_tagged(&annotations)).unwrap();
+    assert_eq!(json["format"], "hexout-bookmarks");
+    assert_eq!(json["version"], format_version());
+    assert_eq!(json["bookmarks"], serde_json::from_str::<serde_json::Value>(&bookmarks_json(&annotations)).unwrap());
+    assert_eq!(
+        bookmarks_json_tagged(&[]),
+        format!("{{\n  \"format\": \"hexout-bookmarks\",\n  \"version\": \"{}\",\n  \"bookmarks\": []\n}}", format_version())
+    );
+}
+
+#[test]
+fn line_sink_emits_whole_lines() {
+    use std::io::Write;
+    let mut records = Vec::new();
+    let mut sink = LineSink::new(|line: &str| records.push(line.to_string()));
+    sink.write_all(b"first li").unwrap();
+    sink.write_all(b"ne\r\nsecond\n\nbad \xff").unwrap();
+    sink.flush().unwrap();
+    sink.flush().unwrap();
+    sink.write_all(b"unterminated").unwrap();
+    drop(sink);
+    assert_eq!(records, ["first line", "second", "", "bad \u{fffd}", "unterminated"]);
+}
+
+#[test]
+fn line_sink_takes_dumps_line_by_line() {
+    let data = (0u8..48).collect::<Vec<u8>>();
+    let settings = HexOutSettings { caption: Some("{len} bytes".to_string()), ..Default::default() };
+    let mut records = Vec::new();
+    let mut sink = LineSink::new(|line: &str| records.push(line.to_string()));
+    std::fmt::Write::write_str(&mut sink, &hex_out(&data, &settings, 0, 0, 0).unwrap()).unwrap();
+    drop(sink);
+    assert_eq!(records, hex_out_vec(&data, &settings, 0, 0, 0).unwrap());
+}
+
+#[test]
+fn shared_types_are_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<HexOutSettings>();
+    assert_send_sync::<CompactSettings>();
+    assert_send_sync::<StyleTable>();
+    assert_send_sync::<Themes>();
+    assert_send_sync::<HexOutError>();
+    assert_send_sync::<HexOutLines>();
+    assert_send_sync::<RenderState>();
+    assert_send_sync::<SnapshotDiffer>();
+    assert_send_sync::<SeekableHexSource<std::fs::File>>();
+    assert_send_sync::<HtmlReport>();
+    assert_send_sync::<Layout>();
+    assert_send_sync::<Pipeline>();
+}
+
+#[test]
+fn dump_many_keeps_order_and_errors() {
+    let packets = (0..50usize).map(|length| vec![length as u8; length]).collect::<Vec<_>>();
+    let batches = packets.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    // Addresses of the longer packets overflow
+    let settings = HexOutSettings { groups_per_line: 4, address_origin: u128::MAX - 20, ..Default::default() };
+    let dumps = dump_many(&batches, &settings);
+    assert_eq!(dumps.len(), 50);
+    for (packet, dump) in packets.iter().zip(&dumps) {
+        match hex_out(packet, &settings, 0, 0, 0) {
+            Ok(expected) => assert_eq!(dump.as_ref().unwrap(), &expected),
+            Err(_) => assert!(matches!(dump, Err(HexOutError::RangeOverflow))),
+        }
+    }
+    assert_eq!(dumps.iter().filter(|dump| dump.is_err()).count(), 29);
+    assert!(dump_many(&[], &settings).is_empty());
+}
+
+#[test]
+fn segments_make_up_plain_lines() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        borders: Borders::Unicode,
+        show_header: true,
+        stripe_style: Some(Style::ansi("2")),
+        highlights: vec![2..5, 11..12],
+        cursor: Some(6),
+        address_style: Some(Style::ansi("36")),
+        ..Default::default()
+    };
+    let data = b"segmented output";
+    let mut lines = Vec::new();
+    hex_out_segments(data, &settings, 1, 0, 0, |segments| {
+        // Neighbouring segments differ in class or column
+        assert!(segments.windows(2).all(|pair| (pair[0].class, pair[0].column) != (pair[1].class, pair[1].column)));
+        lines.push(segments.iter().map(|segment| segment.text).collect::<String>());
+    })
+    .unwrap();
+    assert_eq!(lines, hex_out_vec(data, &settings.without_styles(), 1, 0, 0).unwrap());
+}
+
+#[test]
+fn segments_split_at_columns() {
+    let settings = HexOutSettings { groups_per_line: 2, address_width: 4, cursor: Some(1), ..Default::default() };
+    let mut lines = Vec::new();
+    hex_out_segments(b"ab", &settings, 0, 0, 0, |segments| {
+        let segments = segments.iter().map(|segment| (segment.text.to_string(), segment.class, segment.column));
+        lines.push(segments.collect::<Vec<_>>());
+    })
+    .unwrap();
+    assert_eq!(
+        lines[0],
+        [
+            ("0000".to_string(), HexCellClass::Address, Some(Column::Address)),
+            (": ".to_string(), HexCellClass::Plain, None),
+            ("61  ".to_string(), HexCellClass::Plain, Some(Column::Hex)),
+            ("62".to_string(), HexCellClass::Cursor, Some(Column::Hex)),
+            (" |".to_string(), HexCellClass::Plain, None),
+            ("a ".to_string(), HexCellClass::Plain, Some(Column::Ascii)),
+            ("b".to_string(), HexCellClass::Cursor, Some(Column::Ascii)),
+            ("|".to_string(), HexCellClass::Plain, None),
+        ]
+    );
+}
+
+#[test]
+fn changed_rows_of_cursor_move_and_growth() {
+    let data = (0u8..40).collect::<Vec<u8>>();
+    let settings = HexOutSettings { cursor: Some(3), show_header: true, ..Default::default() };
+    let old = hex_out_cells(&data[..32], &settings, 0, 0, 0).unwrap();
+    // The header is row 0, so moving the cursor to the next line repaints rows 1 and 2; the new line is row 3
+    let settings = HexOutSettings { cursor: Some(20), ..settings };
+    let new = hex_out_cells(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(changed_rows(&old, &new), [1, 2, 3]);
+    assert_eq!(changed_rows(&new, &old), [1, 2, 3]);
+    assert!(changed_rows(&new, &new).is_empty());
+}
+
+#[test]
+fn changed_rows_of_lines() {
+    let old = hex_out_vec(&[0u8; 48], &HexOutSettings::default(), 0, 0, 0).unwrap();
+    let new = hex_out_vec(&[0u8; 16], &HexOutSettings::default(), 0, 0, 0).unwrap();
+    assert_eq!(changed_rows(&old, &new), [1, 2]);
+    assert_eq!(changed_rows::<String>(&[], &[]), Vec::<usize>::new());
+}
+
+#[test]
+fn find_all_hits() {
+    assert_eq!(find_all(b"aaaa", b"aa"), [0, 1, 2]);
+    assert_eq!(find_all(b"abc", b"abcd"), Vec::<usize>::new());
+    assert_eq!(find_all(b"abc", b""), Vec::<usize>::new());
+    assert_eq!(find_all(b"\x00\xff\x00\xff", b"\xff"), [1, 3]);
+}
+
+#[test]
+fn hit_windows_merge_context_and_follow_offset() {
+    let settings = HexOutSettings { groups_per_line: 4, ..Default::default() };
+    // Hits spanning lines 1 and 2, 2 and 3, and 10 and 11, in any order
+    assert_eq!(hit_windows(&[42, 11, 6], 3, &settings, 0, 0), [(1, 3), (10, 2)]);
+    assert_eq!(hit_windows(&[42, 11, 6], 3, &settings, 0, 1), [(0, 5), (9, 4)]);
+    // Windows of neighbouring lines are merged, and a hit covers at least one byte
+    assert_eq!(hit_windows(&[0, 4], 0, &settings, 0, 0), [(0, 2)]);
+    // Hits before the offset are left out; with aligned addresses, lines are counted from 0, otherwise from the offset
+    let data = (0u8..13).collect::<Vec<u8>>();
+    assert_eq!(hit_windows(&[2, 9], 1, &settings, 5, 0), [(2, 1)]);
+    assert_eq!(hex_out(&data[..12], &settings, 5, 2, 1).unwrap(), "00000008: 08 09  0a 0b |.. ..|");
+    let settings = HexOutSettings { align_address: false, ..settings };
+    assert_eq!(hit_windows(&[2, 9], 1, &settings, 5, 0), [(1, 1)]);
+    assert_eq!(hex_out(&data, &settings, 5, 1, 1).unwrap(), "00000009: 09 0a  0b 0c |.. ..|");
+}
+
+#[test]
+fn records_of_offset_and_partial_lines() {
+    let data = (0x41u8..0x55).collect::<Vec<u8>>();
+    let settings = HexOutSettings { group_size: 4, groups_per_line: 2, address_origin: 0x1000, ..Default::default() };
+    let records = hex_dump_records(&data, &settings, 2, 0, 0).unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!((records[0].offset, records[0].address, &records[0].bytes[..]), (0, 0x1000, &data[2..8]));
+    assert_eq!(records[0].groups, [Some("4443????".to_string()), Some("48474645".to_string())]);
+    assert_eq!(records[0].ascii, "  CDEFGH");
+    assert_eq!((records[2].offset, records[2].address, &records[2].bytes[..]), (16, 0x1010, &data[16..]));
+    assert_eq!(records[2].groups, [Some("54535251".to_string()), None]);
+    assert_eq!(records[2].ascii, "QRST");
+    // Lines before the offset are skipped, like by a dump
+    let settings = HexOutSettings { align_address: false, ..settings };
+    let records = hex_dump_records(&data, &settings, 6, 1, 0).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].offset, 14);
+    assert_eq!(records[0].groups, [Some("5251504f".to_string()), Some("????5453".to_string())]);
+    assert!(matches!(hex_dump_records(&data, &settings, 0, usize::MAX, 1), Err(HexOutError::RangeOverflow)));
+}
+
+#[test]
+fn records_follow_formatting_but_not_layout() {
+    let mut data = vec![0u8; 24];
+    data[..6].copy_from_slice(b"\x01\x02a\nbc");
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 4,
+        dual_endianness: true,
+        group_prefix: "<".to_string(),
+        group_suffix: ">".to_string(),
+        text_mode: TextMode::Escaped,
+        skip_fill: Some(0),
+        show_header: true,
+        borders: Borders::Unicode,
+        guide_interval: 4,
+        cursor: Some(1),
+        cursor_style: Some(Style::new("[", "]")),
+        line_annotator: Some(LineAnnotator::new(|_, _| Some("note".to_string()))),
+        ..Default::default()
+    };
+    let records = hex_dump_records(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(
+        records[0].groups,
+        ["<0201/0102>", "<0a61/610a>", "<6362/6263>", "<0000/0000>"].map(|group| Some(group.to_string()))
+    );
+    assert_eq!(records[0].ascii, ". . a \\nb c \\0\\0");
+}
+
+#[test]
+fn classify_groups() {
+    let classifier = GroupClassifier {
+        pointer_ranges: vec![0x1000..0x2000, 0x8000..0x9000],
+        small_integer_max: 100,
+        ..Default::default()
+    };
+    assert_eq!(classifier.classify(&[0x00, 0x10, 0x00, 0x00], Endianness::Little), GroupKind::Pointer);
+    assert_eq!(classifier.classify(&[0x00, 0x00, 0x80, 0x00], Endianness::Big), GroupKind::Pointer);
+    assert_eq!(classifier.classify(&[0x00, 0x20, 0x00, 0x00], Endianness::Little), GroupKind::Unknown);
+    assert_eq!(classifier.classify(b"ok", Endianness::Little), GroupKind::Text);
+    assert_eq!(classifier.classify(&[100, 0], Endianness::Little), GroupKind::SmallInteger);
+    assert_eq!(classifier.classify(&[101, 0], Endianness::Little), GroupKind::Unknown);
+    assert_eq!(classifier.classify(&(-3.25f64).to_be_bytes(), Endianness::Big), GroupKind::Float);
+    assert_eq!(classifier.classify(&1e30f32.to_le_bytes(), Endianness::Little), GroupKind::Unknown);
+    assert_eq!(classifier.classify(&f32::NAN.to_le_bytes(), Endianness::Little), GroupKind::Unknown);
+}
+
+#[test]
+fn classify_annotator_blanks_partial_groups() {
+    let mut settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 4,
+        endianness: Endianness::Little,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let classifier = GroupClassifier { small_integer_max: 0xff, ..Default::default() };
+    settings.line_annotator = Some(classifier.annotator(&settings));
+    let data = b"\x01\x00\x02\x00hi\xff\xff\x03\x00\x04";
+    let result = hex_out(data, &settings, 1, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 00?? 0002  6968 ffff  ia.\n00000008: 0003 ??04            i ");
+}
+
+#[test]
+fn classify_annotator_follows_group_endianness_and_offset_grid() {
+    let mut settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 2,
+        group_endianness: vec![Endianness::Big, Endianness::Little],
+        align_address: false,
+        show_ascii: false,
+        show_centerline: false,
+        ..Default::default()
+    };
+    let classifier = GroupClassifier { small_integer_max: 0xff, ..Default::default() };
+    settings.line_annotator = Some(classifier.annotator(&settings));
+    let result = hex_out(b"\xee\x00\x07\x00\x01\x00\x09", &settings, 1, 0, 0).unwrap();
+    assert_eq!(result, "00000001: 0007 0100 i.\n00000005: 0009      i");
+}
+
+#[test]
+fn telescope_dereferences_pointers() {
+    let memory = b"GET /index.html";
+    let resolver = PointerResolver::new(move |address| {
+        let start = usize::try_from(address.checked_sub(0x7f00_0000)?).ok()?;
+        Some(memory.get(start..)?.iter().take(4).copied().collect())
+    });
+    let settings = HexOutSettings {
+        group_size: 8,
+        groups_per_line: 2,
+        uppercase: true,
+        show_ascii: false,
+        line_annotator: Some(LineAnnotator::new(|offset, _| Some(format!("+{offset}")))),
+        ..Default::default()
+    };
+    let data = [0x7f00_0000u64, 0x7f00_0004, 0x10, 0x7f00_000e].map(u64::to_le_bytes).concat();
+    let result = hex_out_telescope(&data, &resolver, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "00000000: 000000007F000000  000000007F000004 +0, 0x7f000000 -> 47 45 54 20, 0x7f000004 -> 2F 69 6E 64\n\
+         00000010: 0000000000000010  000000007F00000E +16, 0x7f00000e -> 6C"
+    );
+}
+
+#[test]
+fn telescope_skips_groups_cut_short() {
+    let resolver = PointerResolver::new(|address| (address < 0x100).then(|| vec![address as u8]));
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 2,
+        endianness: Endianness::Big,
+        show_ascii: false,
+        ..Default::default()
+    };
+    let result = hex_out_telescope(&[0x00, 0x01, 0x00, 0x02, 0x00], &resolver, &settings, 1, 0, 0).unwrap();
+    assert_eq!(result, "00000000: ??01  0002 0x2 -> 02\n00000004: 00?? ");
+}
+
+#[test]
+fn checksum_algorithms() {
+    assert_eq!(ChecksumAlgorithm::Sum8.compute(&[0xff, 0x02]), 0x01);
+    assert_eq!(ChecksumAlgorithm::Xor8.compute(b"GPGLL"), 0x47 ^ 0x50 ^ 0x47 ^ 0x4c ^ 0x4c);
+    assert_eq!(ChecksumAlgorithm::Crc16.compute(b""), 0xffff);
+    assert_eq!(ChecksumAlgorithm::Crc32.compute(b""), 0);
+    assert_eq!(ChecksumAlgorithm::Crc32.compute(b"The quick brown fox jumps over the lazy dog"), 0x414f_a339);
+    assert_eq!(ChecksumAlgorithm::Adler32.compute(b"Wikipedia"), 0x11e6_0398);
+}
+
+#[test]
+fn checksum_report() {
+    let mut data = b"\x00\x04data".to_vec();
+    data.extend_from_slice(&ChecksumAlgorithm::Crc32.compute(b"data").to_le_bytes());
+    data.extend_from_slice(&[0x12, 0x34]);
+    let fields = [
+        ChecksumField::new("crc", 6, ChecksumAlgorithm::Crc32, 2..6).with_endianness(Endianness::Little),
+        ChecksumField::new("crc16", 10, ChecksumAlgorithm::Crc16, 0..10),
+        ChecksumField::new("trailer", 12, ChecksumAlgorithm::Sum8, 0..12),
+    ];
+    let mut annotations = Layout::new().u16_be("len").text("body", "len").apply(&data);
+    annotations.extend(verify_checksums(&data, &fields));
+    let settings = HexOutSettings { groups_per_line: 8, show_ascii: false, ..Default::default() };
+    let result = hex_out_annotated(&data, &annotations, &settings, 0, 0, 0).unwrap();
+    assert_eq!(
+        result,
+        "00000000: 00 04 64 61  74 61 63 f3 len=4, body=\"data\", crc=OK\n\
+         00000008: f3 ad 12 34              crc16=BAD (computed 0x5e11)"
+    );
+}
+
+#[test]
+fn alignment_guides() {
+    let data = (0u8..32).collect::<Vec<u8>>();
+    let settings = HexOutSettings { groups_per_line: 32, show_ascii: false, guide_interval: 4, ..Default::default() };
+    let result = hex_out(&data[..20], &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 00 01 02 03┊04 05 06 07┊08 09 0a 0b┊0c 0d 0e 0f  10 11 12 13");
+    let result = hex_out(&data, &settings, 0, 0, 0).unwrap();
+    assert_eq!(&result[10..], "00 01 02 03┊04 05 06 07┊08 09 0a 0b┊0c 0d 0e 0f  10 11 12 13┊14 15 16 17┊18 19 1a 1b┊1c 1d 1e 1f");
+}
+
+#[test]
+fn alignment_guides_between_groups_only_and_styled() {
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 4,
+        show_centerline: false,
+        guide_interval: 3,
+        guide_char: ' ',
+        guide_style: Some(Style::new("[", "]")),
+        flag_marker: Some('^'),
+        ..Default::default()
+    };
+    let result = hex_out_flagged(b"abcdefgh", &[0, 0, 0, 0, 0, 0, 1, 0], &settings, 0, 0, 0).unwrap();
+    let markers = "\n                           ^^        ^";
+    assert_eq!(result, format!("00000000: 6261 6463 6665[ ]6867 |abcdefgh|{markers}"));
+    assert_eq!(plain(&result, &settings), hex_out(b"abcdefgh", &settings.without_styles(), 0, 0, 0).unwrap() + markers);
+}
+
+#[test]
+fn xxd_export_matches_xxd() {
+    let data = b"hexample in xxd, with \x00\x01 bytes\xff!";
+    let settings = HexOutSettings { group_size: 3, groups_per_line: 3, uppercase: true, ..Default::default() };
+    let text = xxd_export(data, &settings).unwrap();
+    // The output of `xxd -g 3 -c 9 -u`
+    let xxd = "00000000: 686578 616D70 6C6520  hexample \n\
+               00000009: 696E20 787864 2C2077  in xxd, w\n\
+               00000012: 697468 200001 206279  ith .. by\n\
+               0000001b: 746573 FF21           tes.!\n";
+    assert_eq!(text.split_once('\n').unwrap().1, xxd);
+    let (restored, restored_settings) = undump(&text).unwrap();
+    assert_eq!(restored, data);
+    assert_eq!(hex_out(&restored, &restored_settings, 0, 0, 0).unwrap(), hex_out(data, &settings, 0, 0, 0).unwrap());
+    assert_eq!(undump(xxd).unwrap().0, data);
+}
+
+#[test]
+fn undump_fills_gaps_and_rejects_invalid_lines() {
+    let (data, settings) = undump("# a comment\n\n00000004: 0102  ..\n00000000: ff\n").unwrap();
+    assert_eq!(data, [0xff, 0, 0, 0, 1, 2]);
+    assert_eq!(settings.group_size, 1);
+    let header = "# hexout-xxd version=2.0.0 group_size=4 endianness=big future=1\n";
+    let settings = undump(header).unwrap().1;
+    assert_eq!((settings.group_size, settings.endianness), (4, Endianness::Big));
+    assert!(matches!(undump("# hexout-xxd group_size=four"), Err(HexOutError::InvalidDumpLine { line: 1 })));
+    assert!(matches!(undump("00000000: 41  A\nno address"), Err(HexOutError::InvalidDumpLine { line: 2 })));
+    assert!(matches!(undump("00000000: 4g  A"), Err(HexOutError::InvalidDumpLine { line: 1 })));
+    let settings = HexOutSettings { group_size: 0, ..Default::default() };
+    assert!(matches!(xxd_export(b"", &settings), Err(HexOutError::InvalidGroupSize)));
+}
+
+#[test]
+fn pipeline_without_stages_matches_hex_out() {
+    let data = (0u8..40).collect::<Vec<u8>>();
+    let settings = HexOutSettings { group_size: 4, groups_per_line: 4, ..Default::default() };
+    assert_eq!(Pipeline::new(settings.clone()).run(&data).unwrap(), hex_out(&data, &settings, 0, 0, 0).unwrap());
+    let settings = HexOutSettings { group_size: 0, ..Default::default() };
+    let emitted = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let pipeline = Pipeline::new(settings).emit({
+        let emitted = emitted.clone();
+        move |_| emitted.store(true, std::sync::atomic::Ordering::Relaxed)
+    });
+    assert!(matches!(pipeline.run(&data), Err(HexOutError::InvalidGroupSize)));
+    assert!(!emitted.load(std::sync::atomic::Ordering::Relaxed));
+}
+
+#[test]
+fn pipeline_stages_run_in_order() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        show_ascii: false,
+        line_annotator: Some(LineAnnotator::new(|offset, _| (offset == 0).then(|| "first".to_string()))),
+        ..Default::default()
+    };
+    let pipeline = Pipeline::new(settings)
+        .transform(|data| data.extend_from_slice(b"!"))
+        .transform(|data| data.reverse())
+        .analyze(|data| vec![Annotation::new(0..1, "mark").with_value(format!("{:02x}", data[0]))])
+        .analyze(|data| vec![Annotation::new(4..5, "len").with_value(data.len().to_string())])
+        .annotate(LineAnnotator::new(|_, bytes| Some(format!("{} bytes", bytes.len()))))
+        .annotate(LineAnnotator::new(|_, _| None))
+        .render_with(|data, annotations, settings| {
+            let dump = hex_out_annotated(data, annotations, settings, 0, 0, 0)?;
+            Ok(format!("{} annotations\n{dump}", annotations.len()))
+        });
+    assert_eq!(
+        pipeline.run(b"abcd").unwrap(),
+        "2 annotations\n00000000: 21 64  63 62 first, 4 bytes, mark=21\n00000004: 61           1 bytes, len=5"
+    );
+    assert_eq!(pipeline.settings().groups_per_line, 4);
+}
+
+#[test]
+fn styled_areas_error_bytes_in_ascii_column() {
+    let settings = HexOutSettings {
+        group_size: 2,
+        groups_per_line: 2,
+        hex_out_error_prefix: Some("<".to_string()),
+        hex_out_error_postfix: Some(">".to_string()),
+        ..Default::default()
+    };
+    let result = hex_out(b"abc", &settings, 1, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 62<??>  <??>63 | b c |");
+    let settings = HexOutSettings { styled_areas: StyledAreas::Both, ascii_placeholder: '.', ..settings };
+    let result = hex_out(b"abc", &settings, 1, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 62<??>  <??>63 |<.>b c<.>|");
+}
+
+#[test]
+fn styled_areas_hex_or_ascii_only() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        cursor: Some(1),
+        cursor_style: Some(Style::new("[", "]")),
+        flag_style: Some(Style::new("!", "!")),
+        styled_areas: StyledAreas::HexOnly,
+        ..Default::default()
+    };
+    let result = hex_out_flagged(b"abcd", &[0, 0, 1, 0], &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 61 [62]  !63! 64 |ab cd|");
+    let settings = HexOutSettings { styled_areas: StyledAreas::AsciiOnly, ..settings };
+    let result = hex_out_flagged(b"abcd", &[0, 0, 1, 0], &settings, 0, 0, 0).unwrap();
+    assert_eq!(result, "00000000: 61 62  63 64 |a[b] !c!d|");
+    assert_eq!(CompactSettings::from(&settings).styled_areas, StyledAreas::AsciiOnly);
+}
+
+#[test]
+fn xxd_preset_matches_xxd() {
+    let data = b"# hexout\n\nA compact and depende";
+    let settings = HexOutSettings { group_size: 4, groups_per_line: 4, ..HexOutSettings::xxd() };
+    // The output of `xxd -s 3 -g 4`
+    assert_eq!(
+        hex_out(data, &settings, 3, 0, 0).unwrap(),
+        "00000003: 65786f75 740a0a41 20636f6d 70616374  exout..A compact\n\
+         00000013: 20616e64 20646570 656e6465            and depende"
+    );
+    let text = xxd_export(data, &HexOutSettings { group_size: 4, ..Default::default() }).unwrap();
+    assert_eq!(undump(&text).unwrap().0, data);
+}
+
+#[test]
+fn xxd_preset_matches_xxd_for_odd_lengths() {
+    // The output of `xxd`, with no trailing spaces after a last group cut short
+    assert_eq!(
+        hex_out(b"odd length\x01", &HexOutSettings::xxd(), 0, 0, 0).unwrap(),
+        "00000000: 6f64 6420 6c65 6e67 7468 01              odd length."
+    );
+    // The output of `xxd -g 4 -c 8`
+    let data = b"hexample in xxd, ok!\x7f";
+    let settings = HexOutSettings { group_size: 4, groups_per_line: 2, ..HexOutSettings::xxd() };
+    let xxd = "00000000: 68657861 6d706c65  hexample\n\
+               00000008: 20696e20 7878642c   in xxd,\n\
+               00000010: 206f6b21 7f         ok!.\n";
+    assert_eq!(hex_out(data, &settings, 0, 0, 0).unwrap() + "\n", xxd);
+    let text = xxd_export(data, &settings).unwrap();
+    assert_eq!(text.split_once('\n').unwrap().1, xxd);
+    // The output of `xxd -g 2 -c 4 -u`
+    let settings = HexOutSettings { groups_per_line: 2, uppercase: true, ..HexOutSettings::xxd() };
+    assert_eq!(hex_out(b"abc", &settings, 0, 0, 0).unwrap(), "00000000: 6162 63    abc");
+}
+
+#[test]
+fn xxd_include_matches_xxd_i() {
+    let data = b"# hexout\n\nA compact and depend";
+    // The output of `xxd -i fw-1.0.bin`
+    assert_eq!(
+        xxd_include(data, Some("fw-1.0.bin")),
+        "unsigned char fw_1_0_bin[] = {\n\
+         \x20 0x23, 0x20, 0x68, 0x65, 0x78, 0x6f, 0x75, 0x74, 0x0a, 0x0a, 0x41, 0x20,\n\
+         \x20 0x63, 0x6f, 0x6d, 0x70, 0x61, 0x63, 0x74, 0x20, 0x61, 0x6e, 0x64, 0x20,\n\
+         \x20 0x64, 0x65, 0x70, 0x65, 0x6e, 0x64\n\
+         };\n\
+         unsigned int fw_1_0_bin_len = 30;\n"
+    );
+    assert_eq!(xxd_include(b"", Some("1é")), "unsigned char __1__[] = {\n};\nunsigned int __1___len = 0;\n");
+    assert_eq!(xxd_include(b"", None), "");
+}
+
+#[test]
+fn hex_out_folded_repeats_header_and_frame() {
+    let settings = HexOutSettings {
+        groups_per_line: 4,
+        show_header: true,
+        borders: Borders::Ascii,
+        caption: Some("{len} bytes".to_string()),
+        ..Default::default()
+    };
+    let folded = hex_out_folded(b"folded in three columns", &settings, 3, 0, 0, 0).unwrap();
+    let frame = "+----------+--------------+-------+";
+    let header = "| Address  | 00 01  02 03 |       |";
+    let separator = "+----------+--------------+-------+";
+    assert_eq!(
+        folded.lines().collect::<Vec<_>>(),
+        [
+            "23 bytes".to_string(),
+            [frame; 3].join("    "),
+            [header; 3].join("    "),
+            [separator; 3].join("    "),
+            "| 00000000 | 66 6f  6c 64 | fo ld |    | 00000008 | 6e 20  74 68 | n  th |    \
+             | 00000010 | 63 6f  6c 75 | co lu |"
+                .to_string(),
+            "| 00000004 | 65 64  20 69 | ed  i |    | 0000000c | 72 65  65 20 | re e  |    \
+             | 00000014 | 6d 6e  73    | mn s  |"
+                .to_string(),
+            [frame; 3].join("    "),
+        ]
+    );
+}
+
+#[test]
+fn hex_out_folded_keeps_notes_and_limits_columns() {
+    let data = b"0123456789abcdef";
+    let settings = HexOutSettings { groups_per_line: 4, show_truncation: true, ..Default::default() };
+    assert_eq!(hex_out_folded(data, &settings, 0, 0, 0, 0).unwrap(), hex_out(data, &settings, 0, 0, 0).unwrap());
+    assert_eq!(
+        hex_out_folded(data, &settings, 8, 0, 1, 2).unwrap(),
+        "… 1 line above …                  00000008: 38 39  61 62 |89 ab|\n\
+         00000004: 34 35  36 37 |45 67|    … 1 line below …"
+    );
+}
+
+#[test]
+fn hexdump_c_preset_matches_hexdump() {
+    let settings = HexOutSettings::hexdump_c();
+    // The output of `hexdump -C` and `hexdump -C -s 3`
+    assert_eq!(
+        hex_out(b"hello world\n", &settings, 0, 0, 0).unwrap(),
+        "00000000  68 65 6c 6c 6f 20 77 6f  72 6c 64 0a              |hello world.|\n\
+         0000000c"
+    );
+    assert_eq!(
+        hex_out(b"hello world\n", &settings, 3, 0, 0).unwrap(),
+        "00000003  6c 6f 20 77 6f 72 6c 64  0a                       |lo world.|\n\
+         0000000c"
+    );
+    assert_eq!(hex_out(b"", &settings, 0, 0, 0).unwrap(), "");
+    let header = HexOutSettings { show_header: true, ..HexOutSettings::hexdump_c() };
+    assert_eq!(
+        hex_out(b"ab", &header, 0, 0, 0).unwrap(),
+        "Address  00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f\n\
+         00000000  61 62                                             |ab|\n\
+         00000002"
+    );
+}
+
+#[test]
+fn squeeze_repeats_carries_across_chunks() {
+    let mut data = vec![0u8; 200_000];
+    data.extend_from_slice(b"end");
+    let settings = HexOutSettings::hexdump_c();
+    let dump = "00000000  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|\n\
+                *\n\
+                00030d40  65 6e 64                                          |end|\n\
+                00030d43";
+    assert_eq!(hex_out(&data, &settings, 0, 0, 0).unwrap(), dump);
+    let mut streamed = Vec::new();
+    let reader = TrickleReader { inner: std::io::Cursor::new(data), step: 7000, interrupted: false };
+    hex_dump_reader(reader, &mut streamed, &settings).unwrap();
+    assert_eq!(String::from_utf8(streamed).unwrap(), format!("{dump}\n"));
+}
+
+#[test]
+fn preview_dumps_data_that_fits_in_full() {
+    let data = (0u8..64).collect::<Vec<u8>>();
+    let settings = HexOutSettings { skip_fill: Some(0), ..Default::default() };
+    let full = hex_out(&data, &HexOutSettings::default(), 0, 0, 0).unwrap();
+    assert_eq!(preview(&data, &settings, 4).unwrap(), full);
+    assert_eq!(preview(&data, &settings, 0).unwrap(), full);
+    assert_eq!(preview(b"", &settings, 4).unwrap(), "");
+}
+
+#[test]
+fn preview_samples_lines_within_borders() {
+    let data = vec![0x55u8; 1 << 20];
+    let settings = HexOutSettings {
+        groups_per_line: 8,
+        show_ascii: false,
+        borders: Borders::Ascii,
+        caption: Some("{len} bytes".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        preview(&data, &settings, 3).unwrap(),
+        "1048576 bytes\n\
+         +----------+--------------------------+\n\
+         | 00000000 | 55 55 55 55  55 55 55 55 |\n\
+         | … 131070 lines skipped …            |\n\
+         | 000ffff8 | 55 55 55 55  55 55 55 55 |\n\
+         +----------+--------------------------+"
+    );
+    assert_eq!(preview(&data, &settings, 1).unwrap().lines().count(), 4);
+}
+
+#[test]
+fn hex_dump_values_match_the_dump() {
+    let data = (1u8..=40).collect::<Vec<u8>>();
+    let settings = HexOutSettings {
+        group_size: 4,
+        groups_per_line: 4,
+        group_endianness: vec![Endianness::Big, Endianness::Pdp],
+        show_ascii: false,
+        show_centerline: false,
+        ..Default::default()
+    };
+    let values = hex_dump_values(&data, &settings, 6, 0, 0).unwrap();
+    let dump = hex_out(&data, &settings, 6, 0, 0).unwrap();
+    for (values, line) in values.iter().zip(dump.lines()) {
+        let group = |index: usize| line.get(10 + index * 9..18 + index * 9);
+        let groups = (0..4).map(|index| u128::from_str_radix(group(index)?, 16).ok()).collect::<Vec<_>>();
+        assert_eq!(*values, groups, "{line}");
+    }
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[1], [Some(0x11121314), Some(0x16151817), Some(0x1c1b1a19), Some(0x201f1e1d)]);
+}
+
+#[test]
+fn hex_dump_values_leave_out_skipped_lines() {
+    let mut data = vec![0u8; 12];
+    data.extend_from_slice(&[1, 0, 2, 0]);
+    let settings = HexOutSettings { group_size: 2, groups_per_line: 2, skip_fill: Some(0), ..Default::default() };
+    assert_eq!(hex_dump_values(&data, &settings, 0, 0, 0).unwrap(), [[Some(1), Some(2)]]);
+    let settings = HexOutSettings { endianness: Endianness::Auto, ..settings };
+    assert_eq!(hex_dump_values(&data, &settings, 0, 3, 1).unwrap(), [[Some(1), Some(2)]]);
+    let settings = HexOutSettings { group_size: 0, ..settings };
+    assert!(matches!(hex_dump_values(&data, &settings, 0, 0, 0), Err(HexOutError::InvalidGroupSize)));
+}
+
+#[test]
+fn ihex_matches_objcopy() {
+    let data = (0..40).collect::<Vec<u8>>();
+    // The data records of `objcopy -I binary -O ihex --change-addresses 0xfff0`
+    assert_eq!(
+        hex_dump_ihex(&data, 0xfff0, 16),
+        ":10FFF000000102030405060708090A0B0C0D0E0F89\n\
+         :020000040001F9\n\
+         :10000000101112131415161718191A1B1C1D1E1F78\n\
+         :080010002021222324252627CC\n\
+         :00000001FF\n"
+    );
+}
+
+#[test]
+fn ihex_wraps_around_and_clamps_record_length() {
+    assert_eq!(
+        hex_dump_ihex(b"\xaa\xbb\xcc", 0xffff_ffff, 0),
+        ":02000004FFFFFC\n:01FFFF00AA57\n:020000040000FA\n:01000000BB44\n:01000100CC32\n:00000001FF\n"
+    );
+    let lines = hex_dump_ihex(&[0; 300], 0, 1000);
+    assert_eq!(lines.lines().map(|line| line.len()).collect::<Vec<_>>(), [521, 101, 11]);
+    assert_eq!(hex_dump_ihex(b"", 0x1234_5678, 16), ":00000001FF\n");
+}
+
+#[test]
+fn byte_histogram_ends_the_dump() {
+    let settings = HexOutSettings { byte_histogram: ByteHistogram::Buckets16, ..Default::default() };
+    assert_eq!(
+        hex_out(b"The quick brown fox jumps over the lazy dog, 0123456789!", &settings, 0, 0, 0).unwrap(),
+        "00000000: 54 68 65 20 71 75 69 63  6b 20 62 72 6f 77 6e 20 |The quic k brown |\n\
+         00000010: 66 6f 78 20 6a 75 6d 70  73 20 6f 76 65 72 20 74 |fox jump s over t|\n\
+         00000020: 68 65 20 6c 61 7a 79 20  64 6f 67 2c 20 30 31 32 |he lazy  dog, 012|\n\
+         00000030: 33 34 35 36 37 38 39 21                          |3456789!         |\n\
+         00   ▅▄ ▁█▅         ff"
+    );
+    assert_eq!(hex_out(b"", &settings, 0, 0, 0).unwrap(), "");
+    // Only the lines of the window count, within the borders
+    let settings = HexOutSettings {
+        byte_histogram: ByteHistogram::Buckets32,
+        borders: Borders::Ascii,
+        show_ascii: false,
+        ..Default::default()
+    };
+    assert_eq!(
+        hex_out(&(0u8..=255).collect::<Vec<u8>>(), &settings, 0, 2, 1).unwrap(),
+        "+----------+--------------------------------------------------+\n\
+         | 00000020 | 20 21 22 23 24 25 26 27  28 29 2a 2b 2c 2d 2e 2f |\n\
+         | 00     ██                           ff                      |\n\
+         +----------+--------------------------------------------------+"
+    );
+}
+
+#[test]
+fn byte_histogram_counts_lines_across_chunks_and_pauses() {
+    let mut data = vec![0u8; 200_000];
+    data.extend_from_slice(b"end");
+    let settings = HexOutSettings { byte_histogram: ByteHistogram::Buckets16, ..HexOutSettings::hexdump_c() };
+    // The squeezed lines count too
+    let dump = "00000000  00 00 00 00 00 00 00 00  00 00 00 00 00 00 00 00  |................|\n\
+                *\n\
+                00030d40  65 6e 64                                          |end|\n\
+                00030d43\n\
+                00 █     ▁          ff";
+    assert_eq!(hex_out(&data, &settings, 0, 0, 0).unwrap(), dump);
+    let mut streamed = Vec::new();
+    let reader = TrickleReader { inner: std::io::Cursor::new(data.clone()), step: 7000, interrupted: false };
+    hex_dump_reader(reader, &mut streamed, &settings).unwrap();
+    assert_eq!(String::from_utf8(streamed).unwrap(), format!("{dump}\n"));
+    let mut lines = HexOutLines::new(&data, &settings, 0, 0, 0).unwrap();
+    let mut resumed = Vec::new();
+    while let Some(line) = lines.next_line() {
+        resumed.push(line.to_string());
+        lines = HexOutLines::resume(&data, &settings, &lines.pause()).unwrap();
+    }
+    assert_eq!(resumed.join("\n"), dump);
+}
@@ -0,0 +1,62 @@
+use crate::{push_ascii, push_char, HEX_DIGITS_LOWER, HEX_DIGITS_UPPER};
+
+/// How bytes are shown in the ASCII column (and with `inline_chars`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TextMode {
+    /// Printable characters as is, other bytes as `.`.
+    #[default]
+    Ascii,
+    /// Like `Ascii`, but with NUL, tab, line feed and carriage return shown as `\0`, `\t`, `\n` and `\r`, which makes
+    /// dumps of text protocols easier to read.  Every byte takes two characters, the others being followed by a space.
+    Escaped,
+    /// Printable characters as is, other bytes as their hex digits in angle brackets (e.g. `⟨0a⟩`), in lowercase or
+    /// uppercase, so the column reads densely as text without losing any byte.  Every byte takes four characters, the
+    /// printable ones being followed by three spaces.
+    Hexified {
+        /// Whether the hex digits are uppercase.
+        uppercase: bool,
+    },
+}
+
+impl TextMode {
+    /// Number of characters taken by each byte.
+    pub(crate) fn cell_width(self) -> usize {
+        match self {
+            TextMode::Ascii => 1,
+            TextMode::Escaped => 2,
+            TextMode::Hexified { .. } => 4,
+        }
+    }
+
+    /// Append the representation of `byte` to `buffer`, escaping it for HTML if requested.
+    pub(crate) fn push_byte(self, buffer: &mut String, byte: u8, escape_html: bool) {
+        match (self, byte) {
+            (TextMode::Ascii, _) => push_ascii(buffer, byte, escape_html),
+            (TextMode::Escaped, b'\0') => buffer.push_str("\\0"),
+            (TextMode::Escaped, b'\t') => buffer.push_str("\\t"),
+            (TextMode::Escaped, b'\n') => buffer.push_str("\\n"),
+            (TextMode::Escaped, b'\r') => buffer.push_str("\\r"),
+            (TextMode::Escaped, _) => {
+                push_ascii(buffer, byte, escape_html);
+                buffer.push(' ');
+            }
+            (TextMode::Hexified { .. }, 0x20..0x7f) => {
+                push_ascii(buffer, byte, escape_html);
+                buffer.push_str("   ");
+            }
+            (TextMode::Hexified { uppercase }, _) => {
+                let digits = if uppercase { HEX_DIGITS_UPPER } else { HEX_DIGITS_LOWER };
+                buffer.push('⟨');
+                buffer.push(digits[usize::from(byte >> 4)] as char);
+                buffer.push(digits[usize::from(byte & 0xf)] as char);
+                buffer.push('⟩');
+            }
+        }
+    }
+
+    /// Append `placeholder` in place of a byte before the offset or past the end of the data.
+    pub(crate) fn push_placeholder(self, buffer: &mut String, placeholder: char, escape_html: bool) {
+        push_char(buffer, placeholder, escape_html);
+        buffer.extend(std::iter::repeat_n(' ', self.cell_width() - 1));
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use crate::{HexOutSettings, Style, StyleTable};
+
+/// Color themes by name: the built-in ones and those loaded from user theme files.
+///
+/// Each theme is a [`StyleTable`] holding the style of every semantic class (addresses, cursor, selection,
+/// highlights, flags, errors and stripes).  With the `serde` feature, themes serialize as a map from names to style
+/// tables, each a map from class names to `prefix`/`suffix` pairs, and classes left out of a theme file are
+/// unstyled.  Themes loaded from a file are merged over the built-in ones with [`extend`](Self::extend), so
+/// applications can offer both by name.
+/// ```rust
+/// use hexout::{hex_out, HexOutSettings, Style, StyleTable, Themes};
+/// let mut themes = Themes::builtin();
+/// themes.insert("mine", StyleTable { address: Some(Style::new("<", ">")), ..Default::default() });
+/// let settings = HexOutSettings::default().with_theme(themes.get("mine").unwrap());
+/// assert_eq!(hex_out(b"theme", &settings, 0, 0, 0).unwrap().split_at(10).0, "<00000000>");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+pub struct Themes {
+    themes: BTreeMap<String, StyleTable>,
+}
+
+impl Themes {
+    /// The built-in themes: `plain` without any styles, and `dark` and `light` with ANSI colors suiting terminals
+    /// with a dark or light background.
+    pub fn builtin() -> Self {
+        let mut themes = Self::default();
+        themes.insert("plain", StyleTable::default());
+        themes.insert(
+            "dark",
+            StyleTable {
+                address: Some(Style::ansi("36")),
+                cursor: Some(Style::reverse()),
+                selection: Some(Style::ansi("44")),
+                highlight: Some(Style::ansi("30;43")),
+                flag: Some(Style::ansi("1;31")),
+                error: Some(Style::ansi("31")),
+                stripe: Some(Style::ansi("48;5;236")),
+                guide: Some(Style::ansi("2")),
+            },
+        );
+        themes.insert(
+            "light",
+            StyleTable {
+                address: Some(Style::ansi("34")),
+                cursor: Some(Style::reverse()),
+                selection: Some(Style::ansi("106")),
+                highlight: Some(Style::ansi("103")),
+                flag: Some(Style::ansi("1;31")),
+                error: Some(Style::ansi("31")),
+                stripe: Some(Style::ansi("48;5;254")),
+                guide: Some(Style::ansi("2")),
+            },
+        );
+        themes
+    }
+
+    /// The theme called `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&StyleTable> {
+        self.themes.get(name)
+    }
+
+    /// Add the theme `theme` called `name`, returning the theme it replaces.
+    pub fn insert(&mut self, name: impl Into<String>, theme: StyleTable) -> Option<StyleTable> {
+        self.themes.insert(name.into(), theme)
+    }
+
+    /// Add all themes of `other`, replacing those of the same name, e.g. user themes over the built-in ones.
+    pub fn extend(&mut self, other: Themes) {
+        self.themes.extend(other.themes);
+    }
+
+    /// The names of the themes in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.themes.keys().map(String::as_str)
+    }
+}
+
+impl HexOutSettings {
+    /// A copy of these settings with the styles of `theme`, replacing all of their own styles.
+    pub fn with_theme(&self, theme: &StyleTable) -> HexOutSettings {
+        HexOutSettings {
+            address_style: theme.address.clone(),
+            cursor_style: theme.cursor.clone(),
+            selection_style: theme.selection.clone(),
+            highlight_style: theme.highlight.clone(),
+            flag_style: theme.flag.clone(),
+            error_style: theme.error.clone(),
+            stripe_style: theme.stripe.clone(),
+            guide_style: theme.guide.clone(),
+            ..self.clone()
+        }
+    }
+}
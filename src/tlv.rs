@@ -0,0 +1,110 @@
+use crate::{hex_out, validate, HexOutError, HexOutSettings};
+
+/// The header of a tag-length-value entry as parsed by the callback of [`hex_out_tlv`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tlv {
+    /// The decoded tag, e.g. `SEQUENCE` or `9f02`.
+    pub tag: String,
+    /// Number of bytes taken by the tag and length fields.
+    pub header_length: usize,
+    /// Number of bytes of the value following the header.
+    pub value_length: usize,
+    /// Whether the value is made of further entries, which are walked in turn instead of dumping the value.
+    pub nested: bool,
+}
+
+impl Tlv {
+    /// Create an entry whose value is dumped.
+    pub fn new(tag: impl Into<String>, header_length: usize, value_length: usize) -> Self {
+        Self {
+            tag: tag.into(),
+            header_length,
+            value_length,
+            nested: false,
+        }
+    }
+
+    /// Mark the value as made of further entries.
+    pub fn nested(mut self) -> Self {
+        self.nested = true;
+        self
+    }
+}
+
+/// Generate a dump of tag-length-value structures, such as BER/DER encodings, EMV records or protocol options.
+///
+/// `parse` receives the bytes from the start of an entry to the end of the enclosing value (or of the data) and
+/// returns its header.  Each entry is captioned with its tag and length, and its value is dumped beneath it with the
+/// displayed addresses of its bytes (starting a line of its own regardless of `align_address`), or its entries are
+/// walked in turn, each level indented by two more spaces.
+/// Values extending past the enclosing value are cut off at its end.  Bytes `parse` returns `None` for, or an entry
+/// without a header, are dumped under an `unparsed` caption.
+///
+/// Walking starts at `offset`; all other parameters are the same as for [`hex_out`].
+/// ```rust
+/// use hexout::{hex_out_tlv, HexOutSettings, Tlv};
+/// let settings = HexOutSettings { show_ascii: false, address_width: 4, ..Default::default() };
+/// let parse = |bytes: &[u8]| {
+///     let tlv = Tlv::new(format!("{:02x}", bytes[0]), 2, *bytes.get(1)? as usize);
+///     Some(if bytes[0] & 0x20 != 0 { tlv.nested() } else { tlv })
+/// };
+/// let dump = hex_out_tlv(&[0x30, 0x03, 0x02, 0x01, 0x05], parse, &settings, 0).unwrap();
+/// assert_eq!(dump, "30 (length 3)\n  02 (length 1)\n    0004: 05");
+/// ```
+pub fn hex_out_tlv(
+    data: &[u8],
+    parse: impl Fn(&[u8]) -> Option<Tlv>,
+    settings: &HexOutSettings,
+    offset: usize,
+) -> Result<String, HexOutError> {
+    validate(settings, offset)?;
+    let settings = HexOutSettings { align_address: false, ..settings.clone() };
+    let mut result = String::new();
+    // Ends of the enclosing values, innermost last
+    let mut ends = vec![data.len()];
+    let mut position = offset.min(data.len());
+    loop {
+        while ends.last() == Some(&position) {
+            ends.pop();
+        }
+        let Some(&end) = ends.last() else {
+            break;
+        };
+        let depth = ends.len() - 1;
+        let bytes = &data[position..end];
+        let tlv = parse(bytes).filter(|tlv| tlv.header_length > 0 && tlv.header_length <= bytes.len());
+        let (caption, value_start, value_end) = match &tlv {
+            Some(tlv) => {
+                let value_start = position + tlv.header_length;
+                let value_end = value_start.saturating_add(tlv.value_length).min(end);
+                (format!("{} (length {})", tlv.tag, tlv.value_length), value_start, value_end)
+            }
+            None => {
+                let count = bytes.len();
+                (format!("unparsed ({count} byte{})", if count == 1 { "" } else { "s" }), position, end)
+            }
+        };
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        push_indented(&mut result, &caption, depth);
+        if tlv.as_ref().is_some_and(|tlv| tlv.nested) && value_start < value_end {
+            ends.push(value_end);
+            position = value_start;
+            continue;
+        }
+        let dump = hex_out(&data[..value_end], &settings, value_start, 0, 0)?;
+        for line in dump.lines() {
+            result.push('\n');
+            push_indented(&mut result, line, depth + 1);
+        }
+        position = value_end;
+    }
+    Ok(result)
+}
+
+/// Append `text` to `result`, indented by two spaces per level of `depth`.
+fn push_indented(result: &mut String, text: &str, depth: usize) {
+    result.extend(std::iter::repeat_n(' ', depth * 2));
+    result.push_str(text);
+}
@@ -0,0 +1,171 @@
+use crate::{format_version, hex_out, parse_hex_str, validate, Endianness, HexOutError, HexOutSettings};
+
+/// Prefix of the header line written by [`xxd_export`].
+const HEADER: &str = "# hexout-xxd";
+
+/// Export `data` as text in the format of `xxd`, with a header line recording the presentation settings, so
+/// [`undump`] can restore both the bytes and the settings they were meant to be shown with.
+///
+/// The lines are those of `xxd -c <bytes per line> -g <group size>`: offsets from 0, groups in memory order, and the
+/// ASCII column after two spaces, so `xxd -r dump.txt data.bin` restores the bytes as well.  (Writing to a pipe instead
+/// of a file, `xxd -r` takes the hex digits of the header for data, so the header has to be left out, e.g. with
+/// `tail -n +2`.)  The header line starts with `#` and lists `address_origin`, `address_width`, `group_size`,
+/// `groups_per_line`, `endianness`, `uppercase`, `show_ascii` and `show_centerline` as `key=value` pairs, after the
+/// [`format_version`].  Settings that don't fit a single line, such as styles and callbacks, are not recorded.
+///
+/// Invalid settings are rejected like by [`hex_out`].
+/// ```rust
+/// use hexout::{undump, xxd_export, HexOutSettings};
+/// let settings = HexOutSettings { group_size: 4, groups_per_line: 2, address_origin: 0x8000, ..Default::default() };
+/// let text = xxd_export(b"reversible", &settings).unwrap();
+/// assert_eq!(
+///     text,
+///     "# hexout-xxd version=1.0.0 address_origin=0x8000 address_width=8 group_size=4 groups_per_line=2 \
+///      endianness=little uppercase=false show_ascii=true show_centerline=true\n\
+///      00000000: 72657665 72736962  reversib\n\
+///      00000008: 6c65               le\n"
+/// );
+/// let (data, restored) = undump(&text).unwrap();
+/// assert_eq!(data, b"reversible");
+/// assert_eq!((restored.group_size, restored.address_origin), (4, 0x8000));
+/// ```
+pub fn xxd_export(data: &[u8], settings: &HexOutSettings) -> Result<String, HexOutError> {
+    validate(settings, 0)?;
+    let mut text = format!(
+        "{HEADER} version={} address_origin={:#x} address_width={} group_size={} groups_per_line={} endianness={} \
+         uppercase={} show_ascii={} show_centerline={}\n",
+        format_version(),
+        settings.address_origin,
+        settings.address_width,
+        settings.group_size,
+        settings.groups_per_line,
+        endianness_name(settings.endianness),
+        settings.uppercase,
+        settings.show_ascii,
+        settings.show_centerline,
+    );
+    // Offsets take at least the 8 digits of xxd
+    let digits = (usize::BITS - data.len().leading_zeros()).div_ceil(4) as usize;
+    let xxd = HexOutSettings {
+        address_width: digits.max(8),
+        group_size: settings.group_size,
+        groups_per_line: settings.groups_per_line,
+        uppercase: settings.uppercase,
+        ..HexOutSettings::xxd()
+    };
+    if !data.is_empty() {
+        text.push_str(&hex_out(data, &xxd, 0, 0, 0)?);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// Number of bytes on each line of [`xxd_include`], as with `xxd -i`.
+const INCLUDE_BYTES_PER_LINE: usize = 12;
+
+/// Write `data` as a C array in the format of `xxd -i`, to embed it in a C or C++ build without running `xxd`.
+///
+/// With a `name`, the output is that of `xxd -i <name>`: an `unsigned char` array and an `unsigned int` length
+/// constant named after it, with each byte that is not an ASCII letter or digit replaced by `_` and a `__` prefix
+/// before a leading digit.  Without one, it is that of `xxd -i` reading standard input, only the lines of bytes.
+/// Each line holds up to 12 bytes as `0x` hex literals.
+/// ```rust
+/// use hexout::xxd_include;
+/// assert_eq!(
+///     xxd_include(b"\x7fELF", Some("boot.bin")),
+///     "unsigned char boot_bin[] = {\n  0x7f, 0x45, 0x4c, 0x46\n};\nunsigned int boot_bin_len = 4;\n"
+/// );
+/// assert_eq!(xxd_include(b"\x7fELF", None), "  0x7f, 0x45, 0x4c, 0x46\n");
+/// ```
+pub fn xxd_include(data: &[u8], name: Option<&str>) -> String {
+    let mut text = String::new();
+    let identifier = name.map(|name| {
+        let prefix = if name.starts_with(|c: char| c.is_ascii_digit()) { "__" } else { "" };
+        let mangled = name.bytes().map(|byte| if byte.is_ascii_alphanumeric() { byte as char } else { '_' });
+        prefix.chars().chain(mangled).collect::<String>()
+    });
+    if let Some(identifier) = &identifier {
+        text.push_str(&format!("unsigned char {identifier}[] = {{\n"));
+    }
+    for (index, line) in data.chunks(INCLUDE_BYTES_PER_LINE).enumerate() {
+        let bytes = line.iter().map(|byte| format!("0x{byte:02x}")).collect::<Vec<_>>();
+        text.push_str("  ");
+        text.push_str(&bytes.join(", "));
+        // xxd separates the lines with commas, and ends the last one without
+        let last = (index + 1) * INCLUDE_BYTES_PER_LINE >= data.len();
+        text.push_str(if last { "\n" } else { ",\n" });
+    }
+    if let Some(identifier) = &identifier {
+        text.push_str(&format!("}};\nunsigned int {identifier}_len = {};\n", data.len()));
+    }
+    text
+}
+
+/// Restore the bytes of a dump in the format of `xxd`, and the settings recorded by [`xxd_export`] in its header.
+///
+/// Each line holds the bytes at its address as hex digits up to the ASCII column, which follows after two spaces;
+/// bytes skipped by the addresses are zero, as with `xxd -r`.  Empty lines and lines starting with `#` are skipped,
+/// except for the header, whose settings are applied to the defaults of [`HexOutSettings`] (with the default settings
+/// returned for plain `xxd` output).  Unknown keys of the header are ignored.
+///
+/// Lines that are neither of these, and header settings with invalid values, are rejected with
+/// [`HexOutError::InvalidDumpLine`].  Addresses past the range of a `usize` are rejected with
+/// [`HexOutError::RangeOverflow`].
+pub fn undump(text: &str) -> Result<(Vec<u8>, HexOutSettings), HexOutError> {
+    let mut data = Vec::new();
+    let mut settings = HexOutSettings::default();
+    for (index, line) in text.lines().enumerate() {
+        let invalid = HexOutError::InvalidDumpLine { line: index + 1 };
+        if let Some(header) = line.strip_prefix(HEADER) {
+            for (key, value) in header.split_whitespace().filter_map(|pair| pair.split_once('=')) {
+                apply_setting(&mut settings, key, value).ok_or_else(|| invalid.clone())?;
+            }
+            continue;
+        }
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (address, rest) = line.split_once(':').ok_or_else(|| invalid.clone())?;
+        let address = u128::from_str_radix(address.trim(), 16).map_err(|_| invalid.clone())?;
+        let address = usize::try_from(address).map_err(|_| HexOutError::RangeOverflow)?;
+        // The hex area ends at the two spaces before the ASCII column
+        let rest = rest.strip_prefix(' ').unwrap_or(rest);
+        let hex = rest.split("  ").next().unwrap_or_default();
+        let bytes = parse_hex_str(hex).map_err(|_| invalid)?;
+        let end = address.checked_add(bytes.len()).ok_or(HexOutError::RangeOverflow)?;
+        if data.len() < end {
+            data.resize(end, 0);
+        }
+        data[address..end].copy_from_slice(&bytes);
+    }
+    Ok((data, settings))
+}
+
+fn endianness_name(endianness: Endianness) -> &'static str {
+    match endianness {
+        Endianness::Little => "little",
+        Endianness::Big => "big",
+        Endianness::Pdp => "pdp",
+        Endianness::Auto => "auto",
+    }
+}
+
+/// Set the setting `key` of a header to `value`, or return `None` if the value is invalid.
+fn apply_setting(settings: &mut HexOutSettings, key: &str, value: &str) -> Option<()> {
+    match key {
+        "address_origin" => settings.address_origin = u128::from_str_radix(value.strip_prefix("0x")?, 16).ok()?,
+        "address_width" => settings.address_width = value.parse().ok()?,
+        "group_size" => settings.group_size = value.parse().ok()?,
+        "groups_per_line" => settings.groups_per_line = value.parse().ok()?,
+        "endianness" => {
+            settings.endianness = [Endianness::Little, Endianness::Big, Endianness::Pdp, Endianness::Auto]
+                .into_iter()
+                .find(|endianness| endianness_name(*endianness) == value)?;
+        }
+        "uppercase" => settings.uppercase = value.parse().ok()?,
+        "show_ascii" => settings.show_ascii = value.parse().ok()?,
+        "show_centerline" => settings.show_centerline = value.parse().ok()?,
+        _ => {}
+    }
+    Some(())
+}